@@ -5,6 +5,19 @@ mod m20251113_000002_add_identity_tables;
 mod m20251113_000003_add_stealth_outputs;
 mod m20251113_000004_add_governance_tables;
 mod m20251113_000005_add_faucet_table;
+mod m20261018_000001_normalize_proposal_states;
+mod m20261018_000002_add_voting_power_snapshots;
+mod m20261018_000003_add_delegation_events;
+mod m20261018_000004_add_governance_notifications;
+mod m20261018_000005_add_governance_chain_refs;
+mod m20261018_000006_add_display_name_trigram_index;
+mod m20261018_000007_add_display_name_skeleton;
+mod m20261018_000008_add_social_connections;
+mod m20261018_000009_add_identity_profile_history;
+mod m20261018_000010_add_stealth_scan_registrations;
+mod m20261018_000011_add_snapshot_stake;
+mod m20261018_000012_backfill_governance_chain_refs;
+mod m20261018_000013_reset_display_name_keys;
 
 pub struct Migrator;
 
@@ -17,6 +30,19 @@ impl MigratorTrait for Migrator {
             Box::new(m20251113_000003_add_stealth_outputs::Migration),
             Box::new(m20251113_000004_add_governance_tables::Migration),
             Box::new(m20251113_000005_add_faucet_table::Migration),
+            Box::new(m20261018_000001_normalize_proposal_states::Migration),
+            Box::new(m20261018_000002_add_voting_power_snapshots::Migration),
+            Box::new(m20261018_000003_add_delegation_events::Migration),
+            Box::new(m20261018_000004_add_governance_notifications::Migration),
+            Box::new(m20261018_000005_add_governance_chain_refs::Migration),
+            Box::new(m20261018_000006_add_display_name_trigram_index::Migration),
+            Box::new(m20261018_000007_add_display_name_skeleton::Migration),
+            Box::new(m20261018_000008_add_social_connections::Migration),
+            Box::new(m20261018_000009_add_identity_profile_history::Migration),
+            Box::new(m20261018_000010_add_stealth_scan_registrations::Migration),
+            Box::new(m20261018_000011_add_snapshot_stake::Migration),
+            Box::new(m20261018_000012_backfill_governance_chain_refs::Migration),
+            Box::new(m20261018_000013_reset_display_name_keys::Migration),
         ]
     }
 }
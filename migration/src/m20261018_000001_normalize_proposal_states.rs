@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Proposal states are stored as lowercase lifecycle names
+        manager
+            .get_connection()
+            .execute_unprepared("UPDATE governance_proposals SET state = LOWER(state)")
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GovernanceProposals::Table)
+                    .modify_column(
+                        ColumnDef::new(GovernanceProposals::State)
+                            .string_len(32)
+                            .not_null()
+                            .default("pending"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GovernanceProposals::Table)
+                    .modify_column(
+                        ColumnDef::new(GovernanceProposals::State)
+                            .string_len(32)
+                            .not_null()
+                            .default("Pending"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum GovernanceProposals {
+    Table,
+    State,
+}
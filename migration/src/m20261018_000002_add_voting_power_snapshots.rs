@@ -0,0 +1,119 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_query::Expr;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Per-account voting power captured at the block a proposal opened
+        manager
+            .create_table(
+                Table::create()
+                    .table(VotingPowerSnapshots::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(VotingPowerSnapshots::BlockNumber)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VotingPowerSnapshots::Address)
+                            .string_len(128)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VotingPowerSnapshots::DelegatedIn)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VotingPowerSnapshots::DelegatedOut)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VotingPowerSnapshots::VotingPower)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VotingPowerSnapshots::CapturedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name("pk_voting_power_snapshots")
+                            .col(VotingPowerSnapshots::BlockNumber)
+                            .col(VotingPowerSnapshots::Address),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_voting_power_snapshots_address_block")
+                    .table(VotingPowerSnapshots::Table)
+                    .col(VotingPowerSnapshots::Address)
+                    .col(VotingPowerSnapshots::BlockNumber)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GovernanceProposals::Table)
+                    .add_column(
+                        ColumnDef::new(GovernanceProposals::SnapshotBlock)
+                            .big_integer()
+                            .null(),
+                    )
+                    .add_column(
+                        ColumnDef::new(GovernanceProposals::SnapshotEligiblePower)
+                            .big_integer()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GovernanceProposals::Table)
+                    .drop_column(GovernanceProposals::SnapshotEligiblePower)
+                    .drop_column(GovernanceProposals::SnapshotBlock)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(VotingPowerSnapshots::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum VotingPowerSnapshots {
+    Table,
+    BlockNumber,
+    Address,
+    DelegatedIn,
+    DelegatedOut,
+    VotingPower,
+    CapturedAt,
+}
+
+#[derive(DeriveIden)]
+enum GovernanceProposals {
+    Table,
+    SnapshotBlock,
+    SnapshotEligiblePower,
+}
@@ -0,0 +1,125 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_query::Expr;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Append-only delegation ledger; governance_delegations is derived from it
+        manager
+            .create_table(
+                Table::create()
+                    .table(GovernanceDelegationEvents::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(GovernanceDelegationEvents::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(GovernanceDelegationEvents::Delegator)
+                            .string_len(128)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GovernanceDelegationEvents::Delegatee)
+                            .string_len(128)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GovernanceDelegationEvents::PreviousDelegatee)
+                            .string_len(128)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(GovernanceDelegationEvents::EventType)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GovernanceDelegationEvents::Amount)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GovernanceDelegationEvents::BlockNumber)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GovernanceDelegationEvents::TxId)
+                            .string_len(128)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GovernanceDelegationEvents::EventIndex)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GovernanceDelegationEvents::OccurredAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GovernanceDelegationEvents::IndexedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_delegation_events_tx")
+                            .unique()
+                            .col(GovernanceDelegationEvents::TxId)
+                            .col(GovernanceDelegationEvents::EventIndex),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_delegation_events_delegator")
+                            .col(GovernanceDelegationEvents::Delegator)
+                            .col(GovernanceDelegationEvents::BlockNumber),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_delegation_events_delegatee")
+                            .col(GovernanceDelegationEvents::Delegatee)
+                            .col(GovernanceDelegationEvents::BlockNumber),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(GovernanceDelegationEvents::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum GovernanceDelegationEvents {
+    Table,
+    Id,
+    Delegator,
+    Delegatee,
+    PreviousDelegatee,
+    EventType,
+    Amount,
+    BlockNumber,
+    TxId,
+    EventIndex,
+    OccurredAt,
+    IndexedAt,
+}
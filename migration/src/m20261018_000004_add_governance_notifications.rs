@@ -0,0 +1,89 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_query::Expr;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // One row per (proposal, event kind) so restarts never re-announce an event
+        manager
+            .create_table(
+                Table::create()
+                    .table(GovernanceNotifications::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(GovernanceNotifications::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(GovernanceNotifications::ProposalId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GovernanceNotifications::Kind)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GovernanceNotifications::Proposer)
+                            .string_len(128)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GovernanceNotifications::State)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GovernanceNotifications::BlockNumber)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GovernanceNotifications::EmittedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_governance_notifications_proposal_kind")
+                            .unique()
+                            .col(GovernanceNotifications::ProposalId)
+                            .col(GovernanceNotifications::Kind),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(GovernanceNotifications::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum GovernanceNotifications {
+    Table,
+    Id,
+    ProposalId,
+    Kind,
+    Proposer,
+    State,
+    BlockNumber,
+    EmittedAt,
+}
@@ -0,0 +1,77 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Chain references let exported governance records be checked against blocks
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GovernanceVotes::Table)
+                    .add_column(
+                        ColumnDef::new(GovernanceVotes::BlockNumber)
+                            .big_integer()
+                            .null(),
+                    )
+                    .add_column(ColumnDef::new(GovernanceVotes::TxId).string_len(128).null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GovernanceProposals::Table)
+                    .add_column(
+                        ColumnDef::new(GovernanceProposals::BlockNumber)
+                            .big_integer()
+                            .null(),
+                    )
+                    .add_column(
+                        ColumnDef::new(GovernanceProposals::TxId)
+                            .string_len(128)
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GovernanceProposals::Table)
+                    .drop_column(GovernanceProposals::TxId)
+                    .drop_column(GovernanceProposals::BlockNumber)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GovernanceVotes::Table)
+                    .drop_column(GovernanceVotes::TxId)
+                    .drop_column(GovernanceVotes::BlockNumber)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum GovernanceVotes {
+    Table,
+    BlockNumber,
+    TxId,
+}
+
+#[derive(DeriveIden)]
+enum GovernanceProposals {
+    Table,
+    BlockNumber,
+    TxId,
+}
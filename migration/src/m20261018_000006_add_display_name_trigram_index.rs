@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let connection = manager.get_connection();
+        connection
+            .execute_unprepared("CREATE EXTENSION IF NOT EXISTS pg_trgm")
+            .await?;
+
+        // Search keys are now NFKC case folded. SQL cannot reproduce that
+        // normalization, so the keys are rebuilt by the indexer instead; see
+        // m20261018_000013_reset_display_name_keys.
+        connection
+            .execute_unprepared(
+                "CREATE INDEX IF NOT EXISTS idx_identity_profiles_display_name_trgm \
+                 ON identity_profiles USING GIN (display_name_search gin_trgm_ops)",
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_identity_profiles_display_name_trgm")
+                    .table(IdentityProfiles::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum IdentityProfiles {
+    Table,
+}
@@ -0,0 +1,59 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Existing rows are backfilled by the indexer on startup; the
+        // skeleton mapping lives in the application, not in SQL.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(IdentityProfiles::Table)
+                    .add_column(
+                        ColumnDef::new(IdentityProfiles::DisplayNameSkeleton)
+                            .string_len(512)
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_identity_profiles_display_name_skeleton")
+                    .table(IdentityProfiles::Table)
+                    .col(IdentityProfiles::DisplayNameSkeleton)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_identity_profiles_display_name_skeleton")
+                    .table(IdentityProfiles::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(IdentityProfiles::Table)
+                    .drop_column(IdentityProfiles::DisplayNameSkeleton)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum IdentityProfiles {
+    Table,
+    DisplayNameSkeleton,
+}
@@ -0,0 +1,72 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Current follow edges between identities, maintained from chain
+        // follow/unfollow events. No foreign keys: an edge may be indexed
+        // before the registry sync reaches either identity.
+        manager
+            .create_table(
+                Table::create()
+                    .table(SocialConnections::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SocialConnections::FollowerId)
+                            .binary_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SocialConnections::FollowingId)
+                            .binary_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SocialConnections::CreatedAt)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SocialConnections::LastSyncedBlock)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(SocialConnections::FollowerId)
+                            .col(SocialConnections::FollowingId),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_social_connections_following")
+                    .table(SocialConnections::Table)
+                    .col(SocialConnections::FollowingId)
+                    .col(SocialConnections::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SocialConnections::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SocialConnections {
+    Table,
+    FollowerId,
+    FollowingId,
+    CreatedAt,
+    LastSyncedBlock,
+}
@@ -0,0 +1,121 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Append-only: one row per synced profile version. Rows are never
+        // updated or deleted, including when the profile or its links are.
+        manager
+            .create_table(
+                Table::create()
+                    .table(IdentityProfileHistory::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(IdentityProfileHistory::IdentityId)
+                            .binary_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(IdentityProfileHistory::ProfileVersion)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(IdentityProfileHistory::LastSyncedBlock)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(IdentityProfileHistory::DisplayName)
+                            .string_len(64)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(IdentityProfileHistory::AvatarHash)
+                            .binary_len(32)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(IdentityProfileHistory::Bio)
+                            .string_len(512)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(IdentityProfileHistory::StatsVisibility)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(IdentityProfileHistory::WalletsAdded)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(IdentityProfileHistory::WalletsRemoved)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(IdentityProfileHistory::UpdatedAt)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(IdentityProfileHistory::IdentityId)
+                            .col(IdentityProfileHistory::ProfileVersion)
+                            .col(IdentityProfileHistory::LastSyncedBlock),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Seed every existing profile with its current state so the first
+        // indexed change has something to diff against.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "INSERT INTO identity_profile_history \
+                 (identity_id, profile_version, last_synced_block, display_name, avatar_hash, \
+                  bio, stats_visibility, wallets_added, wallets_removed, updated_at) \
+                 SELECT p.identity_id, p.profile_version, p.last_synced_block, p.display_name, \
+                        p.avatar_hash, p.bio, p.stats_visibility, \
+                        COALESCE((SELECT jsonb_agg(w.wallet_address ORDER BY w.wallet_address) \
+                                  FROM wallet_links w WHERE w.identity_id = p.identity_id), \
+                                 '[]'::jsonb), \
+                        '[]'::jsonb, p.updated_at \
+                 FROM identity_profiles p \
+                 ON CONFLICT DO NOTHING",
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(IdentityProfileHistory::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum IdentityProfileHistory {
+    Table,
+    IdentityId,
+    ProfileVersion,
+    LastSyncedBlock,
+    DisplayName,
+    AvatarHash,
+    Bio,
+    StatsVisibility,
+    WalletsAdded,
+    WalletsRemoved,
+    UpdatedAt,
+}
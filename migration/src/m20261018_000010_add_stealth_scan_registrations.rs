@@ -0,0 +1,178 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_query::Expr;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // View keys registered for background scanning. Key material is
+        // sealed under a server key; only a hash of the access token is kept.
+        manager
+            .create_table(
+                Table::create()
+                    .table(StealthScanRegistrations::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(StealthScanRegistrations::RegistrationId)
+                            .string_len(32)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(StealthScanRegistrations::TokenHash)
+                            .binary_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StealthScanRegistrations::KeyCiphertext)
+                            .binary()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StealthScanRegistrations::KeyNonce)
+                            .binary_len(12)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StealthScanRegistrations::NextBlock)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StealthScanRegistrations::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(StealthScanRegistrations::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_stealth_scan_registrations_next_block")
+                    .table(StealthScanRegistrations::Table)
+                    .col(StealthScanRegistrations::NextBlock)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Sealed scan results. `id` orders an inbox and is its cursor;
+        // `output_tag` is a keyed hash of the output so rescans never insert
+        // it twice without storing which output it is.
+        manager
+            .create_table(
+                Table::create()
+                    .table(StealthScanInbox::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(StealthScanInbox::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(StealthScanInbox::RegistrationId)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StealthScanInbox::OutputTag)
+                            .binary_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StealthScanInbox::Ciphertext)
+                            .binary()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StealthScanInbox::Nonce)
+                            .binary_len(12)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StealthScanInbox::DetectedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_stealth_scan_inbox_registration")
+                            .from(StealthScanInbox::Table, StealthScanInbox::RegistrationId)
+                            .to(
+                                StealthScanRegistrations::Table,
+                                StealthScanRegistrations::RegistrationId,
+                            )
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_stealth_scan_inbox_output")
+                            .unique()
+                            .col(StealthScanInbox::RegistrationId)
+                            .col(StealthScanInbox::OutputTag),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_stealth_scan_inbox_registration_id")
+                    .table(StealthScanInbox::Table)
+                    .col(StealthScanInbox::RegistrationId)
+                    .col(StealthScanInbox::Id)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(StealthScanInbox::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(StealthScanRegistrations::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StealthScanRegistrations {
+    Table,
+    RegistrationId,
+    TokenHash,
+    KeyCiphertext,
+    KeyNonce,
+    NextBlock,
+    CreatedAt,
+    ExpiresAt,
+}
+
+#[derive(DeriveIden)]
+enum StealthScanInbox {
+    Table,
+    Id,
+    RegistrationId,
+    OutputTag,
+    Ciphertext,
+    Nonce,
+    DetectedAt,
+}
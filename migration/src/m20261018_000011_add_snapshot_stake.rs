@@ -0,0 +1,54 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Snapshots now include bonded stake and are rebuilt from history
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(VotingPowerSnapshots::Table)
+                    .add_column(
+                        ColumnDef::new(VotingPowerSnapshots::Staked)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Proposals still voting were frozen from live delegations; let the
+        // lifecycle recapture them at their start height.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE governance_proposals \
+                 SET snapshot_block = NULL, snapshot_eligible_power = NULL \
+                 WHERE state IN ('pending', 'active')",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(VotingPowerSnapshots::Table)
+                    .drop_column(VotingPowerSnapshots::Staked)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum VotingPowerSnapshots {
+    Table,
+    Staked,
+}
@@ -0,0 +1,29 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Proposals and votes stored before the governance event feed carry no
+        // block or transaction. Rewinding the feed makes the indexer replay it,
+        // and its upserts fill both columns on the existing rows.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "DELETE FROM indexer_checkpoints \
+                 WHERE id = 'governance_events' \
+                 AND (EXISTS (SELECT 1 FROM governance_proposals WHERE block_number IS NULL) \
+                 OR EXISTS (SELECT 1 FROM governance_votes WHERE block_number IS NULL))",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // Replayed chain references are kept; nothing to undo.
+        Ok(())
+    }
+}
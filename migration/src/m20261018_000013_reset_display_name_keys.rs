@@ -0,0 +1,28 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Search keys and skeletons now use full case folding, which only the
+        // application implements. Clearing them makes the indexer recompute
+        // both on startup.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE identity_profiles \
+                 SET display_name_search = NULL, display_name_skeleton = NULL \
+                 WHERE display_name IS NOT NULL",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // Cleared keys are recomputed, not restored; nothing to undo.
+        Ok(())
+    }
+}
@@ -0,0 +1,318 @@
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{Context, Result, anyhow};
+use futures::future::BoxFuture;
+use image::imageops::FilterType;
+use image::{ImageFormat, ImageReader, Limits};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::config::AvatarConfig;
+use crate::identity::AVATAR_HASH_BYTES;
+
+/// Hard ceiling on `avatars.max_bytes`, also the request body limit of the
+/// upload route.
+pub const MAX_AVATAR_UPLOAD_BYTES: usize = 8 * 1024 * 1024;
+
+/// Square edge lengths served as resized variants. Anything else is
+/// rejected so variants cannot be used to fill the store.
+pub const AVATAR_VARIANT_SIZES: [u32; 4] = [32, 64, 128, 256];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AvatarFormat {
+    Png,
+    Jpeg,
+    Gif,
+    Webp,
+}
+
+impl AvatarFormat {
+    /// Identifies an allowed image type from its leading bytes. The declared
+    /// content type of an upload is never trusted.
+    pub fn sniff(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a]) {
+            Some(AvatarFormat::Png)
+        } else if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+            Some(AvatarFormat::Jpeg)
+        } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            Some(AvatarFormat::Gif)
+        } else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+            Some(AvatarFormat::Webp)
+        } else {
+            None
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            AvatarFormat::Png => "image/png",
+            AvatarFormat::Jpeg => "image/jpeg",
+            AvatarFormat::Gif => "image/gif",
+            AvatarFormat::Webp => "image/webp",
+        }
+    }
+
+    fn image_format(&self) -> ImageFormat {
+        match self {
+            AvatarFormat::Png => ImageFormat::Png,
+            AvatarFormat::Jpeg => ImageFormat::Jpeg,
+            AvatarFormat::Gif => ImageFormat::Gif,
+            AvatarFormat::Webp => ImageFormat::WebP,
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AvatarError {
+    #[error("Avatar upload is empty")]
+    Empty,
+    #[error("Avatar exceeds {limit} byte limit")]
+    TooLarge { limit: usize },
+    #[error("Avatar must be a PNG, JPEG, GIF or WebP image")]
+    UnsupportedType,
+    #[error("Avatar content does not match hash {expected}")]
+    HashMismatch { expected: String },
+    #[error("Avatar image could not be decoded")]
+    Undecodable,
+    #[error("Avatar dimensions exceed {limit}x{limit}")]
+    DimensionsTooLarge { limit: u32 },
+}
+
+pub fn avatar_hash(bytes: &[u8]) -> [u8; AVATAR_HASH_BYTES] {
+    Sha256::digest(bytes).into()
+}
+
+/// Accepts an upload only if it hashes to `expected_hash`, is an allowed
+/// image type within the size limit, and its header declares dimensions
+/// within bounds.
+pub fn validate_avatar(
+    bytes: &[u8],
+    expected_hash: &[u8],
+    config: &AvatarConfig,
+) -> Result<AvatarFormat, AvatarError> {
+    if bytes.is_empty() {
+        return Err(AvatarError::Empty);
+    }
+    if bytes.len() > config.max_bytes {
+        return Err(AvatarError::TooLarge {
+            limit: config.max_bytes,
+        });
+    }
+    if avatar_hash(bytes).as_slice() != expected_hash {
+        return Err(AvatarError::HashMismatch {
+            expected: hex::encode(expected_hash),
+        });
+    }
+    let format = AvatarFormat::sniff(bytes).ok_or(AvatarError::UnsupportedType)?;
+    let (width, height) = ImageReader::with_format(Cursor::new(bytes), format.image_format())
+        .into_dimensions()
+        .map_err(|_| AvatarError::Undecodable)?;
+    if width == 0 || height == 0 {
+        return Err(AvatarError::Undecodable);
+    }
+    if width > config.max_dimension || height > config.max_dimension {
+        return Err(AvatarError::DimensionsTooLarge {
+            limit: config.max_dimension,
+        });
+    }
+    Ok(format)
+}
+
+/// Center-crops and scales a stored avatar to a `size` square PNG. CPU bound;
+/// run it off the async executor.
+pub fn resize_avatar(bytes: &[u8], size: u32, max_dimension: u32) -> Result<Vec<u8>> {
+    assert!(
+        AVATAR_VARIANT_SIZES.contains(&size),
+        "Avatar variant size must be allow-listed"
+    );
+    let format =
+        AvatarFormat::sniff(bytes).ok_or_else(|| anyhow!("Stored avatar has unknown type"))?;
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(max_dimension);
+    limits.max_image_height = Some(max_dimension);
+    let mut reader = ImageReader::with_format(Cursor::new(bytes), format.image_format());
+    reader.limits(limits);
+    let image = reader.decode().context("Failed to decode stored avatar")?;
+
+    let resized = image.resize_to_fill(size, size, FilterType::Lanczos3);
+    let mut encoded = Vec::new();
+    resized
+        .write_to(&mut Cursor::new(&mut encoded), ImageFormat::Png)
+        .context("Failed to encode avatar variant")?;
+    Ok(encoded)
+}
+
+/// Store key of the original upload for `hash`.
+pub fn original_key(hash: &[u8]) -> String {
+    hex::encode(hash)
+}
+
+/// Store key of a resized PNG variant.
+pub fn variant_key(hash: &[u8], size: u32) -> String {
+    format!("{}-{size}.png", hex::encode(hash))
+}
+
+/// Content-addressed blob storage for avatars. Keys come from
+/// [`original_key`] and [`variant_key`]; a stored key never changes content,
+/// so implementations may cache and replicate freely.
+pub trait AvatarStore: Send + Sync {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<Vec<u8>>>>;
+    fn put<'a>(&'a self, key: &'a str, bytes: Vec<u8>) -> BoxFuture<'a, Result<()>>;
+}
+
+/// Stores avatars as files under `root`, sharded by the first two hex
+/// characters of the key.
+#[derive(Debug, Clone)]
+pub struct LocalAvatarStore {
+    root: PathBuf,
+}
+
+impl LocalAvatarStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path_for(&self, key: &str) -> Result<PathBuf> {
+        let valid = key.len() > 2
+            && key.bytes().all(|byte| {
+                byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'-' || byte == b'.'
+            });
+        if !valid || key.contains("..") {
+            return Err(anyhow!("Invalid avatar store key {key}"));
+        }
+        Ok(self.root.join(&key[..2]).join(key))
+    }
+}
+
+impl AvatarStore for LocalAvatarStore {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<Vec<u8>>>> {
+        Box::pin(async move {
+            let path = self.path_for(key)?;
+            match tokio::fs::read(&path).await {
+                Ok(bytes) => Ok(Some(bytes)),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(err) => {
+                    Err(err).with_context(|| format!("Failed to read avatar {}", path.display()))
+                }
+            }
+        })
+    }
+
+    fn put<'a>(&'a self, key: &'a str, bytes: Vec<u8>) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let path = self.path_for(key)?;
+            let parent = path.parent().expect("Sharded avatar path has a parent");
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+            // Write then rename so readers never observe a partial file.
+            let staging = path.with_extension(format!("tmp-{}", rand::random::<u32>()));
+            tokio::fs::write(&staging, &bytes)
+                .await
+                .with_context(|| format!("Failed to write {}", staging.display()))?;
+            tokio::fs::rename(&staging, &path)
+                .await
+                .with_context(|| format!("Failed to store avatar {}", path.display()))?;
+            Ok(())
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct Avatars {
+    pub store: Arc<dyn AvatarStore>,
+    pub config: AvatarConfig,
+}
+
+impl Avatars {
+    pub fn local(config: &AvatarConfig) -> Self {
+        Self {
+            store: Arc::new(LocalAvatarStore::new(&config.storage_dir)),
+            config: config.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png_bytes(width: u32, height: u32) -> Vec<u8> {
+        let image = image::DynamicImage::new_rgb8(width, height);
+        let mut encoded = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut encoded), ImageFormat::Png)
+            .expect("encode png");
+        encoded
+    }
+
+    #[test]
+    fn sniff_allows_only_known_image_types() {
+        assert_eq!(
+            AvatarFormat::sniff(&png_bytes(1, 1)),
+            Some(AvatarFormat::Png)
+        );
+        assert_eq!(
+            AvatarFormat::sniff(&[0xff, 0xd8, 0xff, 0xe0]),
+            Some(AvatarFormat::Jpeg)
+        );
+        assert_eq!(AvatarFormat::sniff(b"GIF89a...."), Some(AvatarFormat::Gif));
+        assert_eq!(
+            AvatarFormat::sniff(b"RIFF\0\0\0\0WEBPVP8 "),
+            Some(AvatarFormat::Webp)
+        );
+        assert_eq!(AvatarFormat::sniff(b"<svg xmlns="), None);
+    }
+
+    #[test]
+    fn validate_checks_hash_size_and_dimensions() {
+        let config = AvatarConfig::default();
+        let bytes = png_bytes(16, 16);
+        let hash = avatar_hash(&bytes);
+        assert_eq!(
+            validate_avatar(&bytes, &hash, &config),
+            Ok(AvatarFormat::Png)
+        );
+        assert!(matches!(
+            validate_avatar(&bytes, &[0u8; AVATAR_HASH_BYTES], &config),
+            Err(AvatarError::HashMismatch { .. })
+        ));
+
+        let tight = AvatarConfig {
+            max_dimension: 8,
+            ..AvatarConfig::default()
+        };
+        assert_eq!(
+            validate_avatar(&bytes, &hash, &tight),
+            Err(AvatarError::DimensionsTooLarge { limit: 8 })
+        );
+
+        let svg = b"<svg xmlns='http://www.w3.org/2000/svg'/>";
+        assert_eq!(
+            validate_avatar(svg, &avatar_hash(svg), &config),
+            Err(AvatarError::UnsupportedType)
+        );
+    }
+
+    #[test]
+    fn resize_produces_square_png() {
+        let resized = resize_avatar(&png_bytes(40, 20), 32, 4_096).expect("resize");
+        let image = image::load_from_memory(&resized).expect("decode variant");
+        assert_eq!((image.width(), image.height()), (32, 32));
+    }
+
+    #[test]
+    fn local_store_rejects_path_keys() {
+        let store = LocalAvatarStore::new("/tmp/avatars");
+        assert!(store.path_for("../../etc/passwd").is_err());
+        assert!(store.path_for("ab/cd").is_err());
+        assert!(
+            store
+                .path_for(&variant_key(&[0xab; 32], 64))
+                .unwrap()
+                .starts_with("/tmp/avatars/ab")
+        );
+    }
+}
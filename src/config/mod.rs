@@ -6,6 +6,8 @@ use anyhow::{Context, Result};
 use config::{Config, ConfigError, File, FileFormat};
 use serde::Deserialize;
 
+use crate::avatar::MAX_AVATAR_UPLOAD_BYTES;
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct ApiConfig {
     pub server: ServerConfig,
@@ -14,6 +16,14 @@ pub struct ApiConfig {
     pub indexer: IndexerConfig,
     pub rate_limiting: RateLimitingConfig,
     pub cache: CacheConfig,
+    #[serde(default)]
+    pub governance: GovernanceConfig,
+    #[serde(default)]
+    pub avatars: AvatarConfig,
+    #[serde(default)]
+    pub stealth_registrations: StealthRegistrationConfig,
+    #[serde(default)]
+    pub stealth_scan: StealthScanConfig,
 }
 
 impl ApiConfig {
@@ -79,6 +89,10 @@ impl ApiConfig {
         );
         self.indexer.ensure_bounds()?;
         self.cache.ensure_bounds()?;
+        self.governance.ensure_bounds()?;
+        self.avatars.ensure_bounds()?;
+        self.stealth_registrations.ensure_bounds()?;
+        self.stealth_scan.ensure_bounds()?;
         Ok(())
     }
 }
@@ -127,6 +141,12 @@ pub struct IndexerConfig {
     pub batch_size: u64,
     #[serde(default = "IndexerConfig::default_identity_batch_size")]
     pub identity_batch_size: u64,
+    #[serde(default = "IndexerConfig::default_delegation_batch_size")]
+    pub delegation_batch_size: u64,
+    #[serde(default = "IndexerConfig::default_follow_batch_size")]
+    pub follow_batch_size: u64,
+    #[serde(default = "IndexerConfig::default_governance_batch_size")]
+    pub governance_batch_size: u64,
 }
 
 impl IndexerConfig {
@@ -153,6 +173,30 @@ impl IndexerConfig {
             self.identity_batch_size <= 1024,
             "Identity batch size exceeds defensive limit"
         );
+        assert!(
+            self.delegation_batch_size > 0,
+            "Delegation batch size must be positive"
+        );
+        assert!(
+            self.delegation_batch_size <= 1024,
+            "Delegation batch size exceeds defensive limit"
+        );
+        assert!(
+            self.follow_batch_size > 0,
+            "Follow batch size must be positive"
+        );
+        assert!(
+            self.follow_batch_size <= 1024,
+            "Follow batch size exceeds defensive limit"
+        );
+        assert!(
+            self.governance_batch_size > 0,
+            "Governance batch size must be positive"
+        );
+        assert!(
+            self.governance_batch_size <= 1024,
+            "Governance batch size exceeds defensive limit"
+        );
         Ok(())
     }
 
@@ -164,9 +208,45 @@ impl IndexerConfig {
         self.identity_batch_size
     }
 
+    pub fn delegation_batch_size(&self) -> u64 {
+        assert!(
+            self.delegation_batch_size > 0,
+            "Delegation batch size invariant broken"
+        );
+        self.delegation_batch_size
+    }
+
+    pub fn follow_batch_size(&self) -> u64 {
+        assert!(
+            self.follow_batch_size > 0,
+            "Follow batch size invariant broken"
+        );
+        self.follow_batch_size
+    }
+
+    pub fn governance_batch_size(&self) -> u64 {
+        assert!(
+            self.governance_batch_size > 0,
+            "Governance batch size invariant broken"
+        );
+        self.governance_batch_size
+    }
+
     const fn default_identity_batch_size() -> u64 {
         128
     }
+
+    const fn default_delegation_batch_size() -> u64 {
+        256
+    }
+
+    const fn default_follow_batch_size() -> u64 {
+        256
+    }
+
+    const fn default_governance_batch_size() -> u64 {
+        256
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -183,6 +263,25 @@ pub struct CacheConfig {
     pub leaderboards_ttl_seconds: u64,
     pub proposals_max_capacity: u64,
     pub proposals_ttl_seconds: u64,
+    /// Lifetime of cached identity stats. Entries are also dropped once a
+    /// newer block is indexed, so this only bounds balance staleness.
+    #[serde(default = "CacheConfig::default_identity_stats_ttl_seconds")]
+    pub identity_stats_ttl_seconds: u64,
+    /// Lifetime of a wallet link challenge nonce.
+    #[serde(default = "CacheConfig::default_wallet_challenge_ttl_seconds")]
+    pub wallet_challenge_ttl_seconds: u64,
+    /// Outstanding wallet link challenges held across all identities.
+    #[serde(default = "CacheConfig::default_wallet_challenges_max_capacity")]
+    pub wallet_challenges_max_capacity: u64,
+    /// Challenges one identity may be issued per challenge TTL.
+    #[serde(default = "CacheConfig::default_wallet_challenges_per_identity")]
+    pub wallet_challenges_per_identity: u32,
+    /// Pages of the compact stealth output stream. Each holds up to
+    /// `STEALTH_STREAM_PAGE_SIZE` outputs of already indexed blocks.
+    #[serde(default = "CacheConfig::default_stealth_pages_max_capacity")]
+    pub stealth_pages_max_capacity: u64,
+    #[serde(default = "CacheConfig::default_stealth_pages_ttl_seconds")]
+    pub stealth_pages_ttl_seconds: u64,
 }
 
 impl CacheConfig {
@@ -195,8 +294,518 @@ impl CacheConfig {
             self.identities_ttl_seconds <= 86_400,
             "Identity cache TTL cannot exceed one day"
         );
+        assert!(
+            (1..=600).contains(&self.identity_stats_ttl_seconds),
+            "Identity stats TTL must be between 1 and 600 seconds"
+        );
+        assert!(
+            self.wallet_challenge_ttl_seconds >= 30,
+            "Wallet challenge TTL must be at least 30 seconds"
+        );
+        assert!(
+            self.wallet_challenge_ttl_seconds <= 3_600,
+            "Wallet challenge TTL cannot exceed one hour"
+        );
+        assert!(
+            (16..=65_536).contains(&self.wallet_challenges_max_capacity),
+            "Wallet challenge capacity must be between 16 and 65536"
+        );
+        assert!(
+            (1..=32).contains(&self.wallet_challenges_per_identity),
+            "Wallet challenges per identity must be between 1 and 32"
+        );
+        assert!(
+            self.stealth_pages_max_capacity <= 65_536,
+            "Stealth page cache capacity cannot exceed 65536 pages"
+        );
+        assert!(
+            self.stealth_pages_ttl_seconds <= 86_400,
+            "Stealth page cache TTL cannot exceed one day"
+        );
+        Ok(())
+    }
+
+    const fn default_identity_stats_ttl_seconds() -> u64 {
+        30
+    }
+
+    const fn default_wallet_challenge_ttl_seconds() -> u64 {
+        300
+    }
+
+    const fn default_wallet_challenges_max_capacity() -> u64 {
+        1_024
+    }
+
+    const fn default_wallet_challenges_per_identity() -> u32 {
+        4
+    }
+
+    const fn default_stealth_pages_max_capacity() -> u64 {
+        256
+    }
+
+    const fn default_stealth_pages_ttl_seconds() -> u64 {
+        600
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AvatarConfig {
+    /// Directory of the local content-addressed avatar store.
+    #[serde(default = "AvatarConfig::default_storage_dir")]
+    pub storage_dir: String,
+    #[serde(default = "AvatarConfig::default_max_bytes")]
+    pub max_bytes: usize,
+    /// Largest accepted width or height, bounding decode cost when resizing.
+    #[serde(default = "AvatarConfig::default_max_dimension")]
+    pub max_dimension: u32,
+}
+
+impl AvatarConfig {
+    fn ensure_bounds(&self) -> Result<()> {
+        assert!(
+            !self.storage_dir.is_empty(),
+            "Avatar storage directory must be specified"
+        );
+        assert!(
+            (1_024..=MAX_AVATAR_UPLOAD_BYTES).contains(&self.max_bytes),
+            "Avatar size limit must be between 1 KiB and 8 MiB"
+        );
+        assert!(
+            (64..=8_192).contains(&self.max_dimension),
+            "Avatar dimension limit must be between 64 and 8192"
+        );
         Ok(())
     }
+
+    fn default_storage_dir() -> String {
+        "data/avatars".to_string()
+    }
+
+    const fn default_max_bytes() -> usize {
+        1_048_576
+    }
+
+    const fn default_max_dimension() -> u32 {
+        4_096
+    }
+}
+
+impl Default for AvatarConfig {
+    fn default() -> Self {
+        Self {
+            storage_dir: Self::default_storage_dir(),
+            max_bytes: Self::default_max_bytes(),
+            max_dimension: Self::default_max_dimension(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StealthRegistrationConfig {
+    /// Hex-encoded 32 byte key sealing registered view keys and inbox
+    /// entries. Registration is disabled while unset; keep it out of the
+    /// shared config file.
+    #[serde(default)]
+    pub encryption_key: Option<String>,
+    #[serde(default = "StealthRegistrationConfig::default_scan_interval_ms")]
+    pub scan_interval_ms: u64,
+    /// Blocks scanned per registration batch.
+    #[serde(default = "StealthRegistrationConfig::default_scan_batch_blocks")]
+    pub scan_batch_blocks: u64,
+    /// How far behind the indexer tip a new registration may start.
+    #[serde(default = "StealthRegistrationConfig::default_max_backfill_blocks")]
+    pub max_backfill_blocks: u64,
+    #[serde(default = "StealthRegistrationConfig::default_ttl_seconds")]
+    pub default_ttl_seconds: i64,
+    #[serde(default = "StealthRegistrationConfig::default_max_ttl_seconds")]
+    pub max_ttl_seconds: i64,
+    #[serde(default = "StealthRegistrationConfig::default_max_registrations")]
+    pub max_registrations: u64,
+    #[serde(default = "StealthRegistrationConfig::default_stream_capacity")]
+    pub stream_capacity: usize,
+}
+
+impl StealthRegistrationConfig {
+    pub fn scan_interval(&self) -> Duration {
+        Duration::from_millis(self.scan_interval_ms)
+    }
+
+    /// The configured sealing key, if registration is enabled.
+    pub fn encryption_key(&self) -> Result<Option<[u8; 32]>> {
+        let Some(raw) = self.encryption_key.as_deref() else {
+            return Ok(None);
+        };
+        let bytes =
+            hex::decode(raw.trim()).context("stealth_registrations.encryption_key must be hex")?;
+        let key = <[u8; 32]>::try_from(bytes.as_slice()).map_err(|_| {
+            anyhow::anyhow!("stealth_registrations.encryption_key must be 32 bytes")
+        })?;
+        Ok(Some(key))
+    }
+
+    fn ensure_bounds(&self) -> Result<()> {
+        assert!(
+            (500..=60_000).contains(&self.scan_interval_ms),
+            "Stealth scan interval must be between 500ms and 60 seconds"
+        );
+        assert!(
+            (1..=10_000).contains(&self.scan_batch_blocks),
+            "Stealth scan batch must be between 1 and 10000 blocks"
+        );
+        assert!(
+            self.max_backfill_blocks <= 100_000,
+            "Stealth scan backfill cannot exceed 100000 blocks"
+        );
+        assert!(
+            self.default_ttl_seconds > 0 && self.default_ttl_seconds <= self.max_ttl_seconds,
+            "Default registration TTL must be positive and within the maximum"
+        );
+        assert!(
+            self.max_ttl_seconds <= 90 * 86_400,
+            "Registration TTL cannot exceed 90 days"
+        );
+        assert!(
+            self.max_registrations > 0,
+            "Registration limit must be positive"
+        );
+        assert!(
+            self.stream_capacity > 0,
+            "Registration notification capacity must be positive"
+        );
+        self.encryption_key()?;
+        Ok(())
+    }
+
+    const fn default_scan_interval_ms() -> u64 {
+        2_000
+    }
+
+    const fn default_scan_batch_blocks() -> u64 {
+        1_000
+    }
+
+    const fn default_max_backfill_blocks() -> u64 {
+        10_000
+    }
+
+    const fn default_ttl_seconds() -> i64 {
+        7 * 86_400
+    }
+
+    const fn default_max_ttl_seconds() -> i64 {
+        30 * 86_400
+    }
+
+    const fn default_max_registrations() -> u64 {
+        10_000
+    }
+
+    const fn default_stream_capacity() -> usize {
+        1_024
+    }
+}
+
+impl Default for StealthRegistrationConfig {
+    fn default() -> Self {
+        Self {
+            encryption_key: None,
+            scan_interval_ms: Self::default_scan_interval_ms(),
+            scan_batch_blocks: Self::default_scan_batch_blocks(),
+            max_backfill_blocks: Self::default_max_backfill_blocks(),
+            default_ttl_seconds: Self::default_ttl_seconds(),
+            max_ttl_seconds: Self::default_max_ttl_seconds(),
+            max_registrations: Self::default_max_registrations(),
+            stream_capacity: Self::default_stream_capacity(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StealthScanConfig {
+    /// Threads of the detection pool; defaults to the available parallelism.
+    #[serde(default)]
+    pub worker_threads: Option<usize>,
+    /// Scans running at once across requests and registered scanning.
+    /// Requests beyond this are rejected rather than queued.
+    #[serde(default = "StealthScanConfig::default_max_concurrent_scans")]
+    pub max_concurrent_scans: usize,
+    /// Rows fetched per database round trip while scanning.
+    #[serde(default = "StealthScanConfig::default_chunk_rows")]
+    pub chunk_rows: u64,
+}
+
+impl StealthScanConfig {
+    pub fn worker_threads(&self) -> usize {
+        self.worker_threads.unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|threads| threads.get())
+                .unwrap_or(1)
+        })
+    }
+
+    fn ensure_bounds(&self) -> Result<()> {
+        assert!(
+            self.worker_threads
+                .is_none_or(|threads| (1..=256).contains(&threads)),
+            "Stealth scan threads must be between 1 and 256"
+        );
+        assert!(
+            (1..=256).contains(&self.max_concurrent_scans),
+            "Concurrent stealth scans must be between 1 and 256"
+        );
+        assert!(
+            (100..=50_000).contains(&self.chunk_rows),
+            "Stealth scan chunk must be between 100 and 50000 rows"
+        );
+        Ok(())
+    }
+
+    const fn default_max_concurrent_scans() -> usize {
+        4
+    }
+
+    const fn default_chunk_rows() -> u64 {
+        2_000
+    }
+}
+
+impl Default for StealthScanConfig {
+    fn default() -> Self {
+        Self {
+            worker_threads: None,
+            max_concurrent_scans: Self::default_max_concurrent_scans(),
+            chunk_rows: Self::default_chunk_rows(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GovernanceConfig {
+    #[serde(default = "GovernanceConfig::default_lifecycle_interval_ms")]
+    pub lifecycle_interval_ms: u64,
+    #[serde(default = "GovernanceConfig::default_quorum_votes")]
+    pub quorum_votes: i64,
+    #[serde(default = "GovernanceConfig::default_quorum_bps")]
+    pub quorum_bps: u32,
+    #[serde(default = "GovernanceConfig::default_approval_threshold_bps")]
+    pub approval_threshold_bps: u32,
+    #[serde(default = "GovernanceConfig::default_execution_grace_seconds")]
+    pub execution_grace_seconds: i64,
+    #[serde(default)]
+    pub notifications: NotificationConfig,
+    /// Contracts whose manifests the operator publishes for decoding
+    /// proposal actions.
+    #[serde(default)]
+    pub contracts: Vec<GovernedContractConfig>,
+}
+
+/// A proposal target and the deployment manifest published for it.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GovernedContractConfig {
+    pub address: String,
+    /// Code hash the manifest was recorded for at deployment. The target is
+    /// only reported verified while the deployed code matches it.
+    pub code_hash: String,
+    /// JSON deployment manifest describing the contract's methods.
+    pub manifest_path: String,
+}
+
+impl GovernanceConfig {
+    pub fn lifecycle_interval(&self) -> Duration {
+        assert!(
+            self.lifecycle_interval_ms >= 1_000,
+            "Lifecycle interval must be >= 1 second"
+        );
+        assert!(
+            self.lifecycle_interval_ms <= 3_600_000,
+            "Lifecycle interval must be <= 1 hour"
+        );
+        Duration::from_millis(self.lifecycle_interval_ms)
+    }
+
+    fn ensure_bounds(&self) -> Result<()> {
+        assert!(
+            self.lifecycle_interval_ms >= 1_000,
+            "Lifecycle interval must be at least one second"
+        );
+        assert!(
+            self.lifecycle_interval_ms <= 3_600_000,
+            "Lifecycle interval cannot exceed one hour"
+        );
+        assert!(self.quorum_votes >= 0, "Quorum votes cannot be negative");
+        assert!(
+            self.quorum_bps <= 10_000,
+            "Quorum basis points cannot exceed 10000"
+        );
+        assert!(
+            self.approval_threshold_bps < 10_000,
+            "Approval threshold basis points must be below 10000"
+        );
+        assert!(
+            self.execution_grace_seconds > 0,
+            "Execution grace period must be positive"
+        );
+        assert!(
+            self.execution_grace_seconds <= 31_536_000,
+            "Execution grace period cannot exceed one year"
+        );
+        assert!(self.contracts.len() <= 1_024, "Too many governed contracts");
+        for contract in &self.contracts {
+            assert!(
+                !contract.address.trim().is_empty(),
+                "Governed contract address must be specified"
+            );
+            assert!(
+                !contract.manifest_path.is_empty(),
+                "Governed contract manifest path must be specified"
+            );
+        }
+        self.notifications.ensure_bounds()
+    }
+
+    const fn default_lifecycle_interval_ms() -> u64 {
+        15_000
+    }
+
+    const fn default_quorum_votes() -> i64 {
+        0
+    }
+
+    const fn default_quorum_bps() -> u32 {
+        400
+    }
+
+    const fn default_approval_threshold_bps() -> u32 {
+        5_000
+    }
+
+    const fn default_execution_grace_seconds() -> i64 {
+        1_209_600
+    }
+}
+
+impl Default for GovernanceConfig {
+    fn default() -> Self {
+        Self {
+            lifecycle_interval_ms: Self::default_lifecycle_interval_ms(),
+            quorum_votes: Self::default_quorum_votes(),
+            quorum_bps: Self::default_quorum_bps(),
+            approval_threshold_bps: Self::default_approval_threshold_bps(),
+            execution_grace_seconds: Self::default_execution_grace_seconds(),
+            notifications: NotificationConfig::default(),
+            contracts: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotificationConfig {
+    #[serde(default = "NotificationConfig::default_stream_capacity")]
+    pub stream_capacity: usize,
+    #[serde(default = "NotificationConfig::default_closing_soon_seconds")]
+    pub closing_soon_seconds: i64,
+    #[serde(default = "NotificationConfig::default_webhook_timeout_ms")]
+    pub webhook_timeout_ms: u64,
+    #[serde(default = "NotificationConfig::default_webhook_max_attempts")]
+    pub webhook_max_attempts: u32,
+    /// Webhook deliveries allowed in flight at once.
+    #[serde(default = "NotificationConfig::default_webhook_max_concurrency")]
+    pub webhook_max_concurrency: usize,
+    #[serde(default)]
+    pub webhooks: Vec<WebhookSubscriberConfig>,
+}
+
+/// A webhook receiving governance notifications. Empty filters match all.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookSubscriberConfig {
+    pub url: String,
+    #[serde(default)]
+    pub proposer: Option<String>,
+    /// Honoured only while the address's activity is public.
+    #[serde(default)]
+    pub voter: Option<String>,
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+impl NotificationConfig {
+    pub fn webhook_timeout(&self) -> Duration {
+        assert!(
+            self.webhook_timeout_ms >= 100,
+            "Webhook timeout must be >= 100ms"
+        );
+        Duration::from_millis(self.webhook_timeout_ms)
+    }
+
+    fn ensure_bounds(&self) -> Result<()> {
+        assert!(
+            (16..=65_536).contains(&self.stream_capacity),
+            "Notification stream capacity must be between 16 and 65536"
+        );
+        assert!(
+            self.closing_soon_seconds > 0,
+            "Closing-soon window must be positive"
+        );
+        assert!(
+            self.closing_soon_seconds <= 2_592_000,
+            "Closing-soon window cannot exceed 30 days"
+        );
+        assert!(
+            (100..=60_000).contains(&self.webhook_timeout_ms),
+            "Webhook timeout must be between 100ms and 60s"
+        );
+        assert!(
+            (1..=10).contains(&self.webhook_max_attempts),
+            "Webhook attempts must be between 1 and 10"
+        );
+        assert!(
+            (1..=256).contains(&self.webhook_max_concurrency),
+            "Webhook concurrency must be between 1 and 256"
+        );
+        assert!(self.webhooks.len() <= 64, "Too many webhook subscribers");
+        for webhook in &self.webhooks {
+            assert!(
+                webhook.url.starts_with("https://") || webhook.url.starts_with("http://"),
+                "Webhook url must use http or https"
+            );
+        }
+        Ok(())
+    }
+
+    const fn default_stream_capacity() -> usize {
+        256
+    }
+
+    const fn default_closing_soon_seconds() -> i64 {
+        86_400
+    }
+
+    const fn default_webhook_timeout_ms() -> u64 {
+        5_000
+    }
+
+    const fn default_webhook_max_attempts() -> u32 {
+        3
+    }
+
+    const fn default_webhook_max_concurrency() -> usize {
+        16
+    }
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            stream_capacity: Self::default_stream_capacity(),
+            closing_soon_seconds: Self::default_closing_soon_seconds(),
+            webhook_timeout_ms: Self::default_webhook_timeout_ms(),
+            webhook_max_attempts: Self::default_webhook_max_attempts(),
+            webhook_max_concurrency: Self::default_webhook_max_concurrency(),
+            webhooks: Vec::new(),
+        }
+    }
 }
 
 fn map_config_error(err: ConfigError, path: &str) -> ConfigError {
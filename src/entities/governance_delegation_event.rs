@@ -0,0 +1,24 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "governance_delegation_events")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub delegator: String,
+    pub delegatee: String,
+    pub previous_delegatee: Option<String>,
+    pub event_type: String,
+    pub amount: i64,
+    pub block_number: i64,
+    pub tx_id: String,
+    pub event_index: i32,
+    pub occurred_at: DateTimeWithTimeZone,
+    pub indexed_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
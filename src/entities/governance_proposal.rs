@@ -20,6 +20,10 @@ pub struct Model {
     pub executed_at: Option<DateTimeWithTimeZone>,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
+    pub snapshot_block: Option<i64>,
+    pub snapshot_eligible_power: Option<i64>,
+    pub block_number: Option<i64>,
+    pub tx_id: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -12,6 +12,8 @@ pub struct Model {
     pub weight: i64,
     pub reason: Option<String>,
     pub voted_at: DateTimeWithTimeZone,
+    pub block_number: Option<i64>,
+    pub tx_id: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
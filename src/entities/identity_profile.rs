@@ -7,6 +7,7 @@ pub struct Model {
     pub identity_id: Vec<u8>,
     pub display_name: Option<String>,
     pub display_name_search: Option<String>,
+    pub display_name_skeleton: Option<String>,
     pub avatar_hash: Option<Vec<u8>>,
     pub bio: Option<String>,
     pub stats_visibility: String,
@@ -0,0 +1,27 @@
+use sea_orm::JsonValue;
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "identity_profile_history")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub identity_id: Vec<u8>,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub profile_version: i32,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub last_synced_block: i64,
+    pub display_name: Option<String>,
+    pub avatar_hash: Option<Vec<u8>>,
+    pub bio: Option<String>,
+    pub stats_visibility: String,
+    /// JSON array of wallet addresses linked by this version.
+    pub wallets_added: JsonValue,
+    /// JSON array of wallet addresses unlinked by this version.
+    pub wallets_removed: JsonValue,
+    pub updated_at: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
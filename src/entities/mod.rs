@@ -2,10 +2,17 @@ pub mod chain_block;
 pub mod chain_transaction;
 pub mod faucet_request;
 pub mod governance_delegation;
+pub mod governance_delegation_event;
+pub mod governance_notification;
 pub mod governance_proposal;
 pub mod governance_vote;
 pub mod identity_profile;
+pub mod identity_profile_history;
 pub mod indexer_checkpoint;
 pub mod prelude;
+pub mod social_connection;
 pub mod stealth_output;
+pub mod stealth_scan_inbox;
+pub mod stealth_scan_registration;
+pub mod voting_power_snapshot;
 pub mod wallet_link;
@@ -3,9 +3,16 @@
 pub use super::chain_block::Entity as ChainBlock;
 pub use super::chain_transaction::Entity as ChainTransaction;
 pub use super::governance_delegation::Entity as GovernanceDelegation;
+pub use super::governance_delegation_event::Entity as GovernanceDelegationEvent;
+pub use super::governance_notification::Entity as GovernanceNotification;
 pub use super::governance_proposal::Entity as GovernanceProposal;
 pub use super::governance_vote::Entity as GovernanceVote;
 pub use super::identity_profile::Entity as IdentityProfile;
+pub use super::identity_profile_history::Entity as IdentityProfileHistory;
 pub use super::indexer_checkpoint::Entity as IndexerCheckpoint;
+pub use super::social_connection::Entity as SocialConnection;
 pub use super::stealth_output::Entity as StealthOutput;
+pub use super::stealth_scan_inbox::Entity as StealthScanInbox;
+pub use super::stealth_scan_registration::Entity as StealthScanRegistration;
+pub use super::voting_power_snapshot::Entity as VotingPowerSnapshot;
 pub use super::wallet_link::Entity as WalletLink;
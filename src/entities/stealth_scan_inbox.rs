@@ -0,0 +1,33 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "stealth_scan_inbox")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub registration_id: String,
+    pub output_tag: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+    pub nonce: Vec<u8>,
+    pub detected_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::stealth_scan_registration::Entity",
+        from = "Column::RegistrationId",
+        to = "super::stealth_scan_registration::Column::RegistrationId",
+        on_delete = "Cascade",
+        on_update = "Cascade"
+    )]
+    StealthScanRegistration,
+}
+
+impl Related<super::stealth_scan_registration::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::StealthScanRegistration.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,28 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "stealth_scan_registrations")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub registration_id: String,
+    pub token_hash: Vec<u8>,
+    pub key_ciphertext: Vec<u8>,
+    pub key_nonce: Vec<u8>,
+    pub next_block: i64,
+    pub created_at: DateTimeWithTimeZone,
+    pub expires_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::stealth_scan_inbox::Entity")]
+    StealthScanInbox,
+}
+
+impl Related<super::stealth_scan_inbox::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::StealthScanInbox.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,549 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, FixedOffset};
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+
+use crate::entities::{
+    chain_transaction, faucet_request, governance_delegation_event, governance_vote,
+    identity_profile,
+};
+use crate::identity::encode_identity_id;
+use crate::models::identity::{ActivityFeedItemView, ActivityKind};
+
+pub const MAX_FEED_PAGE: u64 = 100;
+/// Wallets merged into a single feed query; beyond this the `IN` lists get
+/// unreasonably large.
+pub const MAX_FEED_WALLETS: usize = 1_024;
+/// Followed identities merged into the following feed.
+pub const MAX_FEED_IDENTITIES: u64 = 500;
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+
+/// Table an activity item was read from. Declaration order is the tie-break
+/// between items that share a timestamp, so it must stay stable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FeedSource {
+    Delegation,
+    Faucet,
+    Profile,
+    Transaction,
+    Vote,
+}
+
+impl FeedSource {
+    pub const ALL: [FeedSource; 5] = [
+        FeedSource::Delegation,
+        FeedSource::Faucet,
+        FeedSource::Profile,
+        FeedSource::Transaction,
+        FeedSource::Vote,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeedSource::Delegation => "delegation",
+            FeedSource::Faucet => "faucet",
+            FeedSource::Profile => "profile",
+            FeedSource::Transaction => "tx",
+            FeedSource::Vote => "vote",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        FeedSource::ALL
+            .into_iter()
+            .find(|source| source.as_str() == value)
+    }
+
+    fn keyed_by_id(&self) -> bool {
+        matches!(
+            self,
+            FeedSource::Delegation | FeedSource::Faucet | FeedSource::Vote
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FeedKey {
+    Id(i64),
+    Text(String),
+}
+
+/// Position of an item in a feed, newest first. Pages continue strictly
+/// after the cursor, so items sharing a timestamp are never repeated or
+/// skipped.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FeedCursor {
+    pub micros: i64,
+    pub source: FeedSource,
+    pub key: FeedKey,
+}
+
+impl FeedCursor {
+    pub fn encode(&self) -> String {
+        let key = match &self.key {
+            FeedKey::Id(id) => id.to_string(),
+            FeedKey::Text(text) => text.clone(),
+        };
+        format!("{}:{}:{}", self.micros, self.source.as_str(), key)
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.splitn(3, ':');
+        let micros = parts.next()?.parse::<i64>().ok()?;
+        let source = FeedSource::parse(parts.next()?)?;
+        let raw_key = parts.next()?;
+        if raw_key.is_empty() {
+            return None;
+        }
+        let key = if source.keyed_by_id() {
+            FeedKey::Id(raw_key.parse().ok()?)
+        } else {
+            FeedKey::Text(raw_key.to_string())
+        };
+        Some(Self {
+            micros,
+            source,
+            key,
+        })
+    }
+
+    fn timestamp(&self) -> Option<DateTime<FixedOffset>> {
+        DateTime::from_timestamp_micros(self.micros).map(|time| time.fixed_offset())
+    }
+}
+
+/// Wallets and identities whose activity a feed merges. Each wallet maps to
+/// the identity it is shown under.
+#[derive(Debug, Default)]
+pub struct FeedScope {
+    pub wallets: HashMap<String, Vec<u8>>,
+    pub profiles: Vec<identity_profile::Model>,
+}
+
+impl FeedScope {
+    fn addresses(&self) -> Vec<String> {
+        let mut addresses = self.wallets.keys().cloned().collect::<Vec<_>>();
+        addresses.sort();
+        addresses
+    }
+
+    fn owner_of(&self, wallet: &str) -> String {
+        self.wallets
+            .get(wallet)
+            .map(|id| encode_identity_id(id))
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug)]
+pub struct FeedPage {
+    pub items: Vec<ActivityFeedItemView>,
+    pub next_cursor: Option<FeedCursor>,
+}
+
+/// Reads one page of merged activity. Every source is queried for
+/// `limit + 1` rows after the cursor, which is enough to know whether the
+/// merged feed continues.
+pub async fn load_feed<C: ConnectionTrait>(
+    db: &C,
+    scope: &FeedScope,
+    cursor: Option<&FeedCursor>,
+    limit: u64,
+) -> Result<FeedPage, DbErr> {
+    assert!(
+        limit > 0 && limit <= MAX_FEED_PAGE,
+        "Feed limit out of bounds"
+    );
+    assert!(
+        scope.wallets.len() <= MAX_FEED_WALLETS,
+        "Feed wallet scope exceeds bound"
+    );
+
+    let fetch = limit + 1;
+    let mut entries = Vec::new();
+    if !scope.wallets.is_empty() {
+        entries.extend(transaction_entries(db, scope, cursor, fetch).await?);
+        entries.extend(vote_entries(db, scope, cursor, fetch).await?);
+        entries.extend(delegation_entries(db, scope, cursor, fetch).await?);
+        entries.extend(faucet_entries(db, scope, cursor, fetch).await?);
+    }
+    entries.extend(profile_entries(scope, cursor));
+
+    Ok(merge_entries(entries, limit as usize))
+}
+
+fn merge_entries(mut entries: Vec<(FeedCursor, ActivityFeedItemView)>, limit: usize) -> FeedPage {
+    entries.sort_by(|left, right| right.0.cmp(&left.0));
+    let has_more = entries.len() > limit;
+    entries.truncate(limit);
+    let next_cursor = if has_more {
+        entries.last().map(|(position, _)| position.clone())
+    } else {
+        None
+    };
+    FeedPage {
+        items: entries.into_iter().map(|(_, item)| item).collect(),
+        next_cursor,
+    }
+}
+
+/// `time < cursor`, or the same instant with a key that sorts after the
+/// cursor in a newest-first feed.
+fn keyset_condition<T, K>(
+    source: FeedSource,
+    cursor: Option<&FeedCursor>,
+    time: T,
+    key: K,
+) -> Condition
+where
+    T: ColumnTrait,
+    K: ColumnTrait,
+{
+    let Some(cursor) = cursor else {
+        return Condition::all();
+    };
+    let Some(at) = cursor.timestamp() else {
+        return Condition::all();
+    };
+    if source < cursor.source {
+        return Condition::all().add(time.lte(at));
+    }
+    if source > cursor.source {
+        return Condition::all().add(time.lt(at));
+    }
+    let before_key = match &cursor.key {
+        FeedKey::Id(id) => key.lt(*id),
+        FeedKey::Text(text) => key.lt(text.as_str()),
+    };
+    Condition::any()
+        .add(time.lt(at))
+        .add(Condition::all().add(time.eq(at)).add(before_key))
+}
+
+async fn transaction_entries<C: ConnectionTrait>(
+    db: &C,
+    scope: &FeedScope,
+    cursor: Option<&FeedCursor>,
+    fetch: u64,
+) -> Result<Vec<(FeedCursor, ActivityFeedItemView)>, DbErr> {
+    let addresses = scope.addresses();
+    let rows = chain_transaction::Entity::find()
+        .filter(
+            Condition::any()
+                .add(chain_transaction::Column::Sender.is_in(addresses.clone()))
+                .add(chain_transaction::Column::Recipient.is_in(addresses)),
+        )
+        .filter(keyset_condition(
+            FeedSource::Transaction,
+            cursor,
+            chain_transaction::Column::Timestamp,
+            chain_transaction::Column::TxId,
+        ))
+        .order_by_desc(chain_transaction::Column::Timestamp)
+        .order_by_desc(chain_transaction::Column::TxId)
+        .limit(fetch)
+        .all(db)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|tx| {
+            let outgoing = scope.wallets.contains_key(&tx.sender);
+            let (wallet, counterparty) = if outgoing {
+                (tx.sender.clone(), tx.recipient.clone())
+            } else {
+                (tx.recipient.clone(), tx.sender.clone())
+            };
+            let kind = match tx.transaction_type.as_str() {
+                "transfer" => ActivityKind::Transfer,
+                "contract_deployment" => ActivityKind::ContractDeployment,
+                _ => ActivityKind::Transaction,
+            };
+            let position = FeedCursor {
+                micros: tx.timestamp.timestamp_micros(),
+                source: FeedSource::Transaction,
+                key: FeedKey::Text(tx.tx_id.clone()),
+            };
+            let item = ActivityFeedItemView {
+                kind,
+                identity_id: scope.owner_of(&wallet),
+                wallet_address: Some(wallet),
+                counterparty: Some(counterparty),
+                amount: Some(tx.amount),
+                proposal_id: None,
+                detail: Some(tx.transaction_type),
+                tx_id: Some(tx.tx_id),
+                block_number: Some(tx.block_number),
+                occurred_at: tx.timestamp.timestamp(),
+            };
+            (position, item)
+        })
+        .collect())
+}
+
+async fn vote_entries<C: ConnectionTrait>(
+    db: &C,
+    scope: &FeedScope,
+    cursor: Option<&FeedCursor>,
+    fetch: u64,
+) -> Result<Vec<(FeedCursor, ActivityFeedItemView)>, DbErr> {
+    let rows = governance_vote::Entity::find()
+        .filter(governance_vote::Column::Voter.is_in(scope.addresses()))
+        .filter(keyset_condition(
+            FeedSource::Vote,
+            cursor,
+            governance_vote::Column::VotedAt,
+            governance_vote::Column::Id,
+        ))
+        .order_by_desc(governance_vote::Column::VotedAt)
+        .order_by_desc(governance_vote::Column::Id)
+        .limit(fetch)
+        .all(db)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|vote| {
+            let support = match vote.support {
+                0 => "against".to_string(),
+                1 => "for".to_string(),
+                2 => "abstain".to_string(),
+                other => other.to_string(),
+            };
+            let position = FeedCursor {
+                micros: vote.voted_at.timestamp_micros(),
+                source: FeedSource::Vote,
+                key: FeedKey::Id(vote.id),
+            };
+            let item = ActivityFeedItemView {
+                kind: ActivityKind::Vote,
+                identity_id: scope.owner_of(&vote.voter),
+                wallet_address: Some(vote.voter),
+                counterparty: None,
+                amount: Some(vote.weight),
+                proposal_id: Some(vote.proposal_id),
+                detail: Some(support),
+                tx_id: vote.tx_id,
+                block_number: vote.block_number,
+                occurred_at: vote.voted_at.timestamp(),
+            };
+            (position, item)
+        })
+        .collect())
+}
+
+async fn delegation_entries<C: ConnectionTrait>(
+    db: &C,
+    scope: &FeedScope,
+    cursor: Option<&FeedCursor>,
+    fetch: u64,
+) -> Result<Vec<(FeedCursor, ActivityFeedItemView)>, DbErr> {
+    let addresses = scope.addresses();
+    let rows = governance_delegation_event::Entity::find()
+        .filter(
+            Condition::any()
+                .add(governance_delegation_event::Column::Delegator.is_in(addresses.clone()))
+                .add(governance_delegation_event::Column::Delegatee.is_in(addresses)),
+        )
+        .filter(keyset_condition(
+            FeedSource::Delegation,
+            cursor,
+            governance_delegation_event::Column::OccurredAt,
+            governance_delegation_event::Column::Id,
+        ))
+        .order_by_desc(governance_delegation_event::Column::OccurredAt)
+        .order_by_desc(governance_delegation_event::Column::Id)
+        .limit(fetch)
+        .all(db)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|event| {
+            let (wallet, counterparty) = if scope.wallets.contains_key(&event.delegator) {
+                (event.delegator.clone(), event.delegatee.clone())
+            } else {
+                (event.delegatee.clone(), event.delegator.clone())
+            };
+            let position = FeedCursor {
+                micros: event.occurred_at.timestamp_micros(),
+                source: FeedSource::Delegation,
+                key: FeedKey::Id(event.id),
+            };
+            let item = ActivityFeedItemView {
+                kind: ActivityKind::Delegation,
+                identity_id: scope.owner_of(&wallet),
+                wallet_address: Some(wallet),
+                counterparty: Some(counterparty),
+                amount: Some(event.amount),
+                proposal_id: None,
+                detail: Some(event.event_type),
+                tx_id: Some(event.tx_id),
+                block_number: Some(event.block_number),
+                occurred_at: event.occurred_at.timestamp(),
+            };
+            (position, item)
+        })
+        .collect())
+}
+
+async fn faucet_entries<C: ConnectionTrait>(
+    db: &C,
+    scope: &FeedScope,
+    cursor: Option<&FeedCursor>,
+    fetch: u64,
+) -> Result<Vec<(FeedCursor, ActivityFeedItemView)>, DbErr> {
+    let rows = faucet_request::Entity::find()
+        .filter(faucet_request::Column::RecipientAddress.is_in(scope.addresses()))
+        .filter(keyset_condition(
+            FeedSource::Faucet,
+            cursor,
+            faucet_request::Column::CreatedAt,
+            faucet_request::Column::Id,
+        ))
+        .order_by_desc(faucet_request::Column::CreatedAt)
+        .order_by_desc(faucet_request::Column::Id)
+        .limit(fetch)
+        .all(db)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|drip| {
+            let position = FeedCursor {
+                micros: drip.created_at.timestamp_micros(),
+                source: FeedSource::Faucet,
+                key: FeedKey::Id(drip.id),
+            };
+            let item = ActivityFeedItemView {
+                kind: ActivityKind::FaucetDrip,
+                identity_id: scope.owner_of(&drip.recipient_address),
+                wallet_address: Some(drip.recipient_address),
+                counterparty: None,
+                amount: Some(drip.amount),
+                proposal_id: None,
+                detail: None,
+                tx_id: Some(drip.tx_hash),
+                block_number: None,
+                occurred_at: drip.created_at.timestamp(),
+            };
+            (position, item)
+        })
+        .collect())
+}
+
+/// The latest profile update of each identity in scope, filtered in memory
+/// since the scope already holds the rows.
+fn profile_entries(
+    scope: &FeedScope,
+    cursor: Option<&FeedCursor>,
+) -> Vec<(FeedCursor, ActivityFeedItemView)> {
+    scope
+        .profiles
+        .iter()
+        .map(|profile| {
+            let identity_id = encode_identity_id(&profile.identity_id);
+            let position = FeedCursor {
+                micros: profile.updated_at.saturating_mul(MICROS_PER_SECOND),
+                source: FeedSource::Profile,
+                key: FeedKey::Text(identity_id.clone()),
+            };
+            let item = ActivityFeedItemView {
+                kind: ActivityKind::ProfileUpdate,
+                identity_id,
+                wallet_address: None,
+                counterparty: None,
+                amount: None,
+                proposal_id: None,
+                detail: Some(profile.profile_version.to_string()),
+                tx_id: None,
+                block_number: Some(profile.last_synced_block),
+                occurred_at: profile.updated_at,
+            };
+            (position, item)
+        })
+        .filter(|(position, _)| cursor.is_none_or(|cursor| position < cursor))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(micros: i64, source: FeedSource, key: FeedKey) -> (FeedCursor, ActivityFeedItemView) {
+        let position = FeedCursor {
+            micros,
+            source,
+            key,
+        };
+        let item = ActivityFeedItemView {
+            kind: ActivityKind::Transaction,
+            identity_id: String::new(),
+            wallet_address: None,
+            counterparty: None,
+            amount: None,
+            proposal_id: None,
+            detail: Some(position.encode()),
+            tx_id: None,
+            block_number: None,
+            occurred_at: micros / MICROS_PER_SECOND,
+        };
+        (position, item)
+    }
+
+    #[test]
+    fn cursor_round_trips() {
+        let cursor = FeedCursor {
+            micros: 1_700_000_000_123_456,
+            source: FeedSource::Transaction,
+            key: FeedKey::Text("ab:cd".to_string()),
+        };
+        assert_eq!(FeedCursor::parse(&cursor.encode()), Some(cursor));
+
+        let cursor = FeedCursor {
+            micros: 5,
+            source: FeedSource::Vote,
+            key: FeedKey::Id(42),
+        };
+        assert_eq!(cursor.encode(), "5:vote:42");
+        assert_eq!(FeedCursor::parse("5:vote:42"), Some(cursor));
+    }
+
+    #[test]
+    fn cursor_rejects_malformed_values() {
+        assert!(FeedCursor::parse("").is_none());
+        assert!(FeedCursor::parse("5:vote").is_none());
+        assert!(FeedCursor::parse("5:vote:abc").is_none());
+        assert!(FeedCursor::parse("5:unknown:1").is_none());
+        assert!(FeedCursor::parse("x:tx:1").is_none());
+    }
+
+    #[test]
+    fn merge_orders_newest_first_and_pages() {
+        let entries = vec![
+            entry(10, FeedSource::Vote, FeedKey::Id(1)),
+            entry(30, FeedSource::Transaction, FeedKey::Text("a".to_string())),
+            entry(20, FeedSource::Delegation, FeedKey::Id(7)),
+            entry(20, FeedSource::Vote, FeedKey::Id(2)),
+        ];
+        let page = merge_entries(entries, 3);
+        let order = page
+            .items
+            .iter()
+            .map(|item| item.detail.clone().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(order, vec!["30:tx:a", "20:vote:2", "20:delegation:7"]);
+        assert_eq!(
+            page.next_cursor.map(|c| c.encode()).as_deref(),
+            Some("20:delegation:7")
+        );
+
+        let page = merge_entries(vec![entry(1, FeedSource::Faucet, FeedKey::Id(1))], 3);
+        assert_eq!(page.items.len(), 1);
+        assert!(page.next_cursor.is_none());
+    }
+}
@@ -0,0 +1,357 @@
+use sea_orm::{ConnectionTrait, DbBackend, DbErr, Statement};
+
+use crate::config::GovernanceConfig;
+use crate::models::governance::VoteTimelineBucket;
+
+use super::{ProposalTally, ProposalTimeline};
+
+pub const BASIS_POINTS: u32 = 10_000;
+pub const TIMELINE_BUCKET_SECONDS: i64 = 3_600;
+
+/// Per-hour, per-support vote totals for one proposal, computed in the
+/// database so the timeline covers every vote regardless of count.
+const VOTE_TIMELINE_SQL: &str = r#"
+SELECT
+  (FLOOR(EXTRACT(EPOCH FROM voted_at) / $2) * $2)::BIGINT AS hour_start,
+  support,
+  COUNT(*)::BIGINT AS votes_cast,
+  COALESCE(SUM(weight), 0)::BIGINT AS weight
+FROM governance_votes
+WHERE proposal_id = $1
+GROUP BY 1, 2
+ORDER BY 1, 2
+"#;
+
+/// Quorum and approval requirements applied to a proposal tally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuorumPolicy {
+    /// Absolute participation floor regardless of eligible power.
+    pub quorum_votes: i64,
+    /// Share of eligible voting power that must participate.
+    pub quorum_bps: u32,
+    /// Share of decisive (for + against) votes that must be in favour.
+    pub approval_threshold_bps: u32,
+}
+
+impl From<&GovernanceConfig> for QuorumPolicy {
+    fn from(config: &GovernanceConfig) -> Self {
+        Self {
+            quorum_votes: config.quorum_votes,
+            quorum_bps: config.quorum_bps,
+            approval_threshold_bps: config.approval_threshold_bps,
+        }
+    }
+}
+
+impl QuorumPolicy {
+    pub fn required_quorum(&self, eligible_power: i64) -> i64 {
+        assert!(eligible_power >= 0, "Eligible power cannot be negative");
+        assert!(
+            self.quorum_bps <= BASIS_POINTS,
+            "Quorum basis points exceed 100%"
+        );
+        let proportional = ceil_div(
+            i128::from(eligible_power) * i128::from(self.quorum_bps),
+            i128::from(BASIS_POINTS),
+        );
+        clamp_to_i64(proportional).max(self.quorum_votes)
+    }
+
+    pub fn quorum_reached(&self, tally: &ProposalTally, eligible_power: i64) -> bool {
+        tally.participation() >= self.required_quorum(eligible_power)
+    }
+
+    pub fn approval_reached(&self, tally: &ProposalTally) -> bool {
+        assert!(
+            self.approval_threshold_bps < BASIS_POINTS,
+            "Approval threshold must be below 100%"
+        );
+        let votes_for = i128::from(tally.votes_for);
+        let decisive = votes_for + i128::from(tally.votes_against);
+        votes_for * i128::from(BASIS_POINTS) > decisive * i128::from(self.approval_threshold_bps)
+    }
+
+    pub fn passes(&self, tally: &ProposalTally, eligible_power: i64) -> bool {
+        self.quorum_reached(tally, eligible_power) && self.approval_reached(tally)
+    }
+
+    /// Additional voting weight needed to flip the current outcome: against
+    /// votes for a passing proposal, for votes for a failing one.
+    pub fn margin_to_flip(&self, tally: &ProposalTally, eligible_power: i64) -> i64 {
+        let threshold = i128::from(self.approval_threshold_bps);
+        let scale = i128::from(BASIS_POINTS);
+        let votes_for = i128::from(tally.votes_for);
+        let votes_against = i128::from(tally.votes_against);
+
+        if self.passes(tally, eligible_power) {
+            if threshold == 0 {
+                // Any for vote passes a zero threshold; only removing votes would flip it.
+                return 0;
+            }
+            let shortfall = votes_for * scale - threshold * (votes_for + votes_against);
+            return clamp_to_i64(ceil_div(shortfall, threshold));
+        }
+
+        let quorum_gap =
+            i128::from(self.required_quorum(eligible_power)) - i128::from(tally.participation());
+        let approval_deficit = threshold * (votes_for + votes_against) - votes_for * scale;
+        let approval_gap = if approval_deficit < 0 {
+            0
+        } else {
+            approval_deficit / (scale - threshold) + 1
+        };
+        clamp_to_i64(quorum_gap.max(approval_gap).max(0))
+    }
+}
+
+/// Percentage of `part` in `whole`, reported as 0 when `whole` is empty.
+pub fn percentage(part: i64, whole: i64) -> f64 {
+    if whole <= 0 {
+        return 0.0;
+    }
+    ((part as f64) * 100.0 / (whole as f64)).clamp(0.0, 100.0)
+}
+
+/// Extrapolates the tally to the end of the voting window at the current
+/// vote rate, without exceeding the eligible voting power.
+pub fn project_tally(
+    tally: &ProposalTally,
+    timeline: &ProposalTimeline,
+    now: i64,
+    eligible_power: i64,
+) -> ProposalTally {
+    let duration = timeline.vote_end - timeline.vote_start;
+    if duration <= 0 {
+        return *tally;
+    }
+    let elapsed = now.clamp(timeline.vote_start, timeline.vote_end) - timeline.vote_start;
+    if elapsed <= 0 || elapsed >= duration {
+        return *tally;
+    }
+
+    let participation = i128::from(tally.participation());
+    if participation == 0 {
+        return *tally;
+    }
+
+    let mut projected_total = participation * i128::from(duration) / i128::from(elapsed);
+    if eligible_power > 0 {
+        projected_total = projected_total.min(i128::from(eligible_power).max(participation));
+    }
+
+    let scale = |votes: i64| clamp_to_i64(i128::from(votes) * projected_total / participation);
+    ProposalTally {
+        votes_for: scale(tally.votes_for),
+        votes_against: scale(tally.votes_against),
+        votes_abstain: scale(tally.votes_abstain),
+    }
+}
+
+/// Start of the hourly timeline bucket containing `timestamp`.
+pub fn timeline_bucket(timestamp: i64) -> i64 {
+    timestamp.div_euclid(TIMELINE_BUCKET_SECONDS) * TIMELINE_BUCKET_SECONDS
+}
+
+/// Loads the hourly vote timeline of a proposal.
+pub async fn load_vote_timeline<C: ConnectionTrait>(
+    db: &C,
+    proposal_id: i64,
+) -> Result<Vec<VoteTimelineBucket>, DbErr> {
+    let rows = db
+        .query_all(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            VOTE_TIMELINE_SQL,
+            [proposal_id.into(), TIMELINE_BUCKET_SECONDS.into()],
+        ))
+        .await?;
+
+    let mut totals = Vec::with_capacity(rows.len());
+    for row in rows {
+        totals.push((
+            row.try_get::<i64>("", "hour_start")?,
+            row.try_get::<i32>("", "support")?,
+            row.try_get::<i64>("", "votes_cast")?,
+            row.try_get::<i64>("", "weight")?,
+        ));
+    }
+    Ok(build_vote_timeline(totals))
+}
+
+/// Folds hourly totals into timeline buckets with running cumulative
+/// weights. `totals` yields `(hour_start, support, votes_cast, weight)` in
+/// chronological order, at most one entry per hour and support value.
+pub fn build_vote_timeline<I>(totals: I) -> Vec<VoteTimelineBucket>
+where
+    I: IntoIterator<Item = (i64, i32, i64, i64)>,
+{
+    let mut buckets: Vec<VoteTimelineBucket> = Vec::new();
+    let (mut cumulative_for, mut cumulative_against, mut cumulative_abstain) = (0i64, 0i64, 0i64);
+
+    for (hour_start, support, votes_cast, weight) in totals {
+        assert!(weight >= 0, "Vote weight cannot be negative");
+        assert!(votes_cast >= 0, "Vote count cannot be negative");
+        assert_eq!(
+            hour_start,
+            timeline_bucket(hour_start),
+            "Timeline totals must be aligned to hours"
+        );
+        match support {
+            0 => cumulative_against = cumulative_against.saturating_add(weight),
+            1 => cumulative_for = cumulative_for.saturating_add(weight),
+            2 => cumulative_abstain = cumulative_abstain.saturating_add(weight),
+            _ => continue,
+        }
+
+        let needs_bucket = buckets
+            .last()
+            .is_none_or(|bucket| bucket.hour_start != hour_start);
+        if needs_bucket {
+            assert!(
+                buckets
+                    .last()
+                    .is_none_or(|bucket| bucket.hour_start < hour_start),
+                "Votes must be supplied in chronological order"
+            );
+            buckets.push(VoteTimelineBucket {
+                hour_start,
+                ..VoteTimelineBucket::default()
+            });
+        }
+
+        let bucket = buckets.last_mut().expect("bucket exists");
+        bucket.votes_cast = bucket.votes_cast.saturating_add(votes_cast);
+        match support {
+            0 => bucket.weight_against = bucket.weight_against.saturating_add(weight),
+            1 => bucket.weight_for = bucket.weight_for.saturating_add(weight),
+            _ => bucket.weight_abstain = bucket.weight_abstain.saturating_add(weight),
+        }
+        bucket.cumulative_for = cumulative_for;
+        bucket.cumulative_against = cumulative_against;
+        bucket.cumulative_abstain = cumulative_abstain;
+    }
+
+    buckets
+}
+
+fn ceil_div(numerator: i128, denominator: i128) -> i128 {
+    assert!(denominator > 0, "Denominator must be positive");
+    if numerator <= 0 {
+        return numerator / denominator;
+    }
+    (numerator + denominator - 1) / denominator
+}
+
+fn clamp_to_i64(value: i128) -> i64 {
+    value.clamp(i128::from(i64::MIN), i128::from(i64::MAX)) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: QuorumPolicy = QuorumPolicy {
+        quorum_votes: 0,
+        quorum_bps: 1_000,
+        approval_threshold_bps: 5_000,
+    };
+
+    fn tally(votes_for: i64, votes_against: i64, votes_abstain: i64) -> ProposalTally {
+        ProposalTally {
+            votes_for,
+            votes_against,
+            votes_abstain,
+        }
+    }
+
+    #[test]
+    fn quorum_scales_with_eligible_power() {
+        assert_eq!(POLICY.required_quorum(1_000), 100);
+        assert_eq!(POLICY.required_quorum(1_001), 101);
+        let floor = QuorumPolicy {
+            quorum_votes: 500,
+            ..POLICY
+        };
+        assert_eq!(floor.required_quorum(1_000), 500);
+        assert!(POLICY.quorum_reached(&tally(50, 0, 50), 1_000));
+        assert!(!POLICY.quorum_reached(&tally(50, 0, 49), 1_000));
+    }
+
+    #[test]
+    fn approval_threshold_is_strict() {
+        assert!(!POLICY.approval_reached(&tally(50, 50, 0)));
+        assert!(POLICY.approval_reached(&tally(51, 50, 0)));
+        let supermajority = QuorumPolicy {
+            approval_threshold_bps: 6_667,
+            ..POLICY
+        };
+        assert!(!supermajority.approval_reached(&tally(66, 34, 0)));
+        assert!(supermajority.approval_reached(&tally(67, 33, 0)));
+    }
+
+    #[test]
+    fn margin_to_flip_passing_and_failing() {
+        // Passing 70/30: 40 more against votes reach a 70/70 tie, which fails.
+        let passing = tally(70, 30, 0);
+        assert!(POLICY.passes(&passing, 1_000));
+        assert_eq!(POLICY.margin_to_flip(&passing, 1_000), 40);
+
+        // Failing 30/70: 41 more for votes are needed for a strict majority.
+        let failing = tally(30, 70, 0);
+        assert_eq!(POLICY.margin_to_flip(&failing, 1_000), 41);
+
+        // Approved but short of quorum: the quorum gap dominates.
+        let thin = tally(20, 10, 0);
+        assert_eq!(POLICY.margin_to_flip(&thin, 1_000), 70);
+    }
+
+    #[test]
+    fn projection_extrapolates_vote_rate() {
+        let timeline = ProposalTimeline {
+            vote_start: 0,
+            vote_end: 100,
+            start_height: None,
+            end_height: None,
+            executed: false,
+        };
+        let projected = project_tally(&tally(10, 5, 5), &timeline, 25, 1_000);
+        assert_eq!(projected, tally(40, 20, 20));
+
+        let capped = project_tally(&tally(100, 50, 50), &timeline, 10, 1_000);
+        assert_eq!(capped.participation(), 1_000);
+
+        let finished = project_tally(&tally(10, 5, 5), &timeline, 200, 1_000);
+        assert_eq!(finished, tally(10, 5, 5));
+    }
+
+    #[test]
+    fn timeline_buckets_align_to_hours() {
+        assert_eq!(timeline_bucket(0), 0);
+        assert_eq!(timeline_bucket(3_599), 0);
+        assert_eq!(timeline_bucket(3_600), 3_600);
+        assert_eq!(timeline_bucket(7_321), 7_200);
+    }
+
+    #[test]
+    fn vote_timeline_accumulates_per_hour() {
+        let timeline = build_vote_timeline([
+            (0, 0, 1, 2),
+            (0, 1, 1, 5),
+            (3_600, 1, 1, 4),
+            (3_600, 2, 1, 1),
+            (7_200, 7, 3, 9),
+        ]);
+        assert_eq!(timeline.len(), 2);
+
+        assert_eq!(timeline[0].hour_start, 0);
+        assert_eq!(timeline[0].votes_cast, 2);
+        assert_eq!(timeline[0].weight_for, 5);
+        assert_eq!(timeline[0].weight_against, 2);
+
+        assert_eq!(timeline[1].hour_start, 3_600);
+        assert_eq!(timeline[1].votes_cast, 2);
+        assert_eq!(timeline[1].weight_for, 4);
+        assert_eq!(timeline[1].cumulative_for, 9);
+        assert_eq!(timeline[1].cumulative_against, 2);
+        assert_eq!(timeline[1].cumulative_abstain, 1);
+    }
+}
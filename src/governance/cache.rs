@@ -0,0 +1,182 @@
+use moka::future::Cache;
+use serde_json::Value;
+use tracing::warn;
+
+use crate::models::governance::ProposalState;
+
+const LIST_PREFIX: &str = "list";
+const DETAIL_PREFIX: &str = "proposal";
+const ANY: &str = "*";
+
+/// Shape of a cached governance read in `ApiCache::proposals`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProposalCacheKey {
+    List {
+        state: Option<ProposalState>,
+        proposer: Option<String>,
+        limit: u64,
+        offset: u64,
+    },
+    Detail(i64),
+}
+
+impl ProposalCacheKey {
+    pub fn encode(&self) -> String {
+        match self {
+            ProposalCacheKey::List {
+                state,
+                proposer,
+                limit,
+                offset,
+            } => format!(
+                "{LIST_PREFIX}|{}|{}|{limit}|{offset}",
+                state.map(|state| state.as_str()).unwrap_or(ANY),
+                proposer
+                    .as_deref()
+                    .map(escape_proposer)
+                    .unwrap_or_else(|| ANY.to_string()),
+            ),
+            ProposalCacheKey::Detail(proposal_id) => format!("{DETAIL_PREFIX}|{proposal_id}"),
+        }
+    }
+
+    pub fn decode(raw: &str) -> Option<Self> {
+        let mut parts = raw.split('|');
+        match parts.next()? {
+            DETAIL_PREFIX => {
+                let proposal_id = parts.next()?.parse().ok()?;
+                parts
+                    .next()
+                    .is_none()
+                    .then_some(ProposalCacheKey::Detail(proposal_id))
+            }
+            LIST_PREFIX => {
+                let state = match parts.next()? {
+                    ANY => None,
+                    other => Some(ProposalState::parse(other)?),
+                };
+                let proposer = match parts.next()? {
+                    ANY => None,
+                    other => Some(unescape_proposer(other)),
+                };
+                let limit = parts.next()?.parse().ok()?;
+                let offset = parts.next()?.parse().ok()?;
+                parts.next().is_none().then_some(ProposalCacheKey::List {
+                    state,
+                    proposer,
+                    limit,
+                    offset,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether a cached read of this shape could include `change`.
+    pub fn is_affected_by(&self, change: &ProposalChange) -> bool {
+        match self {
+            ProposalCacheKey::Detail(proposal_id) => *proposal_id == change.proposal_id,
+            ProposalCacheKey::List {
+                state, proposer, ..
+            } => {
+                let state_matches = match state {
+                    None => true,
+                    Some(state) => change.states.contains(state),
+                };
+                let proposer_matches = proposer
+                    .as_deref()
+                    .is_none_or(|proposer| proposer == change.proposer);
+                state_matches && proposer_matches
+            }
+        }
+    }
+}
+
+/// A write to one proposal. `states` holds the state before and after the
+/// write, which are equal when only tallies changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProposalChange {
+    pub proposal_id: i64,
+    pub proposer: String,
+    pub states: [ProposalState; 2],
+}
+
+/// Evicts the cached reads that could contain `change`, leaving unrelated
+/// lists and other proposals' details in place.
+pub fn invalidate_proposal(cache: &Cache<String, Value>, change: ProposalChange) {
+    let result = cache.invalidate_entries_if(move |key, _| {
+        ProposalCacheKey::decode(key).is_none_or(|key| key.is_affected_by(&change))
+    });
+    if let Err(err) = result {
+        warn!("Precise proposal cache invalidation unavailable, clearing cache: {err}");
+        cache.invalidate_all();
+    }
+}
+
+fn escape_proposer(proposer: &str) -> String {
+    proposer
+        .replace('%', "%25")
+        .replace('|', "%7C")
+        .replace('*', "%2A")
+}
+
+fn unescape_proposer(raw: &str) -> String {
+    raw.replace("%2A", "*")
+        .replace("%7C", "|")
+        .replace("%25", "%")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(states: [ProposalState; 2]) -> ProposalChange {
+        ProposalChange {
+            proposal_id: 7,
+            proposer: "alice".to_string(),
+            states,
+        }
+    }
+
+    #[test]
+    fn keys_round_trip() {
+        let keys = [
+            ProposalCacheKey::Detail(42),
+            ProposalCacheKey::List {
+                state: Some(ProposalState::Active),
+                proposer: Some("odd|name%*".to_string()),
+                limit: 50,
+                offset: 100,
+            },
+            ProposalCacheKey::List {
+                state: None,
+                proposer: None,
+                limit: 10,
+                offset: 0,
+            },
+        ];
+        for key in keys {
+            assert_eq!(ProposalCacheKey::decode(&key.encode()), Some(key));
+        }
+        assert_eq!(ProposalCacheKey::decode("proposal|x"), None);
+    }
+
+    #[test]
+    fn only_matching_shapes_are_affected() {
+        let transition = change([ProposalState::Active, ProposalState::Succeeded]);
+        let list = |state: Option<ProposalState>, proposer: Option<&str>| ProposalCacheKey::List {
+            state,
+            proposer: proposer.map(str::to_string),
+            limit: 50,
+            offset: 0,
+        };
+
+        assert!(ProposalCacheKey::Detail(7).is_affected_by(&transition));
+        assert!(!ProposalCacheKey::Detail(8).is_affected_by(&transition));
+        assert!(list(None, None).is_affected_by(&transition));
+        assert!(list(Some(ProposalState::Active), None).is_affected_by(&transition));
+        assert!(list(Some(ProposalState::Succeeded), Some("alice")).is_affected_by(&transition));
+        assert!(!list(Some(ProposalState::Defeated), None).is_affected_by(&transition));
+        assert!(!list(None, Some("bob")).is_affected_by(&transition));
+    }
+}
@@ -0,0 +1,459 @@
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Arc;
+
+use anyhow::{Context, anyhow};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use tracing::warn;
+
+use crate::config::GovernanceConfig;
+use crate::rpc::RpcClient;
+
+const MAX_CALLDATA_BYTES: usize = 64 * 1024;
+const MAX_ARGUMENTS: usize = 64;
+
+#[derive(Debug, Clone, thiserror::Error, PartialEq, Eq)]
+pub enum CalldataError {
+    #[error("calldata is empty")]
+    Empty,
+    #[error("calldata exceeds {MAX_CALLDATA_BYTES} bytes")]
+    TooLarge,
+    #[error("calldata is neither a JSON call nor hex-encoded JSON")]
+    Encoding,
+    #[error("calldata does not name a method")]
+    MissingMethod,
+    #[error("manifest does not describe method {0}")]
+    UnknownMethod(String),
+    #[error("method {method} expects {expected} arguments but calldata has {found}")]
+    ArgumentCount {
+        method: String,
+        expected: usize,
+        found: usize,
+    },
+    #[error("argument {name} is missing")]
+    MissingArgument { name: String },
+    #[error("argument {name} is not a valid {expected}")]
+    ArgumentType { name: String, expected: String },
+    #[error("argument {name} has unsupported type {kind}")]
+    UnsupportedType { name: String, kind: String },
+    #[error("manifest does not expose a method list: {0}")]
+    ManifestShape(String),
+    #[error("method {0} declares more than {MAX_ARGUMENTS} params")]
+    TooManyParams(String),
+}
+
+/// Callable surface of a contract, read from its deployment manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestAbi {
+    methods: Vec<AbiMethod>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AbiMethod {
+    pub name: String,
+    #[serde(default, alias = "inputs", alias = "args")]
+    pub params: Vec<AbiParam>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AbiParam {
+    pub name: String,
+    #[serde(rename = "type", alias = "ty", alias = "kind")]
+    pub kind: String,
+}
+
+/// A proposal target and the manifest recorded for its deployment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractTarget {
+    /// The deployed code hash matched the manifest's when checked at load.
+    pub verified: bool,
+    pub code_hash: String,
+    pub abi: ManifestAbi,
+}
+
+/// Proposal targets with published manifests, keyed by address.
+#[derive(Debug, Clone, Default)]
+pub struct ContractRegistry {
+    targets: Arc<HashMap<String, Arc<ContractTarget>>>,
+}
+
+impl ContractRegistry {
+    /// Reads every configured manifest and checks its code hash against the
+    /// contract deployed at the address. A missing or malformed manifest is
+    /// a configuration error; a mismatched or unreachable deployment leaves
+    /// the target unverified.
+    pub async fn load(config: &GovernanceConfig, rpc: &RpcClient) -> anyhow::Result<Self> {
+        let mut targets = HashMap::with_capacity(config.contracts.len());
+        for contract in &config.contracts {
+            let raw = fs::read_to_string(&contract.manifest_path)
+                .with_context(|| format!("Failed to read manifest {}", contract.manifest_path))?;
+            let manifest: Value = serde_json::from_str(&raw).with_context(|| {
+                format!("Manifest {} is not valid JSON", contract.manifest_path)
+            })?;
+            let abi = ManifestAbi::from_value(&manifest)
+                .map_err(|err| anyhow!("Manifest {}: {err}", contract.manifest_path))?;
+            let address = contract.address.trim();
+            let code_hash = contract.code_hash.trim().to_string();
+            let verified = match rpc.fetch_contract_code_hash(address).await {
+                Ok(deployed) => {
+                    let matches = same_code_hash(&deployed, &code_hash);
+                    if !matches {
+                        warn!(
+                            address,
+                            deployed = %deployed,
+                            "Deployed code does not match the manifest code hash"
+                        );
+                    }
+                    matches
+                }
+                Err(err) => {
+                    warn!(address, "Failed to fetch deployed code hash: {err:#}");
+                    false
+                }
+            };
+            let target = ContractTarget {
+                verified,
+                code_hash,
+                abi,
+            };
+            if targets
+                .insert(address.to_string(), Arc::new(target))
+                .is_some()
+            {
+                return Err(anyhow!(
+                    "Governed contract {} is configured twice",
+                    contract.address
+                ));
+            }
+        }
+        Ok(Self {
+            targets: Arc::new(targets),
+        })
+    }
+
+    pub fn get(&self, address: &str) -> Option<Arc<ContractTarget>> {
+        self.targets.get(address.trim()).cloned()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedCall {
+    pub method: String,
+    pub arguments: Vec<DecodedArgument>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedArgument {
+    pub name: String,
+    pub kind: String,
+    pub value: Value,
+}
+
+impl ManifestAbi {
+    /// Reads the `methods` list of a serialized manifest, also accepting the
+    /// list nested under `abi`.
+    pub fn from_value(manifest: &Value) -> Result<Self, CalldataError> {
+        let methods = manifest
+            .get("methods")
+            .or_else(|| manifest.get("abi").and_then(|abi| abi.get("methods")))
+            .ok_or_else(|| CalldataError::ManifestShape("missing methods".to_string()))?;
+        let methods: Vec<AbiMethod> = serde_json::from_value(methods.clone())
+            .map_err(|err| CalldataError::ManifestShape(err.to_string()))?;
+        if let Some(method) = methods
+            .iter()
+            .find(|method| method.params.len() > MAX_ARGUMENTS)
+        {
+            return Err(CalldataError::TooManyParams(method.name.clone()));
+        }
+        Ok(Self { methods })
+    }
+
+    pub fn method(&self, name: &str) -> Option<&AbiMethod> {
+        self.methods.iter().find(|method| method.name == name)
+    }
+}
+
+/// Decodes a proposal action's calldata against the target's manifest.
+///
+/// Calldata is a JSON call envelope `{"method": .., "args": ..}`, either
+/// verbatim or hex encoded (with or without a `0x` prefix). Arguments may be
+/// positional or keyed by parameter name.
+pub fn decode_calldata(abi: &ManifestAbi, calldata: &str) -> Result<DecodedCall, CalldataError> {
+    let envelope = parse_envelope(calldata)?;
+    let method_name = envelope
+        .get("method")
+        .and_then(Value::as_str)
+        .ok_or(CalldataError::MissingMethod)?;
+    let method = abi
+        .method(method_name)
+        .ok_or_else(|| CalldataError::UnknownMethod(method_name.to_string()))?;
+
+    let raw_args = envelope.get("args").cloned().unwrap_or(Value::Null);
+    let ordered = order_arguments(method, raw_args)?;
+
+    let mut arguments = Vec::with_capacity(method.params.len());
+    for (param, value) in method.params.iter().zip(ordered) {
+        let matches =
+            value_matches(&param.kind, &value).ok_or_else(|| CalldataError::UnsupportedType {
+                name: param.name.clone(),
+                kind: param.kind.clone(),
+            })?;
+        if !matches {
+            return Err(CalldataError::ArgumentType {
+                name: param.name.clone(),
+                expected: param.kind.clone(),
+            });
+        }
+        arguments.push(DecodedArgument {
+            name: param.name.clone(),
+            kind: param.kind.clone(),
+            value,
+        });
+    }
+
+    Ok(DecodedCall {
+        method: method.name.clone(),
+        arguments,
+    })
+}
+
+fn parse_envelope(calldata: &str) -> Result<Map<String, Value>, CalldataError> {
+    let trimmed = calldata.trim();
+    if trimmed.is_empty() {
+        return Err(CalldataError::Empty);
+    }
+    if trimmed.len() > MAX_CALLDATA_BYTES * 2 {
+        return Err(CalldataError::TooLarge);
+    }
+
+    if let Ok(Value::Object(map)) = serde_json::from_str::<Value>(trimmed) {
+        return Ok(map);
+    }
+
+    let hex_body = trimmed.strip_prefix("0x").unwrap_or(trimmed);
+    let bytes = hex::decode(hex_body).map_err(|_| CalldataError::Encoding)?;
+    if bytes.len() > MAX_CALLDATA_BYTES {
+        return Err(CalldataError::TooLarge);
+    }
+    match serde_json::from_slice::<Value>(&bytes) {
+        Ok(Value::Object(map)) => Ok(map),
+        _ => Err(CalldataError::Encoding),
+    }
+}
+
+fn order_arguments(method: &AbiMethod, raw: Value) -> Result<Vec<Value>, CalldataError> {
+    let expected = method.params.len();
+    if expected > MAX_ARGUMENTS {
+        return Err(CalldataError::TooManyParams(method.name.clone()));
+    }
+
+    match raw {
+        Value::Null => {
+            if expected == 0 {
+                Ok(Vec::new())
+            } else {
+                Err(CalldataError::ArgumentCount {
+                    method: method.name.clone(),
+                    expected,
+                    found: 0,
+                })
+            }
+        }
+        Value::Array(values) => {
+            if values.len() != expected {
+                return Err(CalldataError::ArgumentCount {
+                    method: method.name.clone(),
+                    expected,
+                    found: values.len(),
+                });
+            }
+            Ok(values)
+        }
+        Value::Object(mut named) => {
+            if named.len() != expected {
+                return Err(CalldataError::ArgumentCount {
+                    method: method.name.clone(),
+                    expected,
+                    found: named.len(),
+                });
+            }
+            method
+                .params
+                .iter()
+                .map(|param| {
+                    named
+                        .remove(&param.name)
+                        .ok_or_else(|| CalldataError::MissingArgument {
+                            name: param.name.clone(),
+                        })
+                })
+                .collect()
+        }
+        single => {
+            if expected != 1 {
+                return Err(CalldataError::ArgumentCount {
+                    method: method.name.clone(),
+                    expected,
+                    found: 1,
+                });
+            }
+            Ok(vec![single])
+        }
+    }
+}
+
+/// Compares code hashes as hex, ignoring case and a `0x` prefix.
+fn same_code_hash(left: &str, right: &str) -> bool {
+    let strip = |hash: &str| {
+        let hash = hash.trim();
+        hash.strip_prefix("0x").unwrap_or(hash).to_ascii_lowercase()
+    };
+    let left = strip(left);
+    !left.is_empty() && left == strip(right)
+}
+
+/// Checks a JSON argument against a manifest type name. Returns `None` for
+/// types the decoder does not understand, so they are never shown as
+/// decoded.
+fn value_matches(kind: &str, value: &Value) -> Option<bool> {
+    let kind = kind.trim().to_ascii_lowercase();
+    let element = kind
+        .strip_prefix("vec<")
+        .and_then(|inner| inner.strip_suffix('>'))
+        .or_else(|| kind.strip_suffix("[]"));
+    if let Some(element) = element {
+        // Probing with null reports unknown element types even when the
+        // array is empty.
+        value_matches(element, &Value::Null)?;
+        let Some(items) = value.as_array() else {
+            return Some(false);
+        };
+        let mut all = true;
+        for item in items {
+            all &= value_matches(element, item)?;
+        }
+        return Some(all);
+    }
+
+    let matches = match kind.as_str() {
+        "bool" => value.is_boolean(),
+        "string" => value.is_string(),
+        "address" => value.as_str().is_some_and(|s| !s.trim().is_empty()),
+        "bytes" => value
+            .as_str()
+            .is_some_and(|s| hex::decode(s.strip_prefix("0x").unwrap_or(s)).is_ok()),
+        unsigned if unsigned.starts_with('u') && unsigned[1..].parse::<u32>().is_ok() => {
+            value.is_u64() || value.as_str().is_some_and(|s| s.parse::<u128>().is_ok())
+        }
+        signed if signed.starts_with('i') && signed[1..].parse::<u32>().is_ok() => {
+            value.is_i64() || value.as_str().is_some_and(|s| s.parse::<i128>().is_ok())
+        }
+        _ => return None,
+    };
+    Some(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn abi() -> ManifestAbi {
+        ManifestAbi::from_value(&json!({
+            "name": "treasury",
+            "methods": [
+                {
+                    "name": "transfer",
+                    "params": [
+                        { "name": "to", "type": "address" },
+                        { "name": "amount", "type": "u128" }
+                    ]
+                },
+                { "name": "pause", "params": [] }
+            ]
+        }))
+        .expect("manifest")
+    }
+
+    #[test]
+    fn decodes_positional_and_hex_encoded_calls() {
+        let call = r#"{"method":"transfer","args":["addr1","1000"]}"#;
+        let decoded = decode_calldata(&abi(), call).expect("json call");
+        assert_eq!(decoded.method, "transfer");
+        assert_eq!(decoded.arguments[0].value, json!("addr1"));
+        assert_eq!(decoded.arguments[1].kind, "u128");
+
+        let hex_call = format!("0x{}", hex::encode(call));
+        assert_eq!(decode_calldata(&abi(), &hex_call), Ok(decoded));
+    }
+
+    #[test]
+    fn decodes_named_arguments_in_manifest_order() {
+        let call = r#"{"method":"transfer","args":{"amount":5,"to":"addr2"}}"#;
+        let decoded = decode_calldata(&abi(), call).expect("named call");
+        assert_eq!(decoded.arguments[0].name, "to");
+        assert_eq!(decoded.arguments[1].value, json!(5));
+    }
+
+    #[test]
+    fn rejects_unknown_methods_and_bad_arguments() {
+        assert_eq!(
+            decode_calldata(&abi(), r#"{"method":"mint"}"#),
+            Err(CalldataError::UnknownMethod("mint".to_string()))
+        );
+        assert!(matches!(
+            decode_calldata(&abi(), r#"{"method":"transfer","args":["addr1",-3]}"#),
+            Err(CalldataError::ArgumentType { .. })
+        ));
+        assert!(matches!(
+            decode_calldata(&abi(), r#"{"method":"transfer","args":["addr1"]}"#),
+            Err(CalldataError::ArgumentCount { .. })
+        ));
+        assert_eq!(decode_calldata(&abi(), "zz"), Err(CalldataError::Encoding));
+    }
+
+    #[test]
+    fn rejects_manifests_with_too_many_params() {
+        let params = (0..=MAX_ARGUMENTS)
+            .map(|index| json!({ "name": format!("p{index}"), "type": "u64" }))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ManifestAbi::from_value(&json!({
+                "methods": [{ "name": "wide", "params": params }]
+            })),
+            Err(CalldataError::TooManyParams("wide".to_string()))
+        );
+    }
+
+    #[test]
+    fn code_hashes_compare_as_hex() {
+        assert!(same_code_hash("0xABcd", "abcd"));
+        assert!(!same_code_hash("abcd", "abce"));
+        assert!(!same_code_hash("", "0x"));
+    }
+
+    #[test]
+    fn reports_unsupported_argument_types() {
+        let abi = ManifestAbi::from_value(&json!({
+            "methods": [
+                { "name": "configure", "params": [{ "name": "config", "type": "Settings" }] },
+                { "name": "batch", "params": [{ "name": "amounts", "type": "vec<u64>" }] }
+            ]
+        }))
+        .expect("manifest");
+        assert_eq!(
+            decode_calldata(&abi, r#"{"method":"configure","args":[{"fee":1}]}"#),
+            Err(CalldataError::UnsupportedType {
+                name: "config".to_string(),
+                kind: "Settings".to_string(),
+            })
+        );
+        assert!(decode_calldata(&abi, r#"{"method":"batch","args":[[1,2]]}"#).is_ok());
+        assert!(matches!(
+            decode_calldata(&abi, r#"{"method":"batch","args":[[1,-2]]}"#),
+            Err(CalldataError::ArgumentType { .. })
+        ));
+    }
+}
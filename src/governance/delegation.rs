@@ -0,0 +1,241 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, FixedOffset};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+
+use crate::entities::governance_delegation_event;
+use crate::models::governance::DelegationEventKind;
+
+const LEDGER_PAGE_SIZE: u64 = 5_000;
+
+/// A single ledger entry as seen by the current-state derivation.
+#[derive(Debug, Clone, Copy)]
+pub struct LedgerEntry<'a> {
+    pub kind: DelegationEventKind,
+    pub delegatee: &'a str,
+    pub previous_delegatee: Option<&'a str>,
+    pub amount: i64,
+    pub occurred_at: DateTime<FixedOffset>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DelegationPosition {
+    pub amount: i64,
+    pub updated_at: DateTime<FixedOffset>,
+}
+
+/// Replays one delegator's events in chain order and returns the open
+/// positions keyed by delegatee. Fully undelegated positions are dropped.
+pub fn derive_positions<'a, I>(entries: I) -> BTreeMap<String, DelegationPosition>
+where
+    I: IntoIterator<Item = LedgerEntry<'a>>,
+{
+    let mut positions: BTreeMap<String, DelegationPosition> = BTreeMap::new();
+
+    for entry in entries {
+        assert!(
+            entry.amount >= 0,
+            "Delegation event amount cannot be negative"
+        );
+        match entry.kind {
+            DelegationEventKind::Delegate | DelegationEventKind::Increase => {
+                credit(
+                    &mut positions,
+                    entry.delegatee,
+                    entry.amount,
+                    entry.occurred_at,
+                );
+            }
+            DelegationEventKind::Undelegate => {
+                debit(
+                    &mut positions,
+                    entry.delegatee,
+                    entry.amount,
+                    entry.occurred_at,
+                );
+            }
+            DelegationEventKind::Redelegate => {
+                let moved = match entry.previous_delegatee {
+                    Some(previous) => {
+                        debit(&mut positions, previous, entry.amount, entry.occurred_at)
+                    }
+                    None => entry.amount,
+                };
+                credit(&mut positions, entry.delegatee, moved, entry.occurred_at);
+            }
+        }
+    }
+
+    positions.retain(|_, position| position.amount > 0);
+    positions
+}
+
+/// Loads delegation events recorded at or before `up_to_block`, grouped by
+/// delegator and sorted into chain order. `delegators` restricts the load to
+/// those accounts when set.
+pub async fn load_ledger<C: ConnectionTrait>(
+    db: &C,
+    delegators: Option<&[String]>,
+    up_to_block: Option<i64>,
+) -> Result<BTreeMap<String, Vec<governance_delegation_event::Model>>, DbErr> {
+    let mut ledger: BTreeMap<String, Vec<governance_delegation_event::Model>> = BTreeMap::new();
+    if delegators.is_some_and(|delegators| delegators.is_empty()) {
+        return Ok(ledger);
+    }
+
+    let mut after = 0i64;
+    loop {
+        let mut query = governance_delegation_event::Entity::find()
+            .filter(governance_delegation_event::Column::Id.gt(after));
+        if let Some(delegators) = delegators {
+            query = query
+                .filter(governance_delegation_event::Column::Delegator.is_in(delegators.to_vec()));
+        }
+        if let Some(block) = up_to_block {
+            query = query.filter(governance_delegation_event::Column::BlockNumber.lte(block));
+        }
+        let page = query
+            .order_by_asc(governance_delegation_event::Column::Id)
+            .limit(LEDGER_PAGE_SIZE)
+            .all(db)
+            .await?;
+        let page_len = page.len() as u64;
+        for event in page {
+            after = event.id;
+            ledger
+                .entry(event.delegator.clone())
+                .or_default()
+                .push(event);
+        }
+        if page_len < LEDGER_PAGE_SIZE {
+            break;
+        }
+    }
+
+    for events in ledger.values_mut() {
+        events.sort_by_key(|event| (event.block_number, event.event_index, event.id));
+    }
+    Ok(ledger)
+}
+
+/// Replays stored events of a single delegator, skipping unknown kinds.
+pub fn positions_from_events(
+    events: &[governance_delegation_event::Model],
+) -> BTreeMap<String, DelegationPosition> {
+    derive_positions(events.iter().filter_map(|event| {
+        let kind = DelegationEventKind::parse(&event.event_type)?;
+        Some(LedgerEntry {
+            kind,
+            delegatee: &event.delegatee,
+            previous_delegatee: event.previous_delegatee.as_deref(),
+            amount: event.amount,
+            occurred_at: event.occurred_at,
+        })
+    }))
+}
+
+fn credit(
+    positions: &mut BTreeMap<String, DelegationPosition>,
+    delegatee: &str,
+    amount: i64,
+    occurred_at: DateTime<FixedOffset>,
+) {
+    let position = positions
+        .entry(delegatee.to_string())
+        .or_insert(DelegationPosition {
+            amount: 0,
+            updated_at: occurred_at,
+        });
+    position.amount = position.amount.saturating_add(amount);
+    position.updated_at = occurred_at;
+}
+
+/// Removes up to `amount` from a position and returns how much was removed.
+fn debit(
+    positions: &mut BTreeMap<String, DelegationPosition>,
+    delegatee: &str,
+    amount: i64,
+    occurred_at: DateTime<FixedOffset>,
+) -> i64 {
+    let Some(position) = positions.get_mut(delegatee) else {
+        return 0;
+    };
+    let removed = amount.min(position.amount);
+    position.amount -= removed;
+    position.updated_at = occurred_at;
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(seconds: i64) -> DateTime<FixedOffset> {
+        DateTime::from_timestamp(seconds, 0)
+            .expect("valid timestamp")
+            .fixed_offset()
+    }
+
+    fn entry<'a>(
+        kind: DelegationEventKind,
+        delegatee: &'a str,
+        previous_delegatee: Option<&'a str>,
+        amount: i64,
+        seconds: i64,
+    ) -> LedgerEntry<'a> {
+        LedgerEntry {
+            kind,
+            delegatee,
+            previous_delegatee,
+            amount,
+            occurred_at: at(seconds),
+        }
+    }
+
+    #[test]
+    fn delegate_increase_and_partial_undelegate() {
+        let positions = derive_positions([
+            entry(DelegationEventKind::Delegate, "val_a", None, 100, 1),
+            entry(DelegationEventKind::Increase, "val_a", None, 50, 2),
+            entry(DelegationEventKind::Undelegate, "val_a", None, 30, 3),
+        ]);
+        let position = positions.get("val_a").expect("position remains open");
+        assert_eq!(position.amount, 120);
+        assert_eq!(position.updated_at, at(3));
+    }
+
+    #[test]
+    fn full_undelegate_closes_position() {
+        let positions = derive_positions([
+            entry(DelegationEventKind::Delegate, "val_a", None, 100, 1),
+            entry(DelegationEventKind::Undelegate, "val_a", None, 250, 2),
+        ]);
+        assert!(positions.is_empty());
+    }
+
+    #[test]
+    fn redelegate_moves_only_available_stake() {
+        let positions = derive_positions([
+            entry(DelegationEventKind::Delegate, "val_a", None, 100, 1),
+            entry(
+                DelegationEventKind::Redelegate,
+                "val_b",
+                Some("val_a"),
+                60,
+                2,
+            ),
+            entry(
+                DelegationEventKind::Redelegate,
+                "val_c",
+                Some("val_a"),
+                60,
+                3,
+            ),
+        ]);
+        assert_eq!(positions.get("val_a"), None);
+        assert_eq!(positions.get("val_b").map(|p| p.amount), Some(60));
+        assert_eq!(positions.get("val_c").map(|p| p.amount), Some(40));
+    }
+}
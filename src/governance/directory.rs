@@ -0,0 +1,244 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+
+use crate::entities::{
+    governance_delegation, governance_proposal, governance_vote, identity_profile, wallet_link,
+};
+use crate::identity::{VISIBILITY_PUBLIC, encode_identity_id};
+use crate::models::governance::DelegateView;
+
+use super::participation_rate;
+
+/// Upper bound on delegates considered for the directory.
+pub const MAX_DIRECTORY_DELEGATES: u64 = 5_000;
+
+/// Orderings offered by the delegate directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DelegateRanking {
+    Delegation,
+    Participation,
+    Proposals,
+}
+
+impl DelegateRanking {
+    pub const ALL: [DelegateRanking; 3] = [
+        DelegateRanking::Delegation,
+        DelegateRanking::Participation,
+        DelegateRanking::Proposals,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DelegateRanking::Delegation => "delegation",
+            DelegateRanking::Participation => "participation",
+            DelegateRanking::Proposals => "proposals",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let normalized = value.trim().to_ascii_lowercase();
+        DelegateRanking::ALL
+            .into_iter()
+            .find(|ranking| ranking.as_str() == normalized)
+    }
+
+    /// Key of this ranking in the leaderboards cache.
+    pub fn cache_key(&self) -> String {
+        format!("governance:delegates:{}", self.as_str())
+    }
+}
+
+/// Loads every address that currently receives delegation, together with its
+/// voting record, authored proposals and verified identity link. Entries are
+/// returned unranked.
+pub async fn load_delegates<C: ConnectionTrait>(db: &C) -> Result<Vec<DelegateView>, DbErr> {
+    let received = governance_delegation::Entity::find()
+        .select_only()
+        .column(governance_delegation::Column::Delegatee)
+        .column_as(governance_delegation::Column::Amount.sum(), "total")
+        .column_as(
+            governance_delegation::Column::Delegator.count(),
+            "delegators",
+        )
+        .group_by(governance_delegation::Column::Delegatee)
+        .order_by_desc(governance_delegation::Column::Amount.sum())
+        .limit(MAX_DIRECTORY_DELEGATES)
+        .into_tuple::<(String, Option<i64>, i64)>()
+        .all(db)
+        .await?;
+
+    if received.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let addresses = received
+        .iter()
+        .map(|(address, _, _)| address.clone())
+        .collect::<Vec<_>>();
+
+    let votes = governance_vote::Entity::find()
+        .select_only()
+        .column(governance_vote::Column::Voter)
+        .column_as(governance_vote::Column::ProposalId.count(), "votes")
+        .column_as(governance_vote::Column::VotedAt.max(), "last_vote")
+        .filter(governance_vote::Column::Voter.is_in(addresses.clone()))
+        .group_by(governance_vote::Column::Voter)
+        .into_tuple::<(String, i64, Option<DateTimeWithTimeZone>)>()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|(voter, count, last)| (voter, (count, last)))
+        .collect::<BTreeMap<_, _>>();
+
+    let authored = governance_proposal::Entity::find()
+        .select_only()
+        .column(governance_proposal::Column::Proposer)
+        .column_as(governance_proposal::Column::ProposalId.count(), "proposals")
+        .filter(governance_proposal::Column::Proposer.is_in(addresses.clone()))
+        .group_by(governance_proposal::Column::Proposer)
+        .into_tuple::<(String, i64)>()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
+
+    let total_proposals = governance_proposal::Entity::find().count(db).await?;
+
+    // Only verified links may attach an identity to a delegate.
+    let identities = wallet_link::Entity::find()
+        .filter(wallet_link::Column::WalletAddress.is_in(addresses))
+        .filter(wallet_link::Column::VerifiedAt.is_not_null())
+        .find_also_related(identity_profile::Entity)
+        // Only public identities are named in the directory.
+        .filter(identity_profile::Column::StatsVisibility.eq(VISIBILITY_PUBLIC))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|(link, profile)| (link.wallet_address, (link.identity_id, profile)))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut delegates = Vec::with_capacity(received.len());
+    for (address, total, delegators) in received {
+        let (votes_cast, last_vote) = votes.get(&address).cloned().unwrap_or((0, None));
+        let (identity_id, display_name) = match identities.get(&address) {
+            Some((identity_id, profile)) => (
+                Some(encode_identity_id(identity_id)),
+                profile
+                    .as_ref()
+                    .and_then(|profile| profile.display_name.clone()),
+            ),
+            None => (None, None),
+        };
+
+        delegates.push(DelegateView {
+            rank: 0,
+            received_delegation: total.unwrap_or(0),
+            delegator_count: delegators,
+            votes_cast,
+            participation_rate: participation_rate(votes_cast.max(0) as u64, total_proposals),
+            proposals_authored: authored.get(&address).copied().unwrap_or(0),
+            last_vote_at: last_vote.map(|voted_at| voted_at.timestamp()),
+            identity_id,
+            display_name,
+            address,
+        });
+    }
+
+    Ok(delegates)
+}
+
+/// Sorts delegates by `ranking`, breaking ties with the remaining criteria,
+/// and assigns 1-based ranks.
+pub fn rank_delegates(delegates: &mut [DelegateView], ranking: DelegateRanking) {
+    let by_delegation =
+        |a: &DelegateView, b: &DelegateView| b.received_delegation.cmp(&a.received_delegation);
+    let by_participation =
+        |a: &DelegateView, b: &DelegateView| b.participation_rate.total_cmp(&a.participation_rate);
+    let by_proposals =
+        |a: &DelegateView, b: &DelegateView| b.proposals_authored.cmp(&a.proposals_authored);
+
+    delegates.sort_by(|a, b| {
+        let ordered = match ranking {
+            DelegateRanking::Delegation => by_delegation(a, b)
+                .then_with(|| by_participation(a, b))
+                .then_with(|| by_proposals(a, b)),
+            DelegateRanking::Participation => by_participation(a, b)
+                .then_with(|| by_delegation(a, b))
+                .then_with(|| by_proposals(a, b)),
+            DelegateRanking::Proposals => by_proposals(a, b)
+                .then_with(|| by_delegation(a, b))
+                .then_with(|| by_participation(a, b)),
+        };
+        match ordered {
+            Ordering::Equal => a.address.cmp(&b.address),
+            other => other,
+        }
+    });
+
+    for (index, delegate) in delegates.iter_mut().enumerate() {
+        delegate.rank = (index + 1) as u32;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delegate(address: &str, received: i64, rate: f64, proposals: i64) -> DelegateView {
+        DelegateView {
+            rank: 0,
+            address: address.to_string(),
+            identity_id: None,
+            display_name: None,
+            received_delegation: received,
+            delegator_count: 1,
+            votes_cast: 0,
+            participation_rate: rate,
+            proposals_authored: proposals,
+            last_vote_at: None,
+        }
+    }
+
+    #[test]
+    fn ranks_by_selected_criterion_with_tiebreaks() {
+        let mut delegates = vec![
+            delegate("a", 100, 0.2, 0),
+            delegate("b", 300, 0.1, 1),
+            delegate("c", 100, 0.9, 3),
+        ];
+
+        rank_delegates(&mut delegates, DelegateRanking::Delegation);
+        let order = delegates
+            .iter()
+            .map(|d| d.address.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(order, ["b", "c", "a"]);
+        assert_eq!(delegates[0].rank, 1);
+
+        rank_delegates(&mut delegates, DelegateRanking::Participation);
+        assert_eq!(delegates[0].address, "c");
+
+        rank_delegates(&mut delegates, DelegateRanking::Proposals);
+        let order = delegates
+            .iter()
+            .map(|d| d.address.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(order, ["c", "b", "a"]);
+        assert_eq!(delegates[2].rank, 3);
+    }
+
+    #[test]
+    fn parses_ranking_names() {
+        assert_eq!(
+            DelegateRanking::parse(" Participation "),
+            Some(DelegateRanking::Participation)
+        );
+        assert_eq!(DelegateRanking::parse("votes"), None);
+    }
+}
@@ -0,0 +1,408 @@
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::entities::{governance_delegation_event, governance_proposal, governance_vote};
+
+/// Rows fetched per database round trip while streaming an export.
+pub const EXPORT_PAGE_SIZE: u64 = 1_000;
+
+const CSV_COLUMNS: [&str; 11] = [
+    "activity",
+    "proposal_id",
+    "actor",
+    "counterparty",
+    "detail",
+    "amount",
+    "reason",
+    "block_number",
+    "tx_id",
+    "occurred_at",
+    "event_index",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Ndjson,
+}
+
+impl ExportFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "ndjson" | "jsonl" => Some(ExportFormat::Ndjson),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// One governance action in an export, flattened so votes, proposals and
+/// delegation events share a layout. Every field comes from the chain event
+/// the row was indexed from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ActivityRecord {
+    pub activity: &'static str,
+    pub proposal_id: Option<i64>,
+    pub actor: String,
+    pub counterparty: Option<String>,
+    pub detail: Option<String>,
+    pub amount: Option<i64>,
+    pub reason: Option<String>,
+    pub block_number: Option<i64>,
+    pub tx_id: Option<String>,
+    pub occurred_at: i64,
+    pub event_index: Option<i64>,
+}
+
+impl ActivityRecord {
+    fn from_vote(vote: governance_vote::Model) -> Self {
+        let support = match vote.support {
+            0 => "against".to_string(),
+            1 => "for".to_string(),
+            2 => "abstain".to_string(),
+            other => other.to_string(),
+        };
+        Self {
+            activity: "vote",
+            proposal_id: Some(vote.proposal_id),
+            actor: vote.voter,
+            counterparty: None,
+            detail: Some(support),
+            amount: Some(vote.weight),
+            reason: vote.reason,
+            block_number: vote.block_number,
+            tx_id: vote.tx_id,
+            occurred_at: vote.voted_at.timestamp(),
+            event_index: None,
+        }
+    }
+
+    fn from_proposal(proposal: governance_proposal::Model) -> Self {
+        Self {
+            activity: "proposal",
+            proposal_id: Some(proposal.proposal_id),
+            actor: proposal.proposer,
+            counterparty: None,
+            detail: None,
+            amount: None,
+            reason: None,
+            block_number: proposal.block_number,
+            tx_id: proposal.tx_id,
+            occurred_at: proposal.created_at.timestamp(),
+            event_index: None,
+        }
+    }
+
+    fn from_delegation(event: governance_delegation_event::Model) -> Self {
+        Self {
+            activity: "delegation",
+            proposal_id: None,
+            actor: event.delegator,
+            counterparty: Some(event.delegatee),
+            detail: Some(event.event_type),
+            amount: Some(event.amount),
+            reason: None,
+            block_number: Some(event.block_number),
+            tx_id: Some(event.tx_id),
+            occurred_at: event.occurred_at.timestamp(),
+            event_index: Some(i64::from(event.event_index)),
+        }
+    }
+
+    fn csv_line(&self) -> String {
+        let optional = |value: Option<i64>| value.map(|v| v.to_string()).unwrap_or_default();
+        let fields = [
+            self.activity.to_string(),
+            optional(self.proposal_id),
+            self.actor.clone(),
+            self.counterparty.clone().unwrap_or_default(),
+            self.detail.clone().unwrap_or_default(),
+            optional(self.amount),
+            self.reason.clone().unwrap_or_default(),
+            optional(self.block_number),
+            self.tx_id.clone().unwrap_or_default(),
+            self.occurred_at.to_string(),
+            optional(self.event_index),
+        ];
+        let mut line = fields
+            .iter()
+            .map(|field| csv_escape(field))
+            .collect::<Vec<_>>()
+            .join(",");
+        line.push('\n');
+        line
+    }
+}
+
+/// What an export covers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportSource {
+    /// Votes on one proposal, leaving out voters the caller may not see.
+    ProposalVotes {
+        proposal_id: i64,
+        hidden_voters: Vec<String>,
+    },
+    /// Votes, authored proposals and delegation events of one address, in
+    /// that order.
+    Address(String),
+}
+
+/// Position of a paginated export. Each section is walked by ascending id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportCursor {
+    Votes { after: i64 },
+    Proposals { after: i64 },
+    Delegations { after: i64 },
+    Done,
+}
+
+impl ExportCursor {
+    pub const START: ExportCursor = ExportCursor::Votes { after: i64::MIN };
+}
+
+/// Fetches the next page of `source` and the cursor that follows it.
+pub async fn next_page<C: ConnectionTrait>(
+    db: &C,
+    source: &ExportSource,
+    cursor: ExportCursor,
+) -> Result<(Vec<ActivityRecord>, ExportCursor), DbErr> {
+    match cursor {
+        ExportCursor::Votes { after } => {
+            let filter = match source {
+                ExportSource::ProposalVotes {
+                    proposal_id,
+                    hidden_voters,
+                } => {
+                    let mut filter =
+                        Condition::all().add(governance_vote::Column::ProposalId.eq(*proposal_id));
+                    if !hidden_voters.is_empty() {
+                        filter = filter
+                            .add(governance_vote::Column::Voter.is_not_in(hidden_voters.clone()));
+                    }
+                    filter
+                }
+                ExportSource::Address(address) => {
+                    Condition::all().add(governance_vote::Column::Voter.eq(address.as_str()))
+                }
+            };
+            let votes = governance_vote::Entity::find()
+                .filter(filter)
+                .filter(governance_vote::Column::Id.gt(after))
+                .order_by_asc(governance_vote::Column::Id)
+                .limit(EXPORT_PAGE_SIZE)
+                .all(db)
+                .await?;
+            let next = match (votes.last(), source) {
+                (Some(last), _) if votes.len() as u64 == EXPORT_PAGE_SIZE => {
+                    ExportCursor::Votes { after: last.id }
+                }
+                (_, ExportSource::ProposalVotes { .. }) => ExportCursor::Done,
+                (_, ExportSource::Address(_)) => ExportCursor::Proposals { after: i64::MIN },
+            };
+            Ok((
+                votes.into_iter().map(ActivityRecord::from_vote).collect(),
+                next,
+            ))
+        }
+        ExportCursor::Proposals { after } => {
+            let ExportSource::Address(address) = source else {
+                return Ok((Vec::new(), ExportCursor::Done));
+            };
+            let proposals = governance_proposal::Entity::find()
+                .filter(governance_proposal::Column::Proposer.eq(address.as_str()))
+                .filter(governance_proposal::Column::ProposalId.gt(after))
+                .order_by_asc(governance_proposal::Column::ProposalId)
+                .limit(EXPORT_PAGE_SIZE)
+                .all(db)
+                .await?;
+            let next = match proposals.last() {
+                Some(last) if proposals.len() as u64 == EXPORT_PAGE_SIZE => {
+                    ExportCursor::Proposals {
+                        after: last.proposal_id,
+                    }
+                }
+                _ => ExportCursor::Delegations { after: i64::MIN },
+            };
+            Ok((
+                proposals
+                    .into_iter()
+                    .map(ActivityRecord::from_proposal)
+                    .collect(),
+                next,
+            ))
+        }
+        ExportCursor::Delegations { after } => {
+            let ExportSource::Address(address) = source else {
+                return Ok((Vec::new(), ExportCursor::Done));
+            };
+            let events = governance_delegation_event::Entity::find()
+                .filter(
+                    Condition::any()
+                        .add(governance_delegation_event::Column::Delegator.eq(address.as_str()))
+                        .add(governance_delegation_event::Column::Delegatee.eq(address.as_str())),
+                )
+                .filter(governance_delegation_event::Column::Id.gt(after))
+                .order_by_asc(governance_delegation_event::Column::Id)
+                .limit(EXPORT_PAGE_SIZE)
+                .all(db)
+                .await?;
+            let next = match events.last() {
+                Some(last) if events.len() as u64 == EXPORT_PAGE_SIZE => {
+                    ExportCursor::Delegations { after: last.id }
+                }
+                _ => ExportCursor::Done,
+            };
+            Ok((
+                events
+                    .into_iter()
+                    .map(ActivityRecord::from_delegation)
+                    .collect(),
+                next,
+            ))
+        }
+        ExportCursor::Done => Ok((Vec::new(), ExportCursor::Done)),
+    }
+}
+
+/// Serializes export rows while hashing them.
+///
+/// The digest is SHA-256 over the bytes of every data row exactly as
+/// written, header and trailer excluded. Rows hold chain fields only, so a
+/// verifier can rebuild each one from the referenced transaction and hash
+/// them in the order received.
+pub struct ExportEncoder {
+    format: ExportFormat,
+    hasher: Sha256,
+    rows: u64,
+}
+
+impl ExportEncoder {
+    pub fn new(format: ExportFormat) -> Self {
+        Self {
+            format,
+            hasher: Sha256::new(),
+            rows: 0,
+        }
+    }
+
+    pub fn header(&self) -> Option<String> {
+        match self.format {
+            ExportFormat::Csv => Some(format!("{}\n", CSV_COLUMNS.join(","))),
+            ExportFormat::Ndjson => None,
+        }
+    }
+
+    pub fn encode(&mut self, records: &[ActivityRecord]) -> Result<String, serde_json::Error> {
+        let mut chunk = String::new();
+        for record in records {
+            let line = match self.format {
+                ExportFormat::Csv => record.csv_line(),
+                ExportFormat::Ndjson => {
+                    let mut line = serde_json::to_string(record)?;
+                    line.push('\n');
+                    line
+                }
+            };
+            self.hasher.update(line.as_bytes());
+            self.rows += 1;
+            chunk.push_str(&line);
+        }
+        Ok(chunk)
+    }
+
+    /// Closing line carrying the row count and digest.
+    pub fn finish(self) -> String {
+        let digest = hex::encode(self.hasher.finalize());
+        match self.format {
+            ExportFormat::Csv => format!("# rows={} sha256={digest}\n", self.rows),
+            ExportFormat::Ndjson => format!(
+                "{}\n",
+                serde_json::json!({
+                    "activity": "digest",
+                    "algorithm": "sha256",
+                    "rows": self.rows,
+                    "digest": digest,
+                })
+            ),
+        }
+    }
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(reason: Option<&str>) -> ActivityRecord {
+        ActivityRecord {
+            activity: "vote",
+            proposal_id: Some(3),
+            actor: "alice".to_string(),
+            counterparty: None,
+            detail: Some("for".to_string()),
+            amount: Some(40),
+            reason: reason.map(str::to_string),
+            block_number: Some(12),
+            tx_id: Some("0xabc".to_string()),
+            occurred_at: 1_700_000_000,
+            event_index: None,
+        }
+    }
+
+    #[test]
+    fn csv_rows_escape_separators() {
+        let line = record(Some("yes, \"strongly\"")).csv_line();
+        assert_eq!(
+            line,
+            "vote,3,alice,,for,40,\"yes, \"\"strongly\"\"\",12,0xabc,1700000000,\n"
+        );
+    }
+
+    #[test]
+    fn digest_covers_data_rows_only() {
+        let mut encoder = ExportEncoder::new(ExportFormat::Csv);
+        let rows = encoder.encode(&[record(None)]).expect("encode");
+        let trailer = encoder.finish();
+
+        let expected = hex::encode(Sha256::digest(rows.as_bytes()));
+        assert_eq!(trailer, format!("# rows=1 sha256={expected}\n"));
+    }
+
+    #[test]
+    fn ndjson_trailer_is_a_json_object() {
+        let mut encoder = ExportEncoder::new(ExportFormat::Ndjson);
+        assert!(encoder.header().is_none());
+        encoder
+            .encode(&[record(None), record(None)])
+            .expect("encode");
+        let trailer: serde_json::Value =
+            serde_json::from_str(encoder.finish().trim_end()).expect("json");
+        assert_eq!(trailer["rows"], 2);
+        assert_eq!(trailer["algorithm"], "sha256");
+    }
+}
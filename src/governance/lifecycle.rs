@@ -0,0 +1,354 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, QuerySelect,
+};
+use tokio::sync::watch;
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+use crate::config::GovernanceConfig;
+use crate::entities::governance_proposal;
+use crate::models::governance::{GovernanceNotificationView, ProposalState};
+use crate::state::ApiCache;
+
+use super::cache::{ProposalChange, invalidate_proposal};
+use super::notifier::{
+    GovernanceNotifier, due_events, load_notification_cursor, record_and_publish,
+    store_notification_cursor,
+};
+use super::snapshot::capture_snapshot;
+use super::{
+    ChainClock, LifecycleRules, ProposalTally, ProposalTimeline, eligible_voting_power,
+    evaluate_state, first_block_at_or_after, load_chain_clock,
+};
+
+const LIFECYCLE_PAGE_SIZE: u64 = 1_000;
+const MAX_LIFECYCLE_PAGES: usize = 1_000;
+
+/// Background task that moves proposals between lifecycle states as the
+/// indexed chain advances.
+pub struct ProposalLifecycle {
+    database: DatabaseConnection,
+    config: GovernanceConfig,
+    cache: Arc<ApiCache>,
+    notifier: GovernanceNotifier,
+    /// Tallies and state last seen per open proposal, used to spot writes
+    /// made outside this task. Proposals missing here are treated as changed.
+    observed: HashMap<i64, ProposalFingerprint>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ProposalFingerprint {
+    tally: ProposalTally,
+    state: String,
+}
+
+/// Working state shared by every proposal evaluated in one tick.
+struct TickState {
+    clock: ChainClock,
+    rules: LifecycleRules,
+    /// Eligible power at the tick's height, computed on first use.
+    current_eligible: Option<i64>,
+    /// Eligible power captured per snapshot height during this tick.
+    captured: HashMap<u64, i64>,
+    observed: HashMap<i64, ProposalFingerprint>,
+    notifications: Vec<GovernanceNotificationView>,
+    updated: usize,
+}
+
+impl ProposalLifecycle {
+    pub fn new(
+        database: DatabaseConnection,
+        config: GovernanceConfig,
+        cache: Arc<ApiCache>,
+        notifier: GovernanceNotifier,
+    ) -> Self {
+        assert!(
+            config.execution_grace_seconds > 0,
+            "Execution grace period must be positive"
+        );
+        Self {
+            database,
+            config,
+            cache,
+            notifier,
+            observed: HashMap::new(),
+        }
+    }
+
+    pub async fn run(mut self, mut shutdown: watch::Receiver<bool>) -> Result<()> {
+        info!("Starting proposal lifecycle loop");
+        loop {
+            tokio::select! {
+                changed = shutdown.changed() => {
+                    match changed {
+                        Ok(_) => {
+                            if *shutdown.borrow() {
+                                info!("Proposal lifecycle shutdown signal received");
+                                break;
+                            }
+                        }
+                        Err(_) => {
+                            warn!("Shutdown channel closed unexpectedly. Exiting lifecycle loop");
+                            break;
+                        }
+                    }
+                }
+                _ = sleep(self.config.lifecycle_interval()) => {
+                    if let Err(err) = self.tick().await {
+                        warn!("Proposal lifecycle tick failed: {err:#}");
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn tick(&mut self) -> Result<usize> {
+        let Some(clock) = load_chain_clock(&self.database)
+            .await
+            .context("Failed to load latest indexed block")?
+        else {
+            debug!("No indexed blocks yet; skipping proposal lifecycle tick");
+            return Ok(0);
+        };
+
+        let open_states = ProposalState::ALL
+            .into_iter()
+            .filter(|state| !state.is_terminal())
+            .map(|state| state.as_str())
+            .collect::<Vec<_>>();
+
+        let mut tick = TickState {
+            clock,
+            rules: LifecycleRules::from(&self.config),
+            current_eligible: None,
+            captured: HashMap::new(),
+            observed: HashMap::with_capacity(self.observed.len()),
+            notifications: Vec::new(),
+            updated: 0,
+        };
+
+        // Keyset pages over every open proposal so a backlog of old proposals
+        // cannot keep newer ones from being evaluated.
+        let mut after: Option<i64> = None;
+        for _ in 0..MAX_LIFECYCLE_PAGES {
+            let mut query = governance_proposal::Entity::find()
+                .filter(governance_proposal::Column::State.is_in(open_states.clone()));
+            if let Some(last) = after {
+                query = query.filter(governance_proposal::Column::ProposalId.gt(last));
+            }
+            let proposals = query
+                .order_by_asc(governance_proposal::Column::ProposalId)
+                .limit(LIFECYCLE_PAGE_SIZE)
+                .all(&self.database)
+                .await
+                .context("Failed to load open governance proposals")?;
+            let page_len = proposals.len() as u64;
+            after = proposals.last().map(|proposal| proposal.proposal_id);
+
+            for proposal in proposals {
+                self.evaluate(proposal, &mut tick).await?;
+            }
+            if page_len < LIFECYCLE_PAGE_SIZE {
+                break;
+            }
+        }
+
+        self.observed = tick.observed;
+
+        let announce = load_notification_cursor(&self.database)
+            .await
+            .context("Failed to load governance notification cursor")?
+            .is_some();
+        let recorded =
+            record_and_publish(&self.database, &self.notifier, tick.notifications, announce)
+                .await
+                .context("Failed to record governance notifications")?;
+        store_notification_cursor(&self.database, clock.height as i64)
+            .await
+            .context("Failed to store governance notification cursor")?;
+        if !announce {
+            info!("Seeded {recorded} past governance notifications without announcing them");
+        } else if recorded > 0 {
+            debug!("Published {recorded} governance notifications");
+        }
+
+        Ok(tick.updated)
+    }
+
+    /// Evaluates one open proposal against the tick's clock and persists any
+    /// transition.
+    async fn evaluate(
+        &self,
+        proposal: governance_proposal::Model,
+        tick: &mut TickState,
+    ) -> Result<()> {
+        let clock = tick.clock;
+        let current = ProposalState::parse(&proposal.state).unwrap_or_else(|| {
+            warn!(
+                proposal_id = proposal.proposal_id,
+                "Unknown stored proposal state {}; treating as pending", proposal.state
+            );
+            ProposalState::Pending
+        });
+
+        if proposal.vote_start > proposal.vote_end {
+            warn!(
+                proposal_id = proposal.proposal_id,
+                "Skipping proposal with inverted voting window"
+            );
+            return Ok(());
+        }
+
+        let start_height = match proposal.snapshot_block {
+            Some(block) => u64::try_from(block).ok(),
+            None if clock.timestamp >= proposal.vote_start => {
+                first_block_at_or_after(&self.database, proposal.vote_start)
+                    .await
+                    .context("Failed to resolve voting start height")?
+            }
+            None => None,
+        };
+        let end_height = if clock.timestamp > proposal.vote_end {
+            first_block_at_or_after(&self.database, proposal.vote_end.saturating_add(1))
+                .await
+                .context("Failed to resolve voting end height")?
+        } else {
+            None
+        };
+
+        // Voting power is frozen at the first block inside the voting window,
+        // once the indexed chain has reached it.
+        let snapshot_height = start_height
+            .filter(|height| proposal.snapshot_block.is_none() && clock.height >= *height);
+        let snapshot_eligible = match snapshot_height {
+            Some(height) => Some(match tick.captured.get(&height) {
+                Some(eligible) => *eligible,
+                None => {
+                    let eligible = capture_snapshot(&self.database, height)
+                        .await
+                        .with_context(|| {
+                            format!("Failed to capture voting power at block {height}")
+                        })?;
+                    tick.captured.insert(height, eligible);
+                    eligible
+                }
+            }),
+            None => proposal.snapshot_eligible_power,
+        };
+
+        let timeline = ProposalTimeline {
+            vote_start: proposal.vote_start,
+            vote_end: proposal.vote_end,
+            start_height,
+            end_height,
+            executed: proposal.executed_at.is_some(),
+        };
+        let tally = ProposalTally {
+            votes_for: proposal.votes_for,
+            votes_against: proposal.votes_against,
+            votes_abstain: proposal.votes_abstain,
+        };
+        let fingerprint = ProposalFingerprint {
+            tally,
+            state: proposal.state.clone(),
+        };
+        // Anything not seen in this exact shape last tick, including new
+        // proposals and those beyond the previous page, may be cached stale.
+        if self.observed.get(&proposal.proposal_id) != Some(&fingerprint) {
+            invalidate_proposal(
+                &self.cache.proposals,
+                ProposalChange {
+                    proposal_id: proposal.proposal_id,
+                    proposer: proposal.proposer.clone(),
+                    states: [current, current],
+                },
+            );
+        }
+
+        let eligible = match snapshot_eligible.or(tick.current_eligible) {
+            Some(eligible) => eligible,
+            None => {
+                let eligible = eligible_voting_power(&self.database, clock.height)
+                    .await
+                    .context("Failed to load eligible voting power")?;
+                tick.current_eligible = Some(eligible);
+                eligible
+            }
+        };
+        let next = evaluate_state(current, &timeline, &tally, eligible, &clock, &tick.rules);
+        tick.notifications.extend(
+            due_events(
+                &timeline,
+                next,
+                &clock,
+                self.config.notifications.closing_soon_seconds,
+            )
+            .into_iter()
+            .map(|kind| GovernanceNotificationView {
+                proposal_id: proposal.proposal_id,
+                kind,
+                proposer: proposal.proposer.clone(),
+                state: next,
+                vote_end: proposal.vote_end,
+                block_number: clock.height as i64,
+                emitted_at: clock.timestamp,
+            }),
+        );
+
+        if next == current && proposal.state == current.as_str() && snapshot_height.is_none() {
+            tick.observed.insert(proposal.proposal_id, fingerprint);
+            return Ok(());
+        }
+
+        let proposal_id = proposal.proposal_id;
+        let proposer = proposal.proposer.clone();
+        let mut model = proposal.into_active_model();
+        if let Some(height) = snapshot_height {
+            model.snapshot_block = Set(Some(height as i64));
+            model.snapshot_eligible_power = Set(snapshot_eligible);
+        }
+        model.state = Set(next.as_str().to_string());
+        model.updated_at = Set(Utc::now().fixed_offset());
+        model
+            .update(&self.database)
+            .await
+            .with_context(|| format!("Failed to update state of proposal {proposal_id}"))?;
+
+        invalidate_proposal(
+            &self.cache.proposals,
+            ProposalChange {
+                proposal_id,
+                proposer,
+                states: [current, next],
+            },
+        );
+        tick.observed.insert(
+            proposal_id,
+            ProposalFingerprint {
+                tally,
+                state: next.as_str().to_string(),
+            },
+        );
+
+        if next != current {
+            info!(
+                proposal_id,
+                block = clock.height,
+                "Proposal transitioned from {} to {}",
+                current.as_str(),
+                next.as_str()
+            );
+        }
+        tick.updated += 1;
+        Ok(())
+    }
+}
@@ -0,0 +1,409 @@
+pub mod analytics;
+pub mod cache;
+pub mod calldata;
+pub mod delegation;
+pub mod directory;
+pub mod export;
+pub mod lifecycle;
+pub mod notifier;
+pub mod snapshot;
+pub mod webhooks;
+
+use chrono::DateTime;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+
+use crate::config::GovernanceConfig;
+use crate::entities::chain_block;
+use crate::models::governance::ProposalState;
+
+use self::analytics::QuorumPolicy;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProposalTally {
+    pub votes_for: i64,
+    pub votes_against: i64,
+    pub votes_abstain: i64,
+}
+
+impl ProposalTally {
+    pub fn participation(&self) -> i64 {
+        assert!(self.votes_for >= 0, "Votes for cannot be negative");
+        assert!(self.votes_against >= 0, "Votes against cannot be negative");
+        assert!(self.votes_abstain >= 0, "Votes abstain cannot be negative");
+        self.votes_for
+            .saturating_add(self.votes_against)
+            .saturating_add(self.votes_abstain)
+    }
+}
+
+/// Position of the indexed chain used to drive proposal transitions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainClock {
+    pub height: u64,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProposalTimeline {
+    pub vote_start: i64,
+    pub vote_end: i64,
+    /// First indexed block at or after `vote_start`; `None` until indexed.
+    pub start_height: Option<u64>,
+    /// First indexed block after `vote_end`; `None` until indexed.
+    pub end_height: Option<u64>,
+    pub executed: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LifecycleRules {
+    pub policy: QuorumPolicy,
+    pub execution_grace_seconds: i64,
+}
+
+impl From<&GovernanceConfig> for LifecycleRules {
+    fn from(config: &GovernanceConfig) -> Self {
+        Self {
+            policy: QuorumPolicy::from(config),
+            execution_grace_seconds: config.execution_grace_seconds,
+        }
+    }
+}
+
+/// Computes the state a proposal should be in at the given chain position.
+///
+/// A window boundary counts as crossed once chain time has passed it and the
+/// clock has reached the block where that happened, so a clock that lags
+/// the boundary block never opens or closes voting early.
+///
+/// `Queued` cannot be derived from votes alone, so it is only kept when the
+/// proposal was already queued. Terminal states never transition again.
+pub fn evaluate_state(
+    current: ProposalState,
+    timeline: &ProposalTimeline,
+    tally: &ProposalTally,
+    eligible_power: i64,
+    clock: &ChainClock,
+    rules: &LifecycleRules,
+) -> ProposalState {
+    assert!(
+        timeline.vote_start <= timeline.vote_end,
+        "Proposal voting window must be ordered"
+    );
+    assert!(clock.timestamp >= 0, "Chain time must be non-negative");
+
+    if timeline.executed {
+        return ProposalState::Executed;
+    }
+    if current.is_terminal() {
+        return current;
+    }
+    let started = clock.timestamp >= timeline.vote_start
+        && timeline
+            .start_height
+            .is_some_and(|height| clock.height >= height);
+    if !started {
+        return ProposalState::Pending;
+    }
+    let ended = clock.timestamp > timeline.vote_end
+        && timeline
+            .end_height
+            .is_some_and(|height| clock.height >= height);
+    if !ended {
+        return ProposalState::Active;
+    }
+    if !rules.policy.passes(tally, eligible_power) {
+        return ProposalState::Defeated;
+    }
+
+    let expires_at = timeline
+        .vote_end
+        .saturating_add(rules.execution_grace_seconds);
+    if clock.timestamp > expires_at {
+        return ProposalState::Expired;
+    }
+    if current == ProposalState::Queued {
+        return ProposalState::Queued;
+    }
+    ProposalState::Succeeded
+}
+
+/// Loads the latest indexed block as the clock for governance evaluation.
+pub async fn load_chain_clock<C: ConnectionTrait>(db: &C) -> Result<Option<ChainClock>, DbErr> {
+    let latest = chain_block::Entity::find()
+        .order_by_desc(chain_block::Column::BlockNumber)
+        .one(db)
+        .await?;
+
+    Ok(latest.map(|block| {
+        assert!(block.block_number >= 0, "Negative block number stored");
+        ChainClock {
+            height: block.block_number as u64,
+            timestamp: block.timestamp.timestamp(),
+        }
+    }))
+}
+
+/// First indexed block whose timestamp is at or after `timestamp`.
+pub async fn first_block_at_or_after<C: ConnectionTrait>(
+    db: &C,
+    timestamp: i64,
+) -> Result<Option<u64>, DbErr> {
+    let Some(at) = DateTime::from_timestamp(timestamp, 0) else {
+        return Ok(None);
+    };
+    let block = chain_block::Entity::find()
+        .select_only()
+        .column(chain_block::Column::BlockNumber)
+        .filter(chain_block::Column::Timestamp.gte(at.fixed_offset()))
+        .order_by_asc(chain_block::Column::Timestamp)
+        .order_by_asc(chain_block::Column::BlockNumber)
+        .into_tuple::<i64>()
+        .one(db)
+        .await?;
+    Ok(block.map(|block| {
+        assert!(block >= 0, "Negative block number stored");
+        block as u64
+    }))
+}
+
+/// Share of all proposals an account has voted on, capped at 1.0.
+pub fn participation_rate(votes_cast: u64, total_proposals: u64) -> f64 {
+    if total_proposals == 0 {
+        return 0.0;
+    }
+    let rate = ((votes_cast as f64) / (total_proposals as f64)).min(1.0);
+    assert!(rate >= 0.0, "Participation rate must be non-negative");
+    rate
+}
+
+/// Voting power eligible at `height`, for proposals without a snapshot.
+/// Derived from stake and delegation history exactly like a snapshot.
+pub async fn eligible_voting_power<C: ConnectionTrait>(db: &C, height: u64) -> Result<i64, DbErr> {
+    let block = i64::try_from(height).unwrap_or(i64::MAX);
+    let total = snapshot::eligible_power_at(db, block).await?;
+
+    assert!(total >= 0, "Eligible voting power must be non-negative");
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: LifecycleRules = LifecycleRules {
+        policy: QuorumPolicy {
+            quorum_votes: 100,
+            quorum_bps: 0,
+            approval_threshold_bps: 5_000,
+        },
+        execution_grace_seconds: 1_000,
+    };
+
+    const ELIGIBLE: i64 = 1_000;
+
+    const TIMELINE: ProposalTimeline = ProposalTimeline {
+        vote_start: 1_000,
+        vote_end: 2_000,
+        start_height: Some(10),
+        end_height: Some(10),
+        executed: false,
+    };
+
+    fn clock(timestamp: i64) -> ChainClock {
+        ChainClock {
+            height: 10,
+            timestamp,
+        }
+    }
+
+    fn tally(votes_for: i64, votes_against: i64) -> ProposalTally {
+        ProposalTally {
+            votes_for,
+            votes_against,
+            votes_abstain: 0,
+        }
+    }
+
+    #[test]
+    fn voting_window_drives_pending_and_active() {
+        let current = ProposalState::Pending;
+        let votes = tally(0, 0);
+        assert_eq!(
+            evaluate_state(current, &TIMELINE, &votes, ELIGIBLE, &clock(999), &RULES),
+            ProposalState::Pending
+        );
+        assert_eq!(
+            evaluate_state(current, &TIMELINE, &votes, ELIGIBLE, &clock(1_000), &RULES),
+            ProposalState::Active
+        );
+        assert_eq!(
+            evaluate_state(current, &TIMELINE, &votes, ELIGIBLE, &clock(2_000), &RULES),
+            ProposalState::Active
+        );
+    }
+
+    #[test]
+    fn boundaries_wait_for_their_block_height() {
+        let votes = tally(90, 30);
+        let unindexed = ProposalTimeline {
+            start_height: None,
+            end_height: None,
+            ..TIMELINE
+        };
+        assert_eq!(
+            evaluate_state(
+                ProposalState::Pending,
+                &unindexed,
+                &votes,
+                ELIGIBLE,
+                &clock(1_500),
+                &RULES
+            ),
+            ProposalState::Pending
+        );
+
+        let lagging = ChainClock {
+            height: 9,
+            timestamp: 2_500,
+        };
+        let closed_at_eleven = ProposalTimeline {
+            start_height: Some(5),
+            end_height: Some(11),
+            ..TIMELINE
+        };
+        assert_eq!(
+            evaluate_state(
+                ProposalState::Active,
+                &closed_at_eleven,
+                &votes,
+                ELIGIBLE,
+                &lagging,
+                &RULES
+            ),
+            ProposalState::Active
+        );
+        assert_eq!(
+            evaluate_state(
+                ProposalState::Active,
+                &closed_at_eleven,
+                &votes,
+                ELIGIBLE,
+                &ChainClock {
+                    height: 11,
+                    ..lagging
+                },
+                &RULES
+            ),
+            ProposalState::Succeeded
+        );
+    }
+
+    #[test]
+    fn outcome_requires_quorum_and_majority() {
+        let current = ProposalState::Active;
+        assert_eq!(
+            evaluate_state(
+                current,
+                &TIMELINE,
+                &tally(60, 10),
+                ELIGIBLE,
+                &clock(2_001),
+                &RULES
+            ),
+            ProposalState::Defeated
+        );
+        assert_eq!(
+            evaluate_state(
+                current,
+                &TIMELINE,
+                &tally(60, 60),
+                ELIGIBLE,
+                &clock(2_001),
+                &RULES
+            ),
+            ProposalState::Defeated
+        );
+        assert_eq!(
+            evaluate_state(
+                current,
+                &TIMELINE,
+                &tally(90, 30),
+                ELIGIBLE,
+                &clock(2_001),
+                &RULES
+            ),
+            ProposalState::Succeeded
+        );
+    }
+
+    #[test]
+    fn passed_proposals_expire_after_grace_period() {
+        let votes = tally(90, 30);
+        assert_eq!(
+            evaluate_state(
+                ProposalState::Queued,
+                &TIMELINE,
+                &votes,
+                ELIGIBLE,
+                &clock(3_000),
+                &RULES
+            ),
+            ProposalState::Queued
+        );
+        assert_eq!(
+            evaluate_state(
+                ProposalState::Queued,
+                &TIMELINE,
+                &votes,
+                ELIGIBLE,
+                &clock(3_001),
+                &RULES
+            ),
+            ProposalState::Expired
+        );
+    }
+
+    #[test]
+    fn terminal_states_are_sticky() {
+        let executed = ProposalTimeline {
+            executed: true,
+            ..TIMELINE
+        };
+        assert_eq!(
+            evaluate_state(
+                ProposalState::Succeeded,
+                &executed,
+                &tally(90, 30),
+                ELIGIBLE,
+                &clock(5_000),
+                &RULES
+            ),
+            ProposalState::Executed
+        );
+        assert_eq!(
+            evaluate_state(
+                ProposalState::Defeated,
+                &TIMELINE,
+                &tally(90, 30),
+                ELIGIBLE,
+                &clock(1_500),
+                &RULES
+            ),
+            ProposalState::Defeated
+        );
+    }
+
+    #[test]
+    fn state_names_roundtrip() {
+        for state in ProposalState::ALL {
+            assert_eq!(ProposalState::parse(state.as_str()), Some(state));
+        }
+        assert_eq!(
+            ProposalState::parse("Pending"),
+            Some(ProposalState::Pending)
+        );
+        assert_eq!(ProposalState::parse("cancelled"), None);
+    }
+}
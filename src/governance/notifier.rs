@@ -0,0 +1,289 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use chrono::Utc;
+use sea_orm::ActiveValue::{NotSet, Set};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, PaginatorTrait, QueryFilter};
+use tokio::sync::broadcast;
+use tracing::debug;
+
+use crate::entities::{governance_notification, governance_vote, indexer_checkpoint};
+use crate::models::governance::{GovernanceEventKind, GovernanceNotificationView, ProposalState};
+
+use super::{ChainClock, ProposalTimeline};
+
+/// Checkpoint row recording the last block notifications were announced at.
+const NOTIFICATION_CURSOR_ID: &str = "governance_notifications";
+
+/// Fan-out point for governance notifications. Live stream clients and the
+/// webhook dispatcher each hold their own receiver.
+#[derive(Clone)]
+pub struct GovernanceNotifier {
+    sender: broadcast::Sender<Arc<GovernanceNotificationView>>,
+}
+
+impl GovernanceNotifier {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "Notification capacity must be positive");
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<GovernanceNotificationView>> {
+        self.sender.subscribe()
+    }
+
+    fn publish(&self, notification: GovernanceNotificationView) {
+        // Sending only fails when nobody is listening, which is not an error.
+        if self.sender.send(Arc::new(notification)).is_err() {
+            debug!("No governance notification subscribers connected");
+        }
+    }
+}
+
+/// Subscriber-side filter. Unset fields match every notification.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NotificationFilter {
+    pub proposer: Option<String>,
+    /// Only proposals this address has voted on.
+    pub voter: Option<String>,
+    pub kinds: Vec<GovernanceEventKind>,
+}
+
+impl NotificationFilter {
+    pub async fn matches<C: ConnectionTrait>(
+        &self,
+        db: &C,
+        notification: &GovernanceNotificationView,
+    ) -> Result<bool, DbErr> {
+        if !self.kinds.is_empty() && !self.kinds.contains(&notification.kind) {
+            return Ok(false);
+        }
+        if self
+            .proposer
+            .as_deref()
+            .is_some_and(|proposer| proposer != notification.proposer)
+        {
+            return Ok(false);
+        }
+        let Some(voter) = self.voter.as_deref() else {
+            return Ok(true);
+        };
+        let votes = governance_vote::Entity::find()
+            .filter(governance_vote::Column::ProposalId.eq(notification.proposal_id))
+            .filter(governance_vote::Column::Voter.eq(voter))
+            .count(db)
+            .await?;
+        Ok(votes > 0)
+    }
+}
+
+/// How long before `vote_end` a proposal counts as closing soon: the
+/// configured window, shortened to a quarter of the voting period so short
+/// votes are not flagged the moment they open.
+pub fn closing_soon_threshold(timeline: &ProposalTimeline, closing_soon_seconds: i64) -> i64 {
+    let period = timeline.vote_end.saturating_sub(timeline.vote_start);
+    closing_soon_seconds.min(period / 4).max(1)
+}
+
+/// Events a proposal qualifies for after a lifecycle evaluation. Events that
+/// were already announced are filtered out when recorded.
+pub fn due_events(
+    timeline: &ProposalTimeline,
+    state: ProposalState,
+    clock: &ChainClock,
+    closing_soon_seconds: i64,
+) -> Vec<GovernanceEventKind> {
+    assert!(
+        closing_soon_seconds > 0,
+        "Closing-soon window must be positive"
+    );
+    let mut events = vec![GovernanceEventKind::ProposalCreated];
+    match state {
+        ProposalState::Active => {
+            events.push(GovernanceEventKind::VotingOpened);
+            let remaining = timeline.vote_end - clock.timestamp;
+            if remaining <= closing_soon_threshold(timeline, closing_soon_seconds) {
+                events.push(GovernanceEventKind::VotingClosingSoon);
+            }
+        }
+        ProposalState::Succeeded => events.push(GovernanceEventKind::ProposalPassed),
+        ProposalState::Defeated => events.push(GovernanceEventKind::ProposalFailed),
+        ProposalState::Executed => events.push(GovernanceEventKind::ProposalExecuted),
+        ProposalState::Pending | ProposalState::Queued | ProposalState::Expired => {}
+    }
+    events
+}
+
+/// Block at which notifications were last announced, or `None` before the
+/// first lifecycle pass.
+pub async fn load_notification_cursor<C: ConnectionTrait>(db: &C) -> Result<Option<i64>, DbErr> {
+    Ok(
+        indexer_checkpoint::Entity::find_by_id(NOTIFICATION_CURSOR_ID.to_string())
+            .one(db)
+            .await?
+            .map(|checkpoint| checkpoint.last_block_number),
+    )
+}
+
+pub async fn store_notification_cursor<C: ConnectionTrait>(
+    db: &C,
+    block: i64,
+) -> Result<(), DbErr> {
+    assert!(block >= 0, "Notification cursor must be non-negative");
+    let model = indexer_checkpoint::ActiveModel {
+        id: Set(NOTIFICATION_CURSOR_ID.to_string()),
+        last_block_number: Set(block),
+        updated_at: Set(Utc::now().fixed_offset()),
+    };
+    indexer_checkpoint::Entity::insert(model)
+        .on_conflict(
+            OnConflict::column(indexer_checkpoint::Column::Id)
+                .update_columns([
+                    indexer_checkpoint::Column::LastBlockNumber,
+                    indexer_checkpoint::Column::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+    Ok(())
+}
+
+/// Persists notifications that have not been emitted before and, when
+/// `announce` is set, publishes them. Returns how many were new.
+///
+/// Recording without announcing seeds the history on a first run, so events
+/// that happened before the cursor existed are not broadcast again.
+pub async fn record_and_publish<C: ConnectionTrait>(
+    db: &C,
+    notifier: &GovernanceNotifier,
+    candidates: Vec<GovernanceNotificationView>,
+    announce: bool,
+) -> Result<usize, DbErr> {
+    if candidates.is_empty() {
+        return Ok(0);
+    }
+
+    let proposal_ids = candidates
+        .iter()
+        .map(|candidate| candidate.proposal_id)
+        .collect::<HashSet<_>>();
+    let emitted = governance_notification::Entity::find()
+        .filter(governance_notification::Column::ProposalId.is_in(proposal_ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|row| (row.proposal_id, row.kind))
+        .collect::<HashSet<_>>();
+
+    let fresh = candidates
+        .into_iter()
+        .filter(|candidate| {
+            !emitted.contains(&(candidate.proposal_id, candidate.kind.as_str().to_string()))
+        })
+        .collect::<Vec<_>>();
+    if fresh.is_empty() {
+        return Ok(0);
+    }
+
+    let now = Utc::now().fixed_offset();
+    let models = fresh
+        .iter()
+        .map(|notification| governance_notification::ActiveModel {
+            id: NotSet,
+            proposal_id: Set(notification.proposal_id),
+            kind: Set(notification.kind.as_str().to_string()),
+            proposer: Set(notification.proposer.clone()),
+            state: Set(notification.state.as_str().to_string()),
+            block_number: Set(notification.block_number),
+            emitted_at: Set(now),
+        })
+        .collect::<Vec<_>>();
+    governance_notification::Entity::insert_many(models)
+        .on_conflict(
+            OnConflict::columns([
+                governance_notification::Column::ProposalId,
+                governance_notification::Column::Kind,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    let count = fresh.len();
+    if announce {
+        for notification in fresh {
+            notifier.publish(notification);
+        }
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMELINE: ProposalTimeline = ProposalTimeline {
+        vote_start: 1_000,
+        vote_end: 200_000,
+        start_height: Some(1),
+        end_height: None,
+        executed: false,
+    };
+
+    fn at(timestamp: i64) -> ChainClock {
+        ChainClock {
+            height: 10,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn active_proposals_announce_opening_then_closing_soon() {
+        assert_eq!(
+            due_events(&TIMELINE, ProposalState::Active, &at(2_000), 86_400),
+            vec![
+                GovernanceEventKind::ProposalCreated,
+                GovernanceEventKind::VotingOpened
+            ]
+        );
+        assert!(
+            due_events(&TIMELINE, ProposalState::Active, &at(160_000), 86_400)
+                .contains(&GovernanceEventKind::VotingClosingSoon)
+        );
+    }
+
+    #[test]
+    fn short_votes_scale_the_closing_soon_window() {
+        let hour_long = ProposalTimeline {
+            vote_start: 0,
+            vote_end: 3_600,
+            ..TIMELINE
+        };
+        assert_eq!(closing_soon_threshold(&hour_long, 86_400), 900);
+        assert!(
+            !due_events(&hour_long, ProposalState::Active, &at(60), 86_400)
+                .contains(&GovernanceEventKind::VotingClosingSoon)
+        );
+        assert!(
+            due_events(&hour_long, ProposalState::Active, &at(3_000), 86_400)
+                .contains(&GovernanceEventKind::VotingClosingSoon)
+        );
+        assert_eq!(closing_soon_threshold(&TIMELINE, 3_600), 3_600);
+    }
+
+    #[test]
+    fn outcomes_map_to_their_events() {
+        let events = |state| due_events(&TIMELINE, state, &at(300_000), 86_400);
+        assert_eq!(
+            events(ProposalState::Pending),
+            vec![GovernanceEventKind::ProposalCreated]
+        );
+        assert!(events(ProposalState::Succeeded).contains(&GovernanceEventKind::ProposalPassed));
+        assert!(events(ProposalState::Defeated).contains(&GovernanceEventKind::ProposalFailed));
+        assert!(events(ProposalState::Executed).contains(&GovernanceEventKind::ProposalExecuted));
+    }
+}
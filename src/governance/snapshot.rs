@@ -0,0 +1,284 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::Utc;
+use sea_orm::ActiveValue::Set;
+use sea_orm::sea_query::{Condition, OnConflict};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QuerySelect, TransactionTrait,
+};
+
+use crate::entities::{chain_transaction, governance_delegation_event, voting_power_snapshot};
+
+use super::delegation::{load_ledger, positions_from_events};
+
+const SNAPSHOT_INSERT_CHUNK: usize = 1_000;
+const MAX_SNAPSHOT_ACCOUNTS: usize = 500_000;
+const STAKING_TRANSACTION_TYPE: &str = "staking";
+
+/// Stake and delegation totals that make up an account's voting power.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PowerComponents {
+    pub staked: i64,
+    pub delegated_in: i64,
+    pub delegated_out: i64,
+}
+
+impl PowerComponents {
+    pub fn voting_power(&self) -> i64 {
+        assert!(self.staked >= 0, "Staked amount negative");
+        assert!(self.delegated_in >= 0, "Inbound delegation negative");
+        assert!(self.delegated_out >= 0, "Outbound delegation negative");
+        self.staked
+            .saturating_add(self.delegated_in)
+            .saturating_sub(self.delegated_out)
+            .max(0)
+    }
+}
+
+impl From<&voting_power_snapshot::Model> for PowerComponents {
+    fn from(model: &voting_power_snapshot::Model) -> Self {
+        Self {
+            staked: model.staked,
+            delegated_in: model.delegated_in,
+            delegated_out: model.delegated_out,
+        }
+    }
+}
+
+/// Stake bonded through staking transactions up to and including `block`,
+/// keyed by sender. `addresses` restricts the sum to those accounts when set.
+async fn stake_at<C: ConnectionTrait>(
+    db: &C,
+    block: i64,
+    addresses: Option<Vec<String>>,
+) -> Result<BTreeMap<String, i64>, DbErr> {
+    let mut query = chain_transaction::Entity::find()
+        .select_only()
+        .column(chain_transaction::Column::Sender)
+        .column_as(chain_transaction::Column::Amount.sum(), "total")
+        .filter(chain_transaction::Column::TransactionType.eq(STAKING_TRANSACTION_TYPE))
+        .filter(chain_transaction::Column::BlockNumber.lte(block));
+    if let Some(addresses) = addresses {
+        query = query.filter(chain_transaction::Column::Sender.is_in(addresses));
+    }
+    let totals = query
+        .group_by(chain_transaction::Column::Sender)
+        .into_tuple::<(String, Option<i64>)>()
+        .all(db)
+        .await?;
+
+    Ok(totals
+        .into_iter()
+        .map(|(address, total)| (address, total.unwrap_or(0).max(0)))
+        .collect())
+}
+
+/// Stake bonded by `address` up to and including `block`.
+pub async fn staked_at<C: ConnectionTrait>(
+    db: &C,
+    block: i64,
+    address: &str,
+) -> Result<i64, DbErr> {
+    Ok(stake_at(db, block, Some(vec![address.to_string()]))
+        .await?
+        .remove(address)
+        .unwrap_or(0))
+}
+
+/// Folds replayed delegation ledgers into per-account inbound and outbound
+/// totals.
+fn accumulate_delegations(
+    accounts: &mut BTreeMap<String, PowerComponents>,
+    ledger: &BTreeMap<String, Vec<governance_delegation_event::Model>>,
+) {
+    for (delegator, events) in ledger {
+        for (delegatee, position) in positions_from_events(events) {
+            let outbound = accounts.entry(delegator.clone()).or_default();
+            outbound.delegated_out = outbound.delegated_out.saturating_add(position.amount);
+            let inbound = accounts.entry(delegatee).or_default();
+            inbound.delegated_in = inbound.delegated_in.saturating_add(position.amount);
+        }
+    }
+}
+
+/// Reconstructs every account's stake and delegations as of `block` from the
+/// delegation event log and staking transactions.
+pub async fn power_by_address_at<C: ConnectionTrait>(
+    db: &C,
+    block: i64,
+) -> Result<BTreeMap<String, PowerComponents>, DbErr> {
+    let mut accounts: BTreeMap<String, PowerComponents> = BTreeMap::new();
+    for (address, staked) in stake_at(db, block, None).await? {
+        accounts.entry(address).or_default().staked = staked;
+    }
+    let ledger = load_ledger(db, None, Some(block)).await?;
+    accumulate_delegations(&mut accounts, &ledger);
+
+    assert!(
+        accounts.len() <= MAX_SNAPSHOT_ACCOUNTS,
+        "Voting power snapshot exceeds defensive account bound"
+    );
+    Ok(accounts)
+}
+
+/// Reconstructs a single account's stake and delegations as of `block`.
+pub async fn power_at<C: ConnectionTrait>(
+    db: &C,
+    block: i64,
+    address: &str,
+) -> Result<PowerComponents, DbErr> {
+    // Inbound totals depend on the full history of every delegator that ever
+    // pointed at the address, since later events can move their stake away.
+    let mut delegators = governance_delegation_event::Entity::find()
+        .select_only()
+        .column(governance_delegation_event::Column::Delegator)
+        .distinct()
+        .filter(governance_delegation_event::Column::BlockNumber.lte(block))
+        .filter(
+            Condition::any()
+                .add(governance_delegation_event::Column::Delegatee.eq(address))
+                .add(governance_delegation_event::Column::PreviousDelegatee.eq(address)),
+        )
+        .into_tuple::<String>()
+        .all(db)
+        .await?;
+    delegators.push(address.to_string());
+    delegators.sort();
+    delegators.dedup();
+
+    let ledger = load_ledger(db, Some(&delegators), Some(block)).await?;
+    let mut accounts: BTreeMap<String, PowerComponents> = BTreeMap::new();
+    accumulate_delegations(&mut accounts, &ledger);
+
+    let mut power = accounts.remove(address).unwrap_or_default();
+    power.staked = staked_at(db, block, address).await?;
+    Ok(power)
+}
+
+fn total_voting_power(accounts: &BTreeMap<String, PowerComponents>) -> i64 {
+    accounts.values().fold(0i64, |total, power| {
+        total.saturating_add(power.voting_power())
+    })
+}
+
+/// Total voting power eligible as of `block`, summed the same way snapshots
+/// are but without recording them.
+pub async fn eligible_power_at<C: ConnectionTrait>(db: &C, block: i64) -> Result<i64, DbErr> {
+    let accounts = power_by_address_at(db, block).await?;
+    Ok(total_voting_power(&accounts))
+}
+
+/// Records every account's voting power as of `block` and returns the total
+/// eligible power. Snapshots are derived from history, so recapturing a
+/// block rewrites identical rows.
+pub async fn capture_snapshot<C>(db: &C, block: u64) -> Result<i64, DbErr>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    assert!(
+        block <= i64::MAX as u64,
+        "Snapshot block exceeds i64 bounds"
+    );
+    let block_number = block as i64;
+
+    let txn = db.begin().await?;
+    let accounts = power_by_address_at(&txn, block_number).await?;
+    let eligible = total_voting_power(&accounts);
+    let captured_at = Utc::now().fixed_offset();
+
+    let models = accounts
+        .into_iter()
+        .map(|(address, power)| voting_power_snapshot::ActiveModel {
+            block_number: Set(block_number),
+            address: Set(address),
+            delegated_in: Set(power.delegated_in),
+            delegated_out: Set(power.delegated_out),
+            staked: Set(power.staked),
+            voting_power: Set(power.voting_power()),
+            captured_at: Set(captured_at),
+        })
+        .collect::<Vec<_>>();
+
+    let mut remaining = models.into_iter().peekable();
+    while remaining.peek().is_some() {
+        let chunk = remaining
+            .by_ref()
+            .take(SNAPSHOT_INSERT_CHUNK)
+            .collect::<Vec<_>>();
+        voting_power_snapshot::Entity::insert_many(chunk)
+            .on_conflict(
+                OnConflict::columns([
+                    voting_power_snapshot::Column::BlockNumber,
+                    voting_power_snapshot::Column::Address,
+                ])
+                .update_columns([
+                    voting_power_snapshot::Column::DelegatedIn,
+                    voting_power_snapshot::Column::DelegatedOut,
+                    voting_power_snapshot::Column::Staked,
+                    voting_power_snapshot::Column::VotingPower,
+                    voting_power_snapshot::Column::CapturedAt,
+                ])
+                .to_owned(),
+            )
+            .exec_without_returning(&txn)
+            .await?;
+    }
+
+    txn.commit().await?;
+    assert!(eligible >= 0, "Eligible voting power must be non-negative");
+    Ok(eligible)
+}
+
+/// Snapshot voting power for a set of voters, keyed by address.
+pub async fn powers_in_snapshot<C: ConnectionTrait>(
+    db: &C,
+    snapshot_block: i64,
+    addresses: Vec<String>,
+) -> Result<HashMap<String, i64>, DbErr> {
+    if addresses.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let rows = voting_power_snapshot::Entity::find()
+        .filter(voting_power_snapshot::Column::BlockNumber.eq(snapshot_block))
+        .filter(voting_power_snapshot::Column::Address.is_in(addresses))
+        .all(db)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.address, row.voting_power))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn voting_power_is_net_of_outbound_delegation() {
+        let power = PowerComponents {
+            staked: 0,
+            delegated_in: 100,
+            delegated_out: 30,
+        };
+        assert_eq!(power.voting_power(), 70);
+
+        let overdelegated = PowerComponents {
+            staked: 0,
+            delegated_in: 10,
+            delegated_out: 30,
+        };
+        assert_eq!(overdelegated.voting_power(), 0);
+    }
+
+    #[test]
+    fn voting_power_counts_own_stake() {
+        let power = PowerComponents {
+            staked: 50,
+            delegated_in: 20,
+            delegated_out: 30,
+        };
+        assert_eq!(power.voting_power(), 40);
+    }
+}
@@ -0,0 +1,228 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait,
+    QueryFilter,
+};
+use tokio::sync::{Semaphore, broadcast, watch};
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+use crate::config::{NotificationConfig, WebhookSubscriberConfig};
+use crate::entities::{identity_profile, wallet_link};
+use crate::identity::VISIBILITY_PUBLIC;
+use crate::models::governance::{GovernanceEventKind, GovernanceNotificationView};
+
+use super::notifier::{GovernanceNotifier, NotificationFilter};
+
+const RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+struct WebhookSubscriber {
+    url: String,
+    filter: NotificationFilter,
+}
+
+impl From<&WebhookSubscriberConfig> for WebhookSubscriber {
+    fn from(config: &WebhookSubscriberConfig) -> Self {
+        let kinds = config
+            .events
+            .iter()
+            .filter_map(|raw| {
+                let kind = GovernanceEventKind::parse(raw);
+                if kind.is_none() {
+                    warn!(url = %config.url, "Ignoring unknown webhook event filter {raw}");
+                }
+                kind
+            })
+            .collect();
+        Self {
+            url: config.url.clone(),
+            filter: NotificationFilter {
+                proposer: config.proposer.clone(),
+                voter: config.voter.clone(),
+                kinds,
+            },
+        }
+    }
+}
+
+/// Background task that POSTs governance notifications to the configured
+/// webhook subscribers.
+pub struct WebhookDispatcher {
+    database: DatabaseConnection,
+    client: reqwest::Client,
+    subscribers: Arc<Vec<WebhookSubscriber>>,
+    max_attempts: u32,
+    /// Bounds deliveries in flight across all subscribers.
+    deliveries: Arc<Semaphore>,
+    receiver: broadcast::Receiver<Arc<GovernanceNotificationView>>,
+}
+
+impl WebhookDispatcher {
+    pub fn new(
+        database: DatabaseConnection,
+        config: &NotificationConfig,
+        notifier: &GovernanceNotifier,
+    ) -> Result<Self> {
+        assert!(
+            config.webhook_max_attempts > 0,
+            "Webhook attempts must be positive"
+        );
+        let client = reqwest::Client::builder()
+            .timeout(config.webhook_timeout())
+            .build()
+            .context("Failed to build webhook HTTP client")?;
+        Ok(Self {
+            database,
+            client,
+            subscribers: Arc::new(
+                config
+                    .webhooks
+                    .iter()
+                    .map(WebhookSubscriber::from)
+                    .collect(),
+            ),
+            max_attempts: config.webhook_max_attempts,
+            deliveries: Arc::new(Semaphore::new(config.webhook_max_concurrency)),
+            receiver: notifier.subscribe(),
+        })
+    }
+
+    pub async fn run(mut self, mut shutdown: watch::Receiver<bool>) -> Result<()> {
+        if self.subscribers.is_empty() {
+            info!("No governance webhooks configured; dispatcher idle");
+            return Ok(());
+        }
+        info!(
+            "Starting governance webhook dispatcher for {} subscribers",
+            self.subscribers.len()
+        );
+
+        loop {
+            tokio::select! {
+                changed = shutdown.changed() => {
+                    match changed {
+                        Ok(_) => {
+                            if *shutdown.borrow() {
+                                info!("Webhook dispatcher shutdown signal received");
+                                break;
+                            }
+                        }
+                        Err(_) => {
+                            warn!("Shutdown channel closed unexpectedly. Exiting webhook dispatcher");
+                            break;
+                        }
+                    }
+                }
+                received = self.receiver.recv() => {
+                    match received {
+                        Ok(notification) => self.dispatch(notification).await,
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            warn!("Webhook dispatcher fell behind; {skipped} notifications dropped");
+                        }
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Delivers to each subscriber on its own task so one slow endpoint does
+    /// not hold up the others. Waits for a delivery slot when the concurrency
+    /// cap is reached, letting the broadcast buffer absorb bursts.
+    async fn dispatch(&self, notification: Arc<GovernanceNotificationView>) {
+        for index in 0..self.subscribers.len() {
+            let Ok(permit) = Arc::clone(&self.deliveries).acquire_owned().await else {
+                warn!("Webhook delivery limiter closed; dropping notification");
+                return;
+            };
+            let subscribers = Arc::clone(&self.subscribers);
+            let database = self.database.clone();
+            let client = self.client.clone();
+            let notification = Arc::clone(&notification);
+            let max_attempts = self.max_attempts;
+            tokio::spawn(async move {
+                let _permit = permit;
+                let subscriber = &subscribers[index];
+                if let Some(voter) = subscriber.filter.voter.as_deref() {
+                    match voter_publicly_visible(&database, voter).await {
+                        Ok(true) => {}
+                        Ok(false) => {
+                            debug!(
+                                url = %subscriber.url,
+                                "Webhook voter filter targets a hidden address"
+                            );
+                            return;
+                        }
+                        Err(err) => {
+                            warn!(
+                                url = %subscriber.url,
+                                "Failed to check webhook voter visibility: {err}"
+                            );
+                            return;
+                        }
+                    }
+                }
+                match subscriber.filter.matches(&database, &notification).await {
+                    Ok(true) => deliver(&client, subscriber, &notification, max_attempts).await,
+                    Ok(false) => {}
+                    Err(err) => warn!(
+                        url = %subscriber.url,
+                        "Failed to evaluate webhook filter: {err}"
+                    ),
+                }
+            });
+        }
+    }
+}
+
+/// Webhooks are registered by the operator rather than an identity, so they
+/// see what an anonymous caller sees: a voter filter only applies while no
+/// verified owner of the address restricts its visibility.
+async fn voter_publicly_visible<C: ConnectionTrait>(db: &C, voter: &str) -> Result<bool, DbErr> {
+    let restricted = wallet_link::Entity::find()
+        .filter(wallet_link::Column::WalletAddress.eq(voter))
+        .filter(wallet_link::Column::VerifiedAt.is_not_null())
+        .inner_join(identity_profile::Entity)
+        .filter(identity_profile::Column::StatsVisibility.ne(VISIBILITY_PUBLIC))
+        .count(db)
+        .await?;
+    Ok(restricted == 0)
+}
+
+async fn deliver(
+    client: &reqwest::Client,
+    subscriber: &WebhookSubscriber,
+    notification: &GovernanceNotificationView,
+    max_attempts: u32,
+) {
+    for attempt in 1..=max_attempts {
+        let outcome = client
+            .post(&subscriber.url)
+            .json(notification)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        match outcome {
+            Ok(_) => return,
+            Err(err) if attempt < max_attempts => {
+                warn!(
+                    url = %subscriber.url,
+                    attempt,
+                    "Webhook delivery failed, retrying: {err}"
+                );
+                sleep(RETRY_BACKOFF * attempt).await;
+            }
+            Err(err) => warn!(
+                url = %subscriber.url,
+                proposal_id = notification.proposal_id,
+                kind = notification.kind.as_str(),
+                "Webhook delivery abandoned after {max_attempts} attempts: {err}"
+            ),
+        }
+    }
+}
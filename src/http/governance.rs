@@ -1,21 +1,55 @@
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
+use std::sync::atomic::Ordering as AtomicOrdering;
+
+use axum::body::{Body, Bytes};
 use axum::extract::{Path, Query, State};
-use axum::http::StatusCode;
+use axum::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use axum::http::{HeaderValue, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
 use axum::routing::{get, post};
 use axum::{Json, Router};
-use chrono::DateTime;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, Stream, StreamExt};
 use sea_orm::prelude::*;
-use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
-use serde::Deserialize;
-
-use crate::entities::{governance_delegation, governance_proposal, governance_vote};
+use sea_orm::{
+    ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder, QuerySelect, QueryTrait,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use crate::entities::{
+    governance_delegation, governance_delegation_event, governance_proposal, governance_vote,
+    wallet_link,
+};
+use crate::governance::analytics::{QuorumPolicy, load_vote_timeline, percentage, project_tally};
+use crate::governance::cache::{ProposalCacheKey, ProposalChange, invalidate_proposal};
+use crate::governance::calldata::decode_calldata;
+use crate::governance::directory::{
+    DelegateRanking, MAX_DIRECTORY_DELEGATES, load_delegates, rank_delegates,
+};
+use crate::governance::export::{
+    ExportCursor, ExportEncoder, ExportFormat, ExportSource, next_page,
+};
+use crate::governance::notifier::NotificationFilter;
+use crate::governance::snapshot::{PowerComponents, power_at, powers_in_snapshot, staked_at};
+use crate::governance::{
+    ProposalTally, ProposalTimeline, eligible_voting_power, load_chain_clock, participation_rate,
+};
 use crate::models::governance::{
-    DelegateRequest, DelegateResponse, DelegationView, GovernanceStatsView, ProposalCreateRequest,
-    ProposalSummary, ProposalView, VoteHistoryEntry, VoteSubmissionRequest, VoteSubmissionResponse,
-    VoteView, VotingPowerView,
+    ActionTargetStatus, DecodedArgumentView, DelegateRequest, DelegateResponse, DelegateView,
+    DelegationEventKind, DelegationEventView, DelegationView, GovernanceEventKind,
+    GovernanceStatsView, ProjectedOutcomeView, ProposalActionView, ProposalAnalyticsView,
+    ProposalCreateRequest, ProposalState, ProposalSummary, ProposalView, VoteHistoryEntry,
+    VoteSubmissionRequest, VoteSubmissionResponse, VoteView, VotingPowerView,
 };
 use crate::state::AppState;
 
 use super::HttpError;
+use super::viewer::Viewer;
 
 const MAX_HISTORY_LIMIT: u64 = 500;
 const MAX_PROPOSAL_QUERY_LIMIT: u64 = 100;
@@ -23,18 +57,39 @@ const MAX_PROPOSAL_VOTE_LIMIT: u64 = 500;
 const MAX_DELEGATION_AMOUNT: u64 = 100_000_000_000_000;
 const MAX_PROPOSAL_TITLE_LEN: usize = 256;
 const MAX_PROPOSAL_DESCRIPTION_LEN: usize = 10_000;
+const MAX_PROPOSAL_ACTIONS: usize = 64;
+const MAX_DELEGATE_QUERY_LIMIT: u64 = 200;
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/proposals", get(get_proposals).post(create_proposal))
         .route("/proposals/:proposal_id", get(get_proposal))
         .route("/proposals/:proposal_id/votes", get(get_proposal_votes))
+        .route(
+            "/proposals/:proposal_id/votes/export",
+            get(export_proposal_votes),
+        )
+        .route("/activity/:address/export", get(export_address_activity))
+        .route(
+            "/proposals/:proposal_id/analytics",
+            get(get_proposal_analytics),
+        )
         .route("/votes/:address", get(get_vote_history))
         .route("/votes", post(submit_vote))
         .route("/voting-power/:address", get(get_voting_power))
+        .route("/delegates", get(get_delegates))
         .route("/delegations/:address", get(get_delegations))
         .route("/delegations", post(delegate_voting_power))
+        .route(
+            "/delegation-history/delegator/:address",
+            get(get_delegator_history),
+        )
+        .route(
+            "/delegation-history/delegatee/:address",
+            get(get_delegatee_history),
+        )
         .route("/stats/:address", get(get_governance_stats))
+        .route("/notifications/stream", get(stream_notifications))
 }
 
 #[derive(Debug, Deserialize)]
@@ -52,6 +107,31 @@ struct ProposalDetailQuery {
     voter: Option<String>,
 }
 
+#[derive(Debug, Deserialize, Default)]
+struct VotingPowerQuery {
+    at_block: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct DelegateDirectoryQuery {
+    sort: Option<String>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct NotificationStreamQuery {
+    proposer: Option<String>,
+    voter: Option<String>,
+    /// Comma separated event kinds; all kinds when absent.
+    events: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct ExportQuery {
+    format: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Default)]
 struct VoteHistoryQuery {
     limit: Option<u64>,
@@ -78,10 +158,32 @@ async fn get_proposals(
         "Proposal offset exceeds database bounds"
     );
 
+    let state_filter = match query.state.as_deref() {
+        Some(raw_state) => Some(ProposalState::parse(raw_state).ok_or_else(|| {
+            HttpError::new(
+                StatusCode::BAD_REQUEST,
+                format!("Unsupported proposal state {}", raw_state.trim()),
+            )
+        })?),
+        None => None,
+    };
+
+    let cache_key = ProposalCacheKey::List {
+        state: state_filter,
+        proposer: query.proposer.clone(),
+        limit,
+        offset,
+    }
+    .encode();
+    if let Some(summaries) = cached_proposal_read::<Vec<ProposalSummary>>(&state, &cache_key).await
+    {
+        return Ok(Json(summaries));
+    }
+
     let mut select = governance_proposal::Entity::find();
 
-    if let Some(state_filter) = query.state {
-        select = select.filter(governance_proposal::Column::State.eq(state_filter));
+    if let Some(state_filter) = state_filter {
+        select = select.filter(governance_proposal::Column::State.eq(state_filter.as_str()));
     }
 
     if let Some(proposer) = query.proposer {
@@ -99,6 +201,7 @@ async fn get_proposals(
     let summaries = proposals
         .into_iter()
         .map(|p| ProposalSummary {
+            state: stored_proposal_state(&p.state),
             proposal_id: p.proposal_id,
             proposer: p.proposer,
             description: p.description,
@@ -107,7 +210,6 @@ async fn get_proposals(
             votes_for: p.votes_for,
             votes_against: p.votes_against,
             votes_abstain: p.votes_abstain,
-            state: p.state,
             created_at: p.created_at.timestamp(),
         })
         .collect::<Vec<_>>();
@@ -117,6 +219,8 @@ async fn get_proposals(
         "Returned more proposals than requested",
     );
 
+    cache_proposal_read(&state, cache_key, &summaries).await;
+
     Ok(Json(summaries))
 }
 
@@ -124,9 +228,77 @@ async fn get_proposal(
     Path(proposal_id): Path<i64>,
     Query(detail): Query<ProposalDetailQuery>,
     State(state): State<AppState>,
+    viewer: Viewer,
 ) -> Result<Json<ProposalView>, HttpError> {
     assert!(proposal_id >= 0, "Proposal id must be non-negative");
+    if let Some(voter) = detail.voter.as_deref() {
+        viewer.ensure_address_visible(&state, voter).await?;
+    }
+
+    let cache_key = ProposalCacheKey::Detail(proposal_id).encode();
+    let mut view = match cached_proposal_read::<ProposalView>(&state, &cache_key).await {
+        Some(view) => view,
+        None => {
+            let view = load_proposal_view(&state, proposal_id).await?;
+            cache_proposal_read(&state, cache_key, &view).await;
+            view
+        }
+    };
 
+    let user_vote_record = if let Some(voter) = detail.voter.as_ref() {
+        governance_vote::Entity::find()
+            .filter(governance_vote::Column::ProposalId.eq(proposal_id))
+            .filter(governance_vote::Column::Voter.eq(voter.clone()))
+            .one(&state.database)
+            .await
+            .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+    } else {
+        None
+    };
+
+    let snapshot_powers = match (view.snapshot_block, user_vote_record.as_ref()) {
+        (Some(block), Some(vote)) => Some(
+            powers_in_snapshot(&state.database, block, vec![vote.voter.clone()])
+                .await
+                .map_err(|err| {
+                    HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+                })?,
+        ),
+        _ => None,
+    };
+
+    let (has_voted, user_vote) = match (detail.voter.as_ref(), user_vote_record) {
+        (Some(_), Some(vote)) => (Some(true), Some(vote_view(vote, snapshot_powers.as_ref()))),
+        (Some(_), None) => (Some(false), None),
+        (None, _) => (None, None),
+    };
+    view.has_voted = has_voted;
+    view.user_vote = user_vote;
+
+    Ok(Json(view))
+}
+
+/// Voters on `proposal_id` whose activity is hidden from `viewer`.
+async fn hidden_voters(
+    state: &AppState,
+    viewer: &Viewer,
+    proposal_id: i64,
+) -> Result<HashSet<String>, HttpError> {
+    let voters = governance_vote::Entity::find()
+        .select_only()
+        .column(governance_vote::Column::Voter)
+        .filter(governance_vote::Column::ProposalId.eq(proposal_id))
+        .into_query();
+    viewer
+        .hidden_addresses(
+            state,
+            Condition::all().add(wallet_link::Column::WalletAddress.in_subquery(voters)),
+        )
+        .await
+}
+
+/// Builds the voter-independent part of a proposal's detail view.
+async fn load_proposal_view(state: &AppState, proposal_id: i64) -> Result<ProposalView, HttpError> {
     let proposal = governance_proposal::Entity::find_by_id(proposal_id)
         .one(&state.database)
         .await
@@ -156,60 +328,159 @@ async fn get_proposal(
         "Proposal targets and calldatas must align",
     );
 
-    let user_vote_record = if let Some(voter) = detail.voter.as_ref() {
-        governance_vote::Entity::find()
-            .filter(governance_vote::Column::ProposalId.eq(proposal_id))
-            .filter(governance_vote::Column::Voter.eq(voter.clone()))
-            .one(&state.database)
-            .await
-            .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
-    } else {
-        None
-    };
-
-    let (has_voted, user_vote) = match (detail.voter.as_ref(), user_vote_record) {
-        (Some(_), Some(vote)) => (
-            Some(true),
-            Some(VoteView {
-                proposal_id: vote.proposal_id,
-                voter: vote.voter,
-                support: vote.support,
-                weight: vote.weight,
-                reason: vote.reason,
-                voted_at: vote.voted_at.timestamp(),
-            }),
-        ),
-        (Some(_), None) => (Some(false), None),
-        (None, _) => (None, None),
-    };
+    let actions = decode_actions(state, &targets, &values, &calldatas);
 
-    let view = ProposalView {
+    Ok(ProposalView {
         proposal_id: proposal.proposal_id,
         proposer: proposal.proposer,
         targets,
         values,
         calldatas,
+        actions,
         description: proposal.description,
         vote_start: proposal.vote_start,
         vote_end: proposal.vote_end,
         votes_for: proposal.votes_for,
         votes_against: proposal.votes_against,
         votes_abstain: proposal.votes_abstain,
-        state: proposal.state,
+        state: stored_proposal_state(&proposal.state),
         executed_at: proposal.executed_at.map(|dt| dt.timestamp()),
         created_at: proposal.created_at.timestamp(),
         updated_at: proposal.updated_at.timestamp(),
-        has_voted,
-        user_vote,
-    };
+        snapshot_block: proposal.snapshot_block,
+        has_voted: None,
+        user_vote: None,
+    })
+}
 
-    Ok(Json(view))
+/// Evicts cached reads that include `proposal_id` after its tallies moved.
+async fn invalidate_cached_proposal(state: &AppState, proposal_id: i64) {
+    let proposal = governance_proposal::Entity::find_by_id(proposal_id)
+        .one(&state.database)
+        .await;
+
+    match proposal {
+        Ok(Some(proposal)) => {
+            let current = stored_proposal_state(&proposal.state);
+            invalidate_proposal(
+                &state.cache.proposals,
+                ProposalChange {
+                    proposal_id,
+                    proposer: proposal.proposer,
+                    states: [current, current],
+                },
+            );
+        }
+        Ok(None) => {
+            state
+                .cache
+                .proposals
+                .invalidate(&ProposalCacheKey::Detail(proposal_id).encode())
+                .await;
+        }
+        Err(err) => {
+            warn!(
+                proposal_id,
+                "Failed to load proposal for cache invalidation: {err}"
+            );
+            state.cache.proposals.invalidate_all();
+        }
+    }
+}
+
+async fn cached_proposal_read<T: DeserializeOwned>(state: &AppState, key: &str) -> Option<T> {
+    let value = state.cache.proposals.get(key).await?;
+    match serde_json::from_value(value) {
+        Ok(read) => Some(read),
+        Err(err) => {
+            warn!(key, "Discarding undecodable proposal cache entry: {err}");
+            state.cache.proposals.invalidate(key).await;
+            None
+        }
+    }
 }
 
+async fn cache_proposal_read<T: Serialize>(state: &AppState, key: String, read: &T) {
+    match serde_json::to_value(read) {
+        Ok(value) => state.cache.proposals.insert(key, value).await,
+        Err(err) => warn!(key, "Failed to cache proposal read: {err}"),
+    }
+}
+
+fn decode_actions(
+    state: &AppState,
+    targets: &[String],
+    values: &[String],
+    calldatas: &[String],
+) -> Vec<ProposalActionView> {
+    if targets.len() > MAX_PROPOSAL_ACTIONS {
+        warn!(
+            actions = targets.len(),
+            "Decoding only the first {MAX_PROPOSAL_ACTIONS} proposal actions"
+        );
+    }
+
+    let mut actions = Vec::with_capacity(targets.len().min(MAX_PROPOSAL_ACTIONS));
+    for (index, ((target, value), calldata)) in targets
+        .iter()
+        .zip(values)
+        .zip(calldatas)
+        .take(MAX_PROPOSAL_ACTIONS)
+        .enumerate()
+    {
+        let mut action = ProposalActionView {
+            index: index as u32,
+            target: target.clone(),
+            value: value.clone(),
+            calldata: calldata.clone(),
+            target_status: ActionTargetStatus::Unknown,
+            code_hash: None,
+            method: None,
+            arguments: Vec::new(),
+            decode_error: None,
+        };
+
+        let Some(contract) = state.contracts.get(target) else {
+            action.decode_error = Some("no manifest is published for target".to_string());
+            actions.push(action);
+            continue;
+        };
+
+        action.target_status = if contract.verified {
+            ActionTargetStatus::Verified
+        } else {
+            ActionTargetStatus::Unverified
+        };
+        action.code_hash = Some(contract.code_hash.clone());
+
+        match decode_calldata(&contract.abi, calldata) {
+            Ok(call) => {
+                action.method = Some(call.method);
+                action.arguments = call
+                    .arguments
+                    .into_iter()
+                    .map(|argument| DecodedArgumentView {
+                        name: argument.name,
+                        param_type: argument.kind,
+                        value: argument.value,
+                    })
+                    .collect();
+            }
+            Err(err) => action.decode_error = Some(err.to_string()),
+        }
+        actions.push(action);
+    }
+
+    actions
+}
+
+/// Votes on a proposal, newest first. Votes from wallets whose owner hides
+/// their activity from the caller are left out of the page.
 async fn get_proposal_votes(
     Path(proposal_id): Path<i64>,
     Query(query): Query<GetProposalsQuery>,
     State(state): State<AppState>,
+    viewer: Viewer,
 ) -> Result<Json<Vec<VoteView>>, HttpError> {
     assert!(proposal_id >= 0, "Proposal id must be non-negative");
 
@@ -229,8 +500,12 @@ async fn get_proposal_votes(
         "Vote offset exceeds database bounds"
     );
 
+    // Hidden voters are excluded before paging so pages stay full and do not
+    // hint at the votes left out.
+    let hidden = hidden_voters(&state, &viewer, proposal_id).await?;
     let votes = governance_vote::Entity::find()
         .filter(governance_vote::Column::ProposalId.eq(proposal_id))
+        .filter(governance_vote::Column::Voter.is_not_in(hidden))
         .order_by_desc(governance_vote::Column::VotedAt)
         .limit(limit)
         .offset(offset)
@@ -238,16 +513,32 @@ async fn get_proposal_votes(
         .await
         .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
 
+    let snapshot_block = governance_proposal::Entity::find_by_id(proposal_id)
+        .select_only()
+        .column(governance_proposal::Column::SnapshotBlock)
+        .into_tuple::<Option<i64>>()
+        .one(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+        .flatten();
+
+    let snapshot_powers = match snapshot_block {
+        Some(block) => {
+            let voters = votes.iter().map(|v| v.voter.clone()).collect::<Vec<_>>();
+            Some(
+                powers_in_snapshot(&state.database, block, voters)
+                    .await
+                    .map_err(|err| {
+                        HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+                    })?,
+            )
+        }
+        None => None,
+    };
+
     let vote_views = votes
         .into_iter()
-        .map(|v| VoteView {
-            proposal_id: v.proposal_id,
-            voter: v.voter,
-            support: v.support,
-            weight: v.weight,
-            reason: v.reason,
-            voted_at: v.voted_at.timestamp(),
-        })
+        .map(|v| vote_view(v, snapshot_powers.as_ref()))
         .collect::<Vec<_>>();
 
     assert!(
@@ -258,10 +549,93 @@ async fn get_proposal_votes(
     Ok(Json(vote_views))
 }
 
+async fn get_proposal_analytics(
+    Path(proposal_id): Path<i64>,
+    State(state): State<AppState>,
+) -> Result<Json<ProposalAnalyticsView>, HttpError> {
+    assert!(proposal_id >= 0, "Proposal id must be non-negative");
+
+    let proposal = governance_proposal::Entity::find_by_id(proposal_id)
+        .one(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+        .ok_or_else(|| {
+            HttpError::new(
+                StatusCode::NOT_FOUND,
+                format!("Proposal {proposal_id} not found"),
+            )
+        })?;
+
+    let vote_timeline = load_vote_timeline(&state.database, proposal_id)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    let clock = load_chain_clock(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    let eligible_power = match (proposal.snapshot_eligible_power, clock) {
+        (Some(eligible), _) => eligible,
+        (None, Some(clock)) => eligible_voting_power(&state.database, clock.height)
+            .await
+            .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?,
+        (None, None) => 0,
+    };
+
+    let now = clock
+        .map(|clock| clock.timestamp)
+        .unwrap_or_else(|| Utc::now().timestamp());
+
+    let policy = QuorumPolicy::from(&state.governance);
+    let tally = ProposalTally {
+        votes_for: proposal.votes_for,
+        votes_against: proposal.votes_against,
+        votes_abstain: proposal.votes_abstain,
+    };
+    let timeline = ProposalTimeline {
+        vote_start: proposal.vote_start,
+        vote_end: proposal.vote_end,
+        start_height: proposal
+            .snapshot_block
+            .and_then(|block| u64::try_from(block).ok()),
+        end_height: None,
+        executed: proposal.executed_at.is_some(),
+    };
+
+    let participation = tally.participation();
+    let decisive = tally.votes_for.saturating_add(tally.votes_against);
+    let projected = project_tally(&tally, &timeline, now, eligible_power);
+
+    let view = ProposalAnalyticsView {
+        proposal_id: proposal.proposal_id,
+        state: stored_proposal_state(&proposal.state),
+        eligible_voting_power: eligible_power,
+        participation,
+        turnout_pct: percentage(participation, eligible_power),
+        quorum_required: policy.required_quorum(eligible_power),
+        quorum_reached: policy.quorum_reached(&tally, eligible_power),
+        approval_threshold_bps: policy.approval_threshold_bps,
+        approval_pct: percentage(tally.votes_for, decisive),
+        approval_reached: policy.approval_reached(&tally),
+        passing: policy.passes(&tally, eligible_power),
+        margin_to_flip: policy.margin_to_flip(&tally, eligible_power),
+        projected: ProjectedOutcomeView {
+            votes_for: projected.votes_for,
+            votes_against: projected.votes_against,
+            votes_abstain: projected.votes_abstain,
+            turnout_pct: percentage(projected.participation(), eligible_power),
+            passing: policy.passes(&projected, eligible_power),
+        },
+        timeline: vote_timeline,
+    };
+
+    Ok(Json(view))
+}
+
 async fn get_vote_history(
     Path(address): Path<String>,
     Query(query): Query<VoteHistoryQuery>,
     State(state): State<AppState>,
+    viewer: Viewer,
 ) -> Result<Json<Vec<VoteHistoryEntry>>, HttpError> {
     let address = address.trim().to_string();
     if address.is_empty() {
@@ -271,6 +645,8 @@ async fn get_vote_history(
         ));
     }
 
+    viewer.ensure_address_visible(&state, &address).await?;
+
     let requested_limit = query.limit.unwrap_or(100);
     if requested_limit == 0 {
         return Err(HttpError::new(
@@ -322,7 +698,9 @@ async fn get_vote_history(
 
 async fn get_voting_power(
     Path(address): Path<String>,
+    Query(query): Query<VotingPowerQuery>,
     State(state): State<AppState>,
+    viewer: Viewer,
 ) -> Result<Json<VotingPowerView>, HttpError> {
     let address = address.trim().to_string();
     if address.is_empty() {
@@ -331,34 +709,136 @@ async fn get_voting_power(
             "address must not be empty".to_string(),
         ));
     }
+    viewer.ensure_address_visible(&state, &address).await?;
 
     assert!(address.len() <= 128, "Address exceeds defensive bound");
 
-    let delegated_to =
-        sum_delegations(governance_delegation::Column::Delegatee, &address, &state).await?;
-    let delegated_out =
-        sum_delegations(governance_delegation::Column::Delegator, &address, &state).await?;
+    let latest_block = state.last_indexed_block.load(AtomicOrdering::SeqCst);
+    let (power, snapshot_block) = match query.at_block {
+        Some(at_block) => {
+            if at_block > latest_block {
+                return Err(HttpError::new(
+                    StatusCode::BAD_REQUEST,
+                    format!("at_block {at_block} exceeds latest indexed block {latest_block}"),
+                ));
+            }
+
+            let at_block = i64::try_from(at_block).map_err(|_| {
+                HttpError::new(
+                    StatusCode::BAD_REQUEST,
+                    "at_block exceeds storage bounds".to_string(),
+                )
+            })?;
+            let power = power_at(&state.database, at_block, &address)
+                .await
+                .map_err(|err| {
+                    HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+                })?;
+            (power, Some(at_block))
+        }
+        None => {
+            let latest_block = i64::try_from(latest_block).unwrap_or(i64::MAX);
+            let staked = staked_at(&state.database, latest_block, &address)
+                .await
+                .map_err(|err| {
+                    HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+                })?;
+            let power = PowerComponents {
+                staked,
+                delegated_in: sum_delegations(
+                    governance_delegation::Column::Delegatee,
+                    &address,
+                    &state,
+                )
+                .await?,
+                delegated_out: sum_delegations(
+                    governance_delegation::Column::Delegator,
+                    &address,
+                    &state,
+                )
+                .await?,
+            };
+            (power, None)
+        }
+    };
 
-    let net_power = delegated_to - delegated_out;
-    let voting_power = if net_power < 0 { 0 } else { net_power };
-    let total_power = delegated_to.saturating_add(delegated_out);
+    let voting_power = power.voting_power();
 
     assert!(voting_power >= 0, "Voting power cannot be negative");
-    assert!(total_power >= 0, "Total voting power cannot be negative");
 
     let view = VotingPowerView {
         address,
         voting_power,
-        delegated_power: delegated_out,
-        total_power,
+        staked_power: power.staked,
+        delegated_power: power.delegated_out,
+        total_power: voting_power,
+        snapshot_block,
     };
 
     Ok(Json(view))
 }
 
+async fn get_delegates(
+    Query(query): Query<DelegateDirectoryQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<DelegateView>>, HttpError> {
+    let ranking = match query.sort.as_deref() {
+        None => DelegateRanking::Delegation,
+        Some(raw) => DelegateRanking::parse(raw).ok_or_else(|| {
+            HttpError::new(
+                StatusCode::BAD_REQUEST,
+                format!("Unknown delegate ranking '{raw}'"),
+            )
+        })?,
+    };
+
+    let requested_limit = query.limit.unwrap_or(50);
+    if requested_limit == 0 {
+        return Err(HttpError::new(
+            StatusCode::BAD_REQUEST,
+            "limit must be at least 1".to_string(),
+        ));
+    }
+    let limit = requested_limit.min(MAX_DELEGATE_QUERY_LIMIT) as usize;
+    let offset = query.offset.unwrap_or(0).min(MAX_DIRECTORY_DELEGATES) as usize;
+
+    let cache_key = ranking.cache_key();
+    let cached = state
+        .cache
+        .leaderboards
+        .get(&cache_key)
+        .await
+        .and_then(|value| serde_json::from_value::<Vec<DelegateView>>(value).ok());
+
+    let delegates = match cached {
+        Some(delegates) => delegates,
+        None => {
+            let mut delegates = load_delegates(&state.database).await.map_err(|err| {
+                HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+            })?;
+            rank_delegates(&mut delegates, ranking);
+            let value = serde_json::to_value(&delegates).map_err(|err| {
+                HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+            })?;
+            state.cache.leaderboards.insert(cache_key, value).await;
+            delegates
+        }
+    };
+
+    let page = delegates
+        .into_iter()
+        .skip(offset)
+        .take(limit)
+        .collect::<Vec<_>>();
+    assert!(page.len() <= limit, "Delegate page exceeds requested limit");
+
+    Ok(Json(page))
+}
+
 async fn get_delegations(
     Path(address): Path<String>,
     State(state): State<AppState>,
+    viewer: Viewer,
 ) -> Result<Json<Vec<DelegationView>>, HttpError> {
     let address = address.trim().to_string();
     if address.is_empty() {
@@ -369,6 +849,7 @@ async fn get_delegations(
     }
 
     assert!(address.len() <= 128, "Address exceeds defensive bound");
+    viewer.ensure_address_visible(&state, &address).await?;
 
     let delegations = governance_delegation::Entity::find()
         .filter(
@@ -399,6 +880,116 @@ async fn get_delegations(
     Ok(Json(delegation_views))
 }
 
+async fn get_delegator_history(
+    Path(address): Path<String>,
+    Query(query): Query<VoteHistoryQuery>,
+    State(state): State<AppState>,
+    viewer: Viewer,
+) -> Result<Json<Vec<DelegationEventView>>, HttpError> {
+    delegation_history(
+        &state,
+        &viewer,
+        governance_delegation_event::Column::Delegator,
+        address,
+        query,
+    )
+    .await
+}
+
+async fn get_delegatee_history(
+    Path(address): Path<String>,
+    Query(query): Query<VoteHistoryQuery>,
+    State(state): State<AppState>,
+    viewer: Viewer,
+) -> Result<Json<Vec<DelegationEventView>>, HttpError> {
+    delegation_history(
+        &state,
+        &viewer,
+        governance_delegation_event::Column::Delegatee,
+        address,
+        query,
+    )
+    .await
+}
+
+async fn delegation_history(
+    state: &AppState,
+    viewer: &Viewer,
+    column: governance_delegation_event::Column,
+    address: String,
+    query: VoteHistoryQuery,
+) -> Result<Json<Vec<DelegationEventView>>, HttpError> {
+    let address = address.trim().to_string();
+    if address.is_empty() {
+        return Err(HttpError::new(
+            StatusCode::BAD_REQUEST,
+            "address must not be empty".to_string(),
+        ));
+    }
+    if address.len() > 128 {
+        return Err(HttpError::new(
+            StatusCode::BAD_REQUEST,
+            "address exceeds 128 characters".to_string(),
+        ));
+    }
+    viewer.ensure_address_visible(state, &address).await?;
+
+    let requested_limit = query.limit.unwrap_or(100);
+    if requested_limit == 0 {
+        return Err(HttpError::new(
+            StatusCode::BAD_REQUEST,
+            "limit must be at least 1".to_string(),
+        ));
+    }
+
+    let limit = requested_limit.min(MAX_HISTORY_LIMIT);
+    let offset = query.offset.unwrap_or(0);
+    assert!(limit > 0, "Delegation history limit must be positive");
+    assert!(
+        offset <= i64::MAX as u64,
+        "Delegation history offset exceeds bounds"
+    );
+
+    let events = governance_delegation_event::Entity::find()
+        .filter(column.eq(address))
+        .order_by_desc(governance_delegation_event::Column::BlockNumber)
+        .order_by_desc(governance_delegation_event::Column::EventIndex)
+        .limit(limit)
+        .offset(offset)
+        .all(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    let mut history = Vec::with_capacity(events.len());
+    for event in events {
+        let Some(event_type) = DelegationEventKind::parse(&event.event_type) else {
+            warn!(
+                tx_id = %event.tx_id,
+                "Skipping delegation event with unknown type {}", event.event_type
+            );
+            continue;
+        };
+        history.push(DelegationEventView {
+            delegator: event.delegator,
+            delegatee: event.delegatee,
+            previous_delegatee: event.previous_delegatee,
+            event_type,
+            amount: event.amount,
+            block_number: event.block_number,
+            tx_id: event.tx_id,
+            event_index: event.event_index,
+            occurred_at: event.occurred_at.timestamp(),
+        });
+    }
+
+    assert!(
+        history.len() <= limit as usize,
+        "Delegation history result exceeds requested limit",
+    );
+
+    Ok(Json(history))
+}
+
 async fn create_proposal(
     State(_state): State<AppState>,
     Json(request): Json<ProposalCreateRequest>,
@@ -516,7 +1107,7 @@ async fn submit_vote(
     let votes_against = count_to_i64("votes_against", rpc_response.votes_against)?;
     let vote_weight = count_to_i64("vote_weight", rpc_response.vote_weight)?;
 
-    state.cache.proposals.invalidate_all();
+    invalidate_cached_proposal(&state, proposal_id_numeric).await;
 
     let response = VoteSubmissionResponse {
         proposal_id: proposal_id_numeric,
@@ -589,9 +1180,232 @@ async fn delegate_voting_power(
     Ok(Json(response))
 }
 
+async fn export_proposal_votes(
+    Path(proposal_id): Path<i64>,
+    Query(query): Query<ExportQuery>,
+    State(state): State<AppState>,
+    viewer: Viewer,
+) -> Result<Response, HttpError> {
+    let format = export_format(&query)?;
+    if proposal_id < 0 {
+        return Err(HttpError::new(
+            StatusCode::BAD_REQUEST,
+            "proposal_id must be non-negative".to_string(),
+        ));
+    }
+
+    let exists = governance_proposal::Entity::find_by_id(proposal_id)
+        .count(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    if exists == 0 {
+        return Err(HttpError::new(
+            StatusCode::NOT_FOUND,
+            format!("Proposal {proposal_id} not found"),
+        ));
+    }
+
+    let hidden_voters = hidden_voters(&state, &viewer, proposal_id)
+        .await?
+        .into_iter()
+        .collect();
+
+    Ok(export_response(
+        &state,
+        ExportSource::ProposalVotes {
+            proposal_id,
+            hidden_voters,
+        },
+        format,
+        format!("proposal-{proposal_id}-votes"),
+    ))
+}
+
+async fn export_address_activity(
+    Path(address): Path<String>,
+    Query(query): Query<ExportQuery>,
+    State(state): State<AppState>,
+    viewer: Viewer,
+) -> Result<Response, HttpError> {
+    let format = export_format(&query)?;
+    let address = address.trim().to_string();
+    if address.is_empty() || address.len() > 128 {
+        return Err(HttpError::new(
+            StatusCode::BAD_REQUEST,
+            "address must be between 1 and 128 characters".to_string(),
+        ));
+    }
+    viewer.ensure_address_visible(&state, &address).await?;
+
+    let file_stem = format!(
+        "governance-{}",
+        address
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+    );
+    Ok(export_response(
+        &state,
+        ExportSource::Address(address),
+        format,
+        file_stem,
+    ))
+}
+
+fn export_format(query: &ExportQuery) -> Result<ExportFormat, HttpError> {
+    match query.format.as_deref() {
+        None => Ok(ExportFormat::Ndjson),
+        Some(raw) => ExportFormat::parse(raw).ok_or_else(|| {
+            HttpError::new(
+                StatusCode::BAD_REQUEST,
+                format!("Unsupported export format {}", raw.trim()),
+            )
+        }),
+    }
+}
+
+/// Streams an export page by page. A failure mid-stream aborts the body
+/// before the digest trailer, so truncated files are detectable.
+fn export_response(
+    state: &AppState,
+    source: ExportSource,
+    format: ExportFormat,
+    file_stem: String,
+) -> Response {
+    struct ExportProgress {
+        source: ExportSource,
+        cursor: ExportCursor,
+        encoder: Option<ExportEncoder>,
+    }
+
+    let database = state.database.clone();
+    let encoder = ExportEncoder::new(format);
+    let header = encoder.header();
+    let progress = ExportProgress {
+        source,
+        cursor: ExportCursor::START,
+        encoder: Some(encoder),
+    };
+
+    let rows = stream::unfold(progress, move |mut progress| {
+        let database = database.clone();
+        async move {
+            let mut encoder = progress.encoder.take()?;
+            if progress.cursor == ExportCursor::Done {
+                return Some((Ok(Bytes::from(encoder.finish())), progress));
+            }
+            let (records, next) =
+                match next_page(&database, &progress.source, progress.cursor).await {
+                    Ok(page) => page,
+                    Err(err) => {
+                        warn!("Governance export aborted: {err}");
+                        return Some((Err(std::io::Error::other(err.to_string())), progress));
+                    }
+                };
+            let chunk = match encoder.encode(&records) {
+                Ok(chunk) => chunk,
+                Err(err) => {
+                    warn!("Governance export aborted: {err}");
+                    return Some((Err(std::io::Error::other(err.to_string())), progress));
+                }
+            };
+            progress.cursor = next;
+            progress.encoder = Some(encoder);
+            Some((Ok(Bytes::from(chunk)), progress))
+        }
+    });
+    let body = stream::iter(header.map(|header| Ok(Bytes::from(header)))).chain(rows);
+
+    let mut response = Body::from_stream(body).into_response();
+    let headers = response.headers_mut();
+    headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static(format.content_type()),
+    );
+    if let Ok(disposition) = HeaderValue::from_str(&format!(
+        "attachment; filename=\"{file_stem}.{}\"",
+        format.extension()
+    )) {
+        headers.insert(CONTENT_DISPOSITION, disposition);
+    }
+    response
+}
+
+async fn stream_notifications(
+    Query(query): Query<NotificationStreamQuery>,
+    State(state): State<AppState>,
+    viewer: Viewer,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, HttpError> {
+    let kinds = match query.events.as_deref() {
+        None => Vec::new(),
+        Some(raw) => raw
+            .split(',')
+            .filter(|part| !part.trim().is_empty())
+            .map(|part| {
+                GovernanceEventKind::parse(part).ok_or_else(|| {
+                    HttpError::new(
+                        StatusCode::BAD_REQUEST,
+                        format!("Unknown governance event {}", part.trim()),
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+    let non_empty = |value: Option<String>| {
+        value
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let filter = NotificationFilter {
+        proposer: non_empty(query.proposer),
+        voter: non_empty(query.voter),
+        kinds,
+    };
+    // A voter filter reveals which proposals the address voted on.
+    if let Some(voter) = filter.voter.as_deref() {
+        viewer.ensure_address_visible(&state, voter).await?;
+    }
+
+    let receiver = state.notifier.subscribe();
+    let database = state.database.clone();
+    let events = stream::unfold(
+        (receiver, filter, database),
+        |(mut receiver, filter, database)| async move {
+            loop {
+                let notification = match receiver.recv().await {
+                    Ok(notification) => notification,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Notification stream client lagged; {skipped} events dropped");
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                };
+                match filter.matches(&database, &notification).await {
+                    Ok(true) => {}
+                    Ok(false) => continue,
+                    Err(err) => {
+                        warn!("Failed to evaluate notification filter: {err}");
+                        continue;
+                    }
+                }
+                match Event::default()
+                    .event(notification.kind.as_str())
+                    .json_data(&*notification)
+                {
+                    Ok(event) => return Some((Ok(event), (receiver, filter, database))),
+                    Err(err) => warn!("Failed to encode governance notification: {err}"),
+                }
+            }
+        },
+    );
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
 async fn get_governance_stats(
     Path(address): Path<String>,
     State(state): State<AppState>,
+    viewer: Viewer,
 ) -> Result<Json<GovernanceStatsView>, HttpError> {
     let address = address.trim().to_string();
     if address.is_empty() {
@@ -602,6 +1416,7 @@ async fn get_governance_stats(
     }
 
     assert!(address.len() <= 128, "Address exceeds defensive bound");
+    viewer.ensure_address_visible(&state, &address).await?;
 
     let submitted_raw = governance_proposal::Entity::find()
         .filter(governance_proposal::Column::Proposer.eq(address.clone()))
@@ -623,11 +1438,7 @@ async fn get_governance_stats(
     let proposals_submitted = count_to_i64("proposals submitted", submitted_raw)?;
     let votes_cast = count_to_i64("votes cast", votes_cast_raw)?;
 
-    let participation_rate = if total_proposals_raw == 0 {
-        0.0
-    } else {
-        ((votes_cast_raw as f64) / (total_proposals_raw as f64)).min(1.0)
-    };
+    let participation_rate = participation_rate(votes_cast_raw, total_proposals_raw);
 
     assert!(
         participation_rate >= 0.0,
@@ -696,6 +1507,38 @@ async fn sum_delegations(
     Ok(total)
 }
 
+fn vote_view(vote: governance_vote::Model, snapshot: Option<&HashMap<String, i64>>) -> VoteView {
+    let snapshot_power = snapshot.map(|powers| powers.get(&vote.voter).copied().unwrap_or(0));
+    let weight_verified = snapshot_power.map(|power| vote.weight <= power);
+    if weight_verified == Some(false) {
+        warn!(
+            proposal_id = vote.proposal_id,
+            voter = %vote.voter,
+            "Vote weight {} exceeds snapshot voting power {}",
+            vote.weight,
+            snapshot_power.unwrap_or(0)
+        );
+    }
+
+    VoteView {
+        proposal_id: vote.proposal_id,
+        voter: vote.voter,
+        support: vote.support,
+        weight: vote.weight,
+        reason: vote.reason,
+        voted_at: vote.voted_at.timestamp(),
+        snapshot_power,
+        weight_verified,
+    }
+}
+
+fn stored_proposal_state(raw: &str) -> ProposalState {
+    ProposalState::parse(raw).unwrap_or_else(|| {
+        warn!("Unknown stored proposal state {raw}; reporting as pending");
+        ProposalState::Pending
+    })
+}
+
 fn count_to_i64(label: &str, count: u64) -> Result<i64, HttpError> {
     i64::try_from(count).map_err(|_| {
         HttpError::new(
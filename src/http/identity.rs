@@ -1,28 +1,106 @@
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering as AtomicOrdering};
 
-use axum::extract::{Path, Query, State};
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, Path, Query, State};
 use axum::http::StatusCode;
-use axum::routing::{get, post};
+use axum::http::header::{CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_NONE_MATCH};
+use axum::http::{HeaderMap, HeaderValue};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post, put};
 use axum::{Json, Router};
+use chrono::Utc;
+use futures::future;
+use rand::RngCore;
+use rand::rngs::OsRng;
 use sea_orm::prelude::*;
-use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use sea_orm::{
+    ColumnTrait, DbBackend, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Statement,
+};
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 
-use crate::entities::{identity_profile, wallet_link};
+use crate::avatar::{
+    AVATAR_VARIANT_SIZES, AvatarError, AvatarFormat, MAX_AVATAR_UPLOAD_BYTES, original_key,
+    resize_avatar, validate_avatar, variant_key,
+};
+use crate::entities::{
+    governance_proposal, identity_profile, identity_profile_history, social_connection, wallet_link,
+};
+use crate::feed::{
+    FeedCursor, FeedScope, MAX_FEED_IDENTITIES, MAX_FEED_PAGE, MAX_FEED_WALLETS, load_feed,
+};
+use crate::governance::participation_rate;
 use crate::identity::{
-    decode_identity_id, decode_signature, encode_identity_id, sanitize_wallet_address,
+    AVATAR_HASH_BYTES, LinkProofError, MAX_WALLET_LINKS, VISIBILITY_PUBLIC,
+    WALLET_CHALLENGE_NONCE_BYTES, WalletChallenge, WalletSignatureScheme,
+    canonicalize_display_name, decode_hex_with_expected, decode_identity_id, decode_public_key,
+    decode_signature, display_name_search_key, display_name_skeleton, encode_identity_id,
+    normalize_link_type, resolve_wallet_key, sanitize_wallet_address, verify_link_proof,
+    wallet_link_message,
 };
-use crate::models::identity::{IdentityProfileView, IdentitySearchResult, WalletLinkView};
+use crate::identity_stats::{identity_activity_totals, total_balance};
+use crate::models::identity::{
+    ActivityFeedView, FollowEntryView, FollowListView, FollowRelationView, IdentityProfileView,
+    IdentitySearchResult, WalletLinkView, WalletOwnerView,
+};
+use crate::social::{self, MAX_FOLLOW_PAGE};
 use crate::state::AppState;
 
 use super::HttpError;
+use super::viewer::Viewer;
+
+const MAX_WALLET_OWNER_BATCH: usize = 100;
+const MAX_IDENTITY_BATCH: usize = 100;
+const MAX_CONFUSABLE_MATCHES: u32 = 100;
+const MAX_HISTORY_PAGE: u64 = 100;
+
+/// Trigram search over `display_name_search`. Exact and prefix matches are
+/// boosted above the similarity score so `$1 = "ali"` ranks "ali" and
+/// "alice" ahead of a closer-length fuzzy hit; literal substring matches are
+/// kept for queries too short to share trigrams.
+const SEARCH_PROFILES_SQL: &str = r#"
+SELECT * FROM identity_profiles
+WHERE display_name_search IS NOT NULL
+  AND (display_name_search % $1 OR STRPOS(display_name_search, $1) > 0)
+ORDER BY
+  CASE
+    WHEN display_name_search = $1 THEN 2.0
+    WHEN STARTS_WITH(display_name_search, $1) THEN 1.0
+    ELSE 0.0
+  END + SIMILARITY(display_name_search, $1) DESC,
+  updated_at DESC
+LIMIT $2
+"#;
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/search", get(search_profiles))
+        .route("/confusables", get(find_confusable_names))
+        .route("/batch", post(get_profiles_batch))
+        .route(
+            "/avatars/:avatar_hash",
+            put(upload_avatar).layer(DefaultBodyLimit::max(MAX_AVATAR_UPLOAD_BYTES)),
+        )
+        .route("/by-wallet", post(get_wallet_owners))
+        .route("/by-wallet/:address", get(get_wallet_owner))
+        .route("/feed/following", get(get_following_feed))
         .route("/:identity_id", get(get_profile))
         .route("/:identity_id/wallets", get(get_wallets))
+        .route("/:identity_id/followers", get(get_followers))
+        .route("/:identity_id/following", get(get_following))
+        .route("/:identity_id/follows/:other_id", get(get_follow_relation))
+        .route("/:identity_id/feed", get(get_identity_feed))
+        .route("/:identity_id/history", get(get_profile_history))
+        .route("/:identity_id/stats", get(get_identity_stats))
+        .route("/:identity_id/avatar", get(get_avatar))
         .route("/:identity_id/wallets/verify", post(verify_wallet_link))
+        .route(
+            "/:identity_id/wallets/challenge",
+            post(issue_wallet_challenge),
+        )
+        .route("/:identity_id/wallets/link", post(submit_wallet_link))
 }
 
 async fn get_profile(
@@ -33,61 +111,212 @@ async fn get_profile(
         .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
     let canonical_id = encode_identity_id(&identity_bytes);
 
-    if let Some(cached) = state.cache.identity_profiles.get(&canonical_id).await {
-        return Ok(Json((*cached).clone()));
-    }
-
-    let profile = identity_profile::Entity::find_by_id(identity_bytes.clone())
-        .one(&state.database)
-        .await
-        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+    let mut views = load_profile_views(&state, vec![identity_bytes]).await?;
+    views
+        .remove(&canonical_id)
+        .map(|view| Json((*view).clone()))
         .ok_or_else(|| {
             HttpError::new(
                 StatusCode::NOT_FOUND,
                 format!("Identity {identity_id} not found"),
             )
-        })?;
+        })
+}
 
-    let wallet_count = wallet_link::Entity::find()
-        .filter(wallet_link::Column::IdentityId.eq(identity_bytes.clone()))
-        .count(&state.database)
-        .await
-        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+/// Resolves up to [`MAX_IDENTITY_BATCH`] identity ids and wallet addresses in
+/// one round trip. Unknown ids and addresses without a public owner are left
+/// out rather than failing the batch.
+async fn get_profiles_batch(
+    State(state): State<AppState>,
+    Json(payload): Json<IdentityBatchRequest>,
+) -> Result<Json<IdentityBatchView>, HttpError> {
+    if payload.identity_ids.len() + payload.wallet_addresses.len() > MAX_IDENTITY_BATCH {
+        return Err(HttpError::new(
+            StatusCode::BAD_REQUEST,
+            format!("At most {MAX_IDENTITY_BATCH} identity ids and wallet addresses per request"),
+        ));
+    }
+
+    let mut identity_ids = Vec::with_capacity(MAX_IDENTITY_BATCH);
+    for identity_id in &payload.identity_ids {
+        let identity_bytes = decode_identity_id(identity_id)
+            .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
+        if !identity_ids.contains(&identity_bytes) {
+            identity_ids.push(identity_bytes);
+        }
+    }
+    let mut addresses = Vec::with_capacity(payload.wallet_addresses.len());
+    for address in &payload.wallet_addresses {
+        let address = sanitize_wallet_address(address)
+            .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
+        if !addresses.contains(&address) {
+            addresses.push(address);
+        }
+    }
+
+    let owners = resolve_wallet_owners(&state, addresses.clone()).await?;
+    let mut wallet_owners = BTreeMap::new();
+    for address in addresses {
+        let Some(owner) = owners.get(&address).cloned().flatten() else {
+            continue;
+        };
+        let identity_bytes = decode_identity_id(&owner.identity_id)
+            .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+        if !identity_ids.contains(&identity_bytes) {
+            identity_ids.push(identity_bytes);
+        }
+        wallet_owners.insert(address, owner.identity_id.clone());
+    }
+
+    let order = identity_ids
+        .iter()
+        .map(|id| encode_identity_id(id))
+        .collect::<Vec<_>>();
+    let mut views = load_profile_views(&state, identity_ids).await?;
+    let profiles = order
+        .iter()
+        .filter_map(|id| views.remove(id))
+        .map(|view| (*view).clone())
+        .collect();
+
+    Ok(Json(IdentityBatchView {
+        profiles,
+        wallet_owners,
+    }))
+}
+
+/// Builds profile views through the `identity_profiles` cache. Misses are
+/// loaded with one query each for profiles, wallet counts and name
+/// collisions, however many identities are requested. Unknown identities are
+/// absent from the result.
+async fn load_profile_views(
+    state: &AppState,
+    identity_ids: Vec<Vec<u8>>,
+) -> Result<HashMap<String, Arc<IdentityProfileView>>, HttpError> {
     assert!(
-        wallet_count <= u32::MAX as u64,
-        "Wallet count exceeds u32 bounds"
+        identity_ids.len() <= MAX_IDENTITY_BATCH,
+        "Profile batch exceeds bound"
     );
+    let mut views = HashMap::with_capacity(identity_ids.len());
+    let mut misses = Vec::new();
+    for identity_bytes in identity_ids {
+        let canonical_id = encode_identity_id(&identity_bytes);
+        match state.cache.identity_profiles.get(&canonical_id).await {
+            Some(cached) => {
+                views.insert(canonical_id, cached);
+            }
+            None => misses.push(identity_bytes),
+        }
+    }
+    if misses.is_empty() {
+        return Ok(views);
+    }
 
-    let view = IdentityProfileView {
-        identity_id: canonical_id.clone(),
-        display_name: profile.display_name.clone(),
-        avatar_hash: profile.avatar_hash.as_ref().map(hex::encode),
-        bio: profile.bio.clone(),
-        stats_visibility: profile.stats_visibility.clone(),
-        wallet_count: wallet_count as u32,
-        created_at: profile.created_at,
-        updated_at: profile.updated_at,
-        last_synced_block: profile.last_synced_block,
-        profile_version: profile.profile_version,
+    let profiles = identity_profile::Entity::find()
+        .filter(identity_profile::Column::IdentityId.is_in(misses))
+        .all(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    if profiles.is_empty() {
+        return Ok(views);
+    }
+    let found = profiles
+        .iter()
+        .map(|profile| profile.identity_id.clone())
+        .collect::<Vec<_>>();
+
+    let wallet_counts = wallet_link::Entity::find()
+        .filter(wallet_link::Column::IdentityId.is_in(found))
+        .select_only()
+        .column(wallet_link::Column::IdentityId)
+        .column_as(wallet_link::Column::WalletAddress.count(), "wallet_count")
+        .group_by(wallet_link::Column::IdentityId)
+        .into_tuple::<(Vec<u8>, i64)>()
+        .all(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+
+    // A skeleton shared by more than one identity is a collision for each
+    // of them.
+    let skeletons = profiles
+        .iter()
+        .filter_map(|profile| profile.display_name_skeleton.clone())
+        .collect::<Vec<_>>();
+    let skeleton_counts = if skeletons.is_empty() {
+        HashMap::new()
+    } else {
+        identity_profile::Entity::find()
+            .filter(identity_profile::Column::DisplayNameSkeleton.is_in(skeletons))
+            .select_only()
+            .column(identity_profile::Column::DisplayNameSkeleton)
+            .column_as(identity_profile::Column::IdentityId.count(), "holders")
+            .group_by(identity_profile::Column::DisplayNameSkeleton)
+            .into_tuple::<(String, i64)>()
+            .all(&state.database)
+            .await
+            .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+            .into_iter()
+            .collect::<HashMap<_, _>>()
     };
 
-    state
-        .cache
-        .identity_profiles
-        .insert(canonical_id.clone(), Arc::new(view.clone()))
-        .await;
+    for profile in profiles {
+        let canonical_id = encode_identity_id(&profile.identity_id);
+        let wallet_count = wallet_counts
+            .get(&profile.identity_id)
+            .copied()
+            .unwrap_or(0);
+        assert!(
+            (0..=u32::MAX as i64).contains(&wallet_count),
+            "Wallet count exceeds u32 bounds"
+        );
+        let name_collision = profile
+            .display_name_skeleton
+            .as_ref()
+            .and_then(|skeleton| skeleton_counts.get(skeleton))
+            .is_some_and(|holders| *holders > 1);
 
-    Ok(Json(view))
+        let view = Arc::new(IdentityProfileView {
+            identity_id: canonical_id.clone(),
+            display_name: profile.display_name,
+            avatar_hash: profile.avatar_hash.as_ref().map(hex::encode),
+            bio: profile.bio,
+            stats_visibility: profile.stats_visibility,
+            wallet_count: wallet_count as u32,
+            created_at: profile.created_at,
+            updated_at: profile.updated_at,
+            last_synced_block: profile.last_synced_block,
+            profile_version: profile.profile_version,
+            name_collision,
+        });
+        state
+            .cache
+            .identity_profiles
+            .insert(canonical_id.clone(), view.clone())
+            .await;
+        views.insert(canonical_id, view);
+    }
+    Ok(views)
 }
 
 async fn get_wallets(
     Path(identity_id): Path<String>,
     State(state): State<AppState>,
+    viewer: Viewer,
 ) -> Result<Json<Vec<WalletLinkView>>, HttpError> {
     let identity_bytes = decode_identity_id(&identity_id)
         .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
     let canonical_id = encode_identity_id(&identity_bytes);
 
+    let owner = identity_profile::Entity::find_by_id(identity_bytes.clone())
+        .one(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    if let Some(owner) = &owner {
+        viewer.ensure_identity_visible(&state, owner).await?;
+    }
+
     if let Some(cached) = state.cache.identity_wallets.get(&canonical_id).await {
         return Ok(Json((*cached).clone()));
     }
@@ -126,6 +355,729 @@ async fn get_wallets(
     Ok(Json(views))
 }
 
+async fn get_followers(
+    Path(identity_id): Path<String>,
+    Query(query): Query<FollowPageQuery>,
+    State(state): State<AppState>,
+    viewer: Viewer,
+) -> Result<Json<FollowListView>, HttpError> {
+    follow_page(
+        &state,
+        &viewer,
+        &identity_id,
+        FollowDirection::Followers,
+        query,
+    )
+    .await
+}
+
+async fn get_following(
+    Path(identity_id): Path<String>,
+    Query(query): Query<FollowPageQuery>,
+    State(state): State<AppState>,
+    viewer: Viewer,
+) -> Result<Json<FollowListView>, HttpError> {
+    follow_page(
+        &state,
+        &viewer,
+        &identity_id,
+        FollowDirection::Following,
+        query,
+    )
+    .await
+}
+
+#[derive(Debug, Clone, Copy)]
+enum FollowDirection {
+    Followers,
+    Following,
+}
+
+/// A page of follow edges. Lists of an identity that hides its activity
+/// from the viewer are refused, and listed identities hidden from the viewer
+/// are left out of the page; `total` still counts every edge.
+async fn follow_page(
+    state: &AppState,
+    viewer: &Viewer,
+    identity_id: &str,
+    direction: FollowDirection,
+    query: FollowPageQuery,
+) -> Result<Json<FollowListView>, HttpError> {
+    let identity_bytes = decode_identity_id(identity_id)
+        .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
+    let owner = identity_profile::Entity::find_by_id(identity_bytes.clone())
+        .one(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    if let Some(owner) = &owner {
+        viewer.ensure_identity_visible(state, owner).await?;
+    }
+    let limit = query.limit.unwrap_or(50);
+    if limit == 0 || limit > MAX_FOLLOW_PAGE {
+        return Err(HttpError::new(
+            StatusCode::BAD_REQUEST,
+            format!("limit must be between 1 and {MAX_FOLLOW_PAGE}"),
+        ));
+    }
+    let offset = query.offset.unwrap_or(0);
+
+    // `own` is the column holding the requested identity, `other` the
+    // column listed back.
+    let (own, other) = match direction {
+        FollowDirection::Followers => (
+            social_connection::Column::FollowingId,
+            social_connection::Column::FollowerId,
+        ),
+        FollowDirection::Following => (
+            social_connection::Column::FollowerId,
+            social_connection::Column::FollowingId,
+        ),
+    };
+    let edges = social_connection::Entity::find().filter(own.eq(identity_bytes.clone()));
+    let total = edges
+        .clone()
+        .count(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    let page = edges
+        .order_by_desc(social_connection::Column::CreatedAt)
+        .order_by_asc(other)
+        .limit(limit)
+        .offset(offset)
+        .all(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    let listed = page
+        .iter()
+        .map(|edge| match direction {
+            FollowDirection::Followers => edge.follower_id.clone(),
+            FollowDirection::Following => edge.following_id.clone(),
+        })
+        .collect::<Vec<_>>();
+    let profiles = identity_profile::Entity::find()
+        .filter(identity_profile::Column::IdentityId.is_in(listed.clone()))
+        .all(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    let mut names = HashMap::with_capacity(profiles.len());
+    let mut hidden = Vec::new();
+    for profile in profiles {
+        if !viewer.may_view(state, &profile).await? {
+            hidden.push(profile.identity_id);
+            continue;
+        }
+        names.insert(profile.identity_id, profile.display_name);
+    }
+
+    let entries = page
+        .iter()
+        .zip(listed)
+        .filter(|(_, listed_id)| !hidden.contains(listed_id))
+        .map(|(edge, listed_id)| FollowEntryView {
+            identity_id: encode_identity_id(&listed_id),
+            display_name: names.get(&listed_id).cloned().flatten(),
+            followed_at: edge.created_at,
+        })
+        .collect();
+
+    Ok(Json(FollowListView {
+        identity_id: encode_identity_id(&identity_bytes),
+        total,
+        limit,
+        offset,
+        entries,
+    }))
+}
+
+/// Follow edges between two identities. Both must be visible to the caller,
+/// as their follow lists would be.
+async fn get_follow_relation(
+    Path((identity_id, other_id)): Path<(String, String)>,
+    State(state): State<AppState>,
+    viewer: Viewer,
+) -> Result<Json<FollowRelationView>, HttpError> {
+    let identity_bytes = decode_identity_id(&identity_id)
+        .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
+    let other_bytes = decode_identity_id(&other_id)
+        .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
+    let profiles = identity_profile::Entity::find()
+        .filter(
+            identity_profile::Column::IdentityId
+                .is_in([identity_bytes.clone(), other_bytes.clone()]),
+        )
+        .all(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    for profile in &profiles {
+        viewer.ensure_identity_visible(&state, profile).await?;
+    }
+
+    let follows = social::follows(&state.database, &identity_bytes, &other_bytes)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    let followed_by = social::follows(&state.database, &other_bytes, &identity_bytes)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    Ok(Json(FollowRelationView {
+        identity_id: encode_identity_id(&identity_bytes),
+        other_id: encode_identity_id(&other_bytes),
+        follows,
+        followed_by,
+        mutual: follows && followed_by,
+    }))
+}
+
+async fn get_identity_feed(
+    Path(identity_id): Path<String>,
+    Query(query): Query<FeedQuery>,
+    State(state): State<AppState>,
+    viewer: Viewer,
+) -> Result<Json<ActivityFeedView>, HttpError> {
+    let identity_bytes = decode_identity_id(&identity_id)
+        .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
+    let (cursor, limit) = query.validate()?;
+
+    let profile = identity_profile::Entity::find_by_id(identity_bytes.clone())
+        .one(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+        .ok_or_else(|| {
+            HttpError::new(
+                StatusCode::NOT_FOUND,
+                format!("Identity {identity_id} not found"),
+            )
+        })?;
+    viewer.ensure_identity_visible(&state, &profile).await?;
+
+    let scope = feed_scope(&state, vec![profile]).await?;
+    feed_response(&state, &scope, cursor.as_ref(), limit).await
+}
+
+/// Merged activity of every identity the caller follows and may view.
+async fn get_following_feed(
+    Query(query): Query<FeedQuery>,
+    State(state): State<AppState>,
+    viewer: Viewer,
+) -> Result<Json<ActivityFeedView>, HttpError> {
+    let Some(viewer_id) = viewer.identity_id() else {
+        return Err(HttpError::new(
+            StatusCode::UNAUTHORIZED,
+            "The following feed requires Identity authorization".to_string(),
+        ));
+    };
+    let (cursor, limit) = query.validate()?;
+
+    let followed = social_connection::Entity::find()
+        .filter(social_connection::Column::FollowerId.eq(viewer_id.to_vec()))
+        .order_by_desc(social_connection::Column::CreatedAt)
+        .limit(MAX_FEED_IDENTITIES)
+        .all(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+        .into_iter()
+        .map(|edge| edge.following_id)
+        .collect::<Vec<_>>();
+    let profiles = identity_profile::Entity::find()
+        .filter(identity_profile::Column::IdentityId.is_in(followed))
+        .all(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    let mut visible = Vec::with_capacity(profiles.len());
+    for profile in profiles {
+        if viewer.may_view(&state, &profile).await? {
+            visible.push(profile);
+        }
+    }
+
+    let scope = feed_scope(&state, visible).await?;
+    feed_response(&state, &scope, cursor.as_ref(), limit).await
+}
+
+/// Only verified links count towards a feed; an unverified claim on a
+/// wallet must not surface someone else's activity.
+async fn feed_scope(
+    state: &AppState,
+    profiles: Vec<identity_profile::Model>,
+) -> Result<FeedScope, HttpError> {
+    let identity_ids = profiles
+        .iter()
+        .map(|profile| profile.identity_id.clone())
+        .collect::<Vec<_>>();
+    let links = wallet_link::Entity::find()
+        .filter(wallet_link::Column::IdentityId.is_in(identity_ids))
+        .filter(wallet_link::Column::VerifiedAt.is_not_null())
+        .order_by_asc(wallet_link::Column::WalletAddress)
+        .limit(MAX_FEED_WALLETS as u64)
+        .all(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    let mut wallets = HashMap::with_capacity(links.len());
+    for link in links {
+        wallets
+            .entry(link.wallet_address)
+            .or_insert(link.identity_id);
+    }
+    Ok(FeedScope { wallets, profiles })
+}
+
+async fn feed_response(
+    state: &AppState,
+    scope: &FeedScope,
+    cursor: Option<&FeedCursor>,
+    limit: u64,
+) -> Result<Json<ActivityFeedView>, HttpError> {
+    let page = load_feed(&state.database, scope, cursor, limit)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    Ok(Json(ActivityFeedView {
+        items: page.items,
+        next_cursor: page.next_cursor.map(|cursor| cursor.encode()),
+    }))
+}
+
+/// Every recorded version of a profile, kept even after the profile or its
+/// wallet links change, so past names and wallets can be investigated.
+async fn get_profile_history(
+    Path(identity_id): Path<String>,
+    Query(query): Query<HistoryPageQuery>,
+    State(state): State<AppState>,
+    viewer: Viewer,
+) -> Result<Json<ProfileHistoryView>, HttpError> {
+    let identity_bytes = decode_identity_id(&identity_id)
+        .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
+    let limit = query.limit.unwrap_or(50);
+    if limit == 0 || limit > MAX_HISTORY_PAGE {
+        return Err(HttpError::new(
+            StatusCode::BAD_REQUEST,
+            format!("limit must be between 1 and {MAX_HISTORY_PAGE}"),
+        ));
+    }
+    let offset = query.offset.unwrap_or(0);
+
+    let versions = identity_profile_history::Entity::find()
+        .filter(identity_profile_history::Column::IdentityId.eq(identity_bytes.clone()));
+    let total = versions
+        .clone()
+        .count(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    if total == 0 {
+        return Err(HttpError::new(
+            StatusCode::NOT_FOUND,
+            format!("No history recorded for identity {identity_id}"),
+        ));
+    }
+    // One extra row gives the oldest entry on the page something to diff
+    // against.
+    let rows = versions
+        .order_by_desc(identity_profile_history::Column::ProfileVersion)
+        .order_by_desc(identity_profile_history::Column::LastSyncedBlock)
+        .limit(limit + 1)
+        .offset(offset)
+        .all(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    // Wallet changes follow the identity's current visibility; a deleted
+    // profile has no owner to ask, so its wallets stay hidden.
+    let owner = identity_profile::Entity::find_by_id(identity_bytes.clone())
+        .one(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    let wallets_visible = match &owner {
+        Some(owner) => viewer.may_view(&state, owner).await?,
+        None => false,
+    };
+
+    let entries = rows
+        .iter()
+        .take(limit as usize)
+        .enumerate()
+        .map(|(index, row)| {
+            let previous = rows.get(index + 1);
+            ProfileHistoryEntryView {
+                profile_version: row.profile_version,
+                last_synced_block: row.last_synced_block,
+                updated_at: row.updated_at,
+                display_name: row.display_name.clone(),
+                avatar_hash: row.avatar_hash.as_ref().map(hex::encode),
+                bio: row.bio.clone(),
+                stats_visibility: row.stats_visibility.clone(),
+                changed_fields: changed_profile_fields(previous, row),
+                wallets_added: wallets_visible.then(|| history_addresses(&row.wallets_added)),
+                wallets_removed: wallets_visible.then(|| history_addresses(&row.wallets_removed)),
+            }
+        })
+        .collect();
+
+    Ok(Json(ProfileHistoryView {
+        identity_id: encode_identity_id(&identity_bytes),
+        total,
+        limit,
+        offset,
+        entries,
+    }))
+}
+
+fn changed_profile_fields(
+    previous: Option<&identity_profile_history::Model>,
+    current: &identity_profile_history::Model,
+) -> Vec<&'static str> {
+    let Some(previous) = previous else {
+        let mut fields = Vec::new();
+        if current.display_name.is_some() {
+            fields.push("display_name");
+        }
+        if current.avatar_hash.is_some() {
+            fields.push("avatar_hash");
+        }
+        if current.bio.is_some() {
+            fields.push("bio");
+        }
+        fields.push("stats_visibility");
+        return fields;
+    };
+    let mut fields = Vec::new();
+    if previous.display_name != current.display_name {
+        fields.push("display_name");
+    }
+    if previous.avatar_hash != current.avatar_hash {
+        fields.push("avatar_hash");
+    }
+    if previous.bio != current.bio {
+        fields.push("bio");
+    }
+    if previous.stats_visibility != current.stats_visibility {
+        fields.push("stats_visibility");
+    }
+    fields
+}
+
+fn history_addresses(value: &serde_json::Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|addresses| {
+            addresses
+                .iter()
+                .filter_map(|address| address.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+async fn get_identity_stats(
+    Path(identity_id): Path<String>,
+    State(state): State<AppState>,
+    viewer: Viewer,
+) -> Result<Json<IdentityStatsView>, HttpError> {
+    let identity_bytes = decode_identity_id(&identity_id)
+        .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
+    let canonical_id = encode_identity_id(&identity_bytes);
+
+    let profile = identity_profile::Entity::find_by_id(identity_bytes.clone())
+        .one(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+        .ok_or_else(|| {
+            HttpError::new(
+                StatusCode::NOT_FOUND,
+                format!("Identity {identity_id} not found"),
+            )
+        })?;
+    // Checked before the cache so a cached entry never leaks past a
+    // visibility change.
+    viewer.ensure_identity_visible(&state, &profile).await?;
+
+    // Entries from an older block are recomputed, so indexed activity shows
+    // up without waiting for the TTL.
+    let indexed_block = state.last_indexed_block.load(AtomicOrdering::SeqCst);
+    let cached = state.cache.identity_stats.get(&canonical_id).await;
+    if let Some(cached) = cached.filter(|cached| cached.0 == indexed_block) {
+        return Ok(Json(cached.1.clone()));
+    }
+
+    let wallets = wallet_link::Entity::find()
+        .filter(wallet_link::Column::IdentityId.eq(identity_bytes.clone()))
+        .filter(wallet_link::Column::VerifiedAt.is_not_null())
+        .order_by_asc(wallet_link::Column::WalletAddress)
+        .all(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+        .into_iter()
+        .map(|link| link.wallet_address)
+        .collect::<Vec<_>>();
+    assert!(
+        wallets.len() <= MAX_WALLET_LINKS,
+        "Wallet link bound exceeded"
+    );
+
+    let totals = identity_activity_totals(&state.database, &wallets)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    let total_proposals = governance_proposal::Entity::find()
+        .count(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    let balances = future::join_all(wallets.iter().map(|wallet| async {
+        match state.rpc.fetch_account_balance(wallet).await {
+            Ok(balance) => Some(balance),
+            Err(err) => {
+                warn!(wallet = %wallet, "Failed to fetch wallet balance: {err:#}");
+                None
+            }
+        }
+    }))
+    .await;
+
+    let wallet_views = wallets
+        .iter()
+        .zip(&balances)
+        .map(|(wallet, balance)| {
+            let counts = totals.wallets.get(wallet).copied().unwrap_or_default();
+            WalletStatsView {
+                wallet_address: wallet.clone(),
+                balance: *balance,
+                transactions_sent: counts.sent,
+                transactions_received: counts.received,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let view = IdentityStatsView {
+        identity_id: canonical_id.clone(),
+        wallet_count: wallets.len() as u32,
+        proposals_submitted: totals.proposals_submitted,
+        votes_cast: totals.votes_cast,
+        proposals_voted: totals.proposals_voted,
+        participation_rate: participation_rate(totals.proposals_voted, total_proposals),
+        delegated_in: totals.delegated_in,
+        delegated_out: totals.delegated_out,
+        net_voting_power: (totals.delegated_in - totals.delegated_out).max(0),
+        total_balance: total_balance(&balances),
+        transactions_sent: wallet_views.iter().map(|w| w.transactions_sent).sum(),
+        transactions_received: wallet_views.iter().map(|w| w.transactions_received).sum(),
+        first_activity_at: totals.first_activity_at,
+        last_activity_at: totals.last_activity_at,
+        wallets: wallet_views,
+    };
+
+    // A failed balance lookup is reported once but never cached.
+    if balances.iter().all(Option::is_some) {
+        state
+            .cache
+            .identity_stats
+            .insert(canonical_id, Arc::new((indexed_block, view.clone())))
+            .await;
+    }
+    Ok(Json(view))
+}
+
+/// Stores an avatar under its content hash. Only hashes that some profile
+/// references are accepted, so the store cannot be used for arbitrary files;
+/// upload after the profile update carrying the hash has been indexed.
+async fn upload_avatar(
+    Path(avatar_hash): Path<String>,
+    State(state): State<AppState>,
+    body: Bytes,
+) -> Result<Json<AvatarUploadView>, HttpError> {
+    let hash = decode_hex_with_expected(&avatar_hash, AVATAR_HASH_BYTES, "avatar hash")
+        .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
+
+    let referenced = identity_profile::Entity::find()
+        .filter(identity_profile::Column::AvatarHash.eq(hash.clone()))
+        .count(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    if referenced == 0 {
+        return Err(HttpError::new(
+            StatusCode::NOT_FOUND,
+            format!("No profile references avatar {}", hex::encode(&hash)),
+        ));
+    }
+
+    let format = validate_avatar(&body, &hash, &state.avatars.config).map_err(|err| {
+        let status = match err {
+            AvatarError::TooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            AvatarError::UnsupportedType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        HttpError::new(status, err.to_string())
+    })?;
+
+    let key = original_key(&hash);
+    let stored = state
+        .avatars
+        .store
+        .get(&key)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+        .is_some();
+    if !stored {
+        state
+            .avatars
+            .store
+            .put(&key, body.to_vec())
+            .await
+            .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    }
+
+    Ok(Json(AvatarUploadView {
+        avatar_hash: hex::encode(&hash),
+        content_type: format.content_type(),
+        size_bytes: body.len() as u64,
+    }))
+}
+
+/// Serves an identity's current avatar, or a square PNG variant with
+/// `?size=`. ETags are derived from the content hash, so they are strong
+/// and survive any number of replicas.
+async fn get_avatar(
+    Path(identity_id): Path<String>,
+    Query(query): Query<AvatarQuery>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Response, HttpError> {
+    let identity_bytes = decode_identity_id(&identity_id)
+        .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
+    let canonical_id = encode_identity_id(&identity_bytes);
+    if let Some(size) = query.size {
+        if !AVATAR_VARIANT_SIZES.contains(&size) {
+            return Err(HttpError::new(
+                StatusCode::BAD_REQUEST,
+                format!("size must be one of {AVATAR_VARIANT_SIZES:?}"),
+            ));
+        }
+    }
+
+    let mut views = load_profile_views(&state, vec![identity_bytes]).await?;
+    let view = views.remove(&canonical_id).ok_or_else(|| {
+        HttpError::new(
+            StatusCode::NOT_FOUND,
+            format!("Identity {identity_id} not found"),
+        )
+    })?;
+    let hash_hex = view.avatar_hash.clone().ok_or_else(|| {
+        HttpError::new(
+            StatusCode::NOT_FOUND,
+            format!("Identity {identity_id} has no avatar"),
+        )
+    })?;
+    let hash = hex::decode(&hash_hex)
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    let etag = match query.size {
+        Some(size) => format!("\"{hash_hex}-{size}\""),
+        None => format!("\"{hash_hex}\""),
+    };
+    let not_modified = headers
+        .get(IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value
+                .split(',')
+                .any(|candidate| candidate.trim() == etag || candidate.trim() == "*")
+        });
+    if not_modified {
+        return Ok(avatar_response(
+            StatusCode::NOT_MODIFIED,
+            &etag,
+            None,
+            Vec::new(),
+        ));
+    }
+
+    let original = state
+        .avatars
+        .store
+        .get(&original_key(&hash))
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+        .ok_or_else(|| {
+            HttpError::new(
+                StatusCode::NOT_FOUND,
+                format!("Avatar {hash_hex} has not been uploaded"),
+            )
+        })?;
+
+    let Some(size) = query.size else {
+        let content_type = AvatarFormat::sniff(&original)
+            .map(|format| format.content_type())
+            .unwrap_or("application/octet-stream");
+        return Ok(avatar_response(
+            StatusCode::OK,
+            &etag,
+            Some(content_type),
+            original,
+        ));
+    };
+
+    let key = variant_key(&hash, size);
+    let cached_variant = state
+        .avatars
+        .store
+        .get(&key)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    let variant = match cached_variant {
+        Some(variant) => variant,
+        None => {
+            let max_dimension = state.avatars.config.max_dimension;
+            let variant =
+                tokio::task::spawn_blocking(move || resize_avatar(&original, size, max_dimension))
+                    .await
+                    .map_err(|err| {
+                        HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+                    })?
+                    .map_err(|err| {
+                        HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}"))
+                    })?;
+            state
+                .avatars
+                .store
+                .put(&key, variant.clone())
+                .await
+                .map_err(|err| {
+                    HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+                })?;
+            variant
+        }
+    };
+    Ok(avatar_response(
+        StatusCode::OK,
+        &etag,
+        Some("image/png"),
+        variant,
+    ))
+}
+
+fn avatar_response(
+    status: StatusCode,
+    etag: &str,
+    content_type: Option<&'static str>,
+    body: Vec<u8>,
+) -> Response {
+    let mut response = (status, body).into_response();
+    let headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(etag) {
+        headers.insert(ETAG, value);
+    }
+    // The identity may point at a new avatar at any time; clients
+    // revalidate cheaply through the ETag.
+    headers.insert(
+        CACHE_CONTROL,
+        HeaderValue::from_static("public, max-age=300"),
+    );
+    if let Some(content_type) = content_type {
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+    }
+    response
+}
+
 async fn verify_wallet_link(
     Path(identity_id): Path<String>,
     State(state): State<AppState>,
@@ -144,46 +1096,421 @@ async fn verify_wallet_link(
         .await
         .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
 
-    if let Some(link) = link {
-        let stored_signature = link.proof_signature.clone();
-        let provided_matches = if let Some(signature) = payload.signature.as_deref() {
-            let provided = decode_signature(signature)
-                .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
-            provided == stored_signature
-        } else {
-            link.verified_at.is_some()
-        };
-
+    let Some(link) = link else {
         let response = WalletVerificationResponse {
             identity_id: canonical_id,
             wallet_address: sanitized_address,
-            linked: true,
-            verified: provided_matches,
-            proof_signature: Some(hex::encode(stored_signature)),
-            verified_at: link.verified_at,
-            last_synced_block: Some(link.last_synced_block),
-            reason: if provided_matches {
-                None
-            } else {
-                Some("Signature mismatch or verification pending".to_string())
-            },
+            linked: false,
+            verified: false,
+            signature_scheme: None,
+            proof_signature: None,
+            verified_at: None,
+            last_synced_block: None,
+            reason: Some("Wallet not linked to identity".to_string()),
         };
         return Ok(Json(response));
-    }
+    };
+
+    let signature = match payload.signature.as_deref() {
+        Some(signature) => decode_signature(signature)
+            .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?,
+        None => link.proof_signature.clone(),
+    };
+    let key = WalletKey::decode(&payload.public_key, payload.signature_scheme.as_deref())?;
+    let message = wallet_link_message(&link.identity_id, &link.wallet_address, &link.link_type);
+    let outcome = check_link_proof(&link.wallet_address, &key, &message, &signature);
 
     let response = WalletVerificationResponse {
         identity_id: canonical_id,
         wallet_address: sanitized_address,
-        linked: false,
-        verified: false,
-        proof_signature: None,
-        verified_at: None,
-        last_synced_block: None,
-        reason: Some("Wallet not linked to identity".to_string()),
+        linked: true,
+        verified: outcome.is_ok(),
+        signature_scheme: match &outcome {
+            Ok(scheme) => Some(scheme.as_str()),
+            Err(err) => err.scheme(),
+        },
+        proof_signature: Some(hex::encode(&link.proof_signature)),
+        verified_at: link.verified_at,
+        last_synced_block: Some(link.last_synced_block),
+        reason: outcome.err().map(|err| err.to_string()),
+    };
+    Ok(Json(response))
+}
+
+async fn get_wallet_owner(
+    Path(address): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<WalletOwnerView>, HttpError> {
+    let address = sanitize_wallet_address(&address)
+        .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
+    let mut owners = resolve_wallet_owners(&state, vec![address.clone()]).await?;
+    owners
+        .remove(&address)
+        .flatten()
+        .map(|owner| Json((*owner).clone()))
+        .ok_or_else(|| {
+            HttpError::new(
+                StatusCode::NOT_FOUND,
+                format!("No public identity owns wallet {address}"),
+            )
+        })
+}
+
+/// Resolves many addresses at once. Addresses without a publicly visible
+/// owner are left out of the response.
+async fn get_wallet_owners(
+    State(state): State<AppState>,
+    Json(payload): Json<WalletOwnerBatchRequest>,
+) -> Result<Json<Vec<WalletOwnerView>>, HttpError> {
+    if payload.addresses.len() > MAX_WALLET_OWNER_BATCH {
+        return Err(HttpError::new(
+            StatusCode::BAD_REQUEST,
+            format!("At most {MAX_WALLET_OWNER_BATCH} addresses can be resolved per request"),
+        ));
+    }
+    let mut addresses = Vec::with_capacity(payload.addresses.len());
+    for address in &payload.addresses {
+        let address = sanitize_wallet_address(address)
+            .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
+        if !addresses.contains(&address) {
+            addresses.push(address);
+        }
+    }
+
+    let mut owners = resolve_wallet_owners(&state, addresses.clone()).await?;
+    let views = addresses
+        .iter()
+        .filter_map(|address| owners.remove(address).flatten())
+        .map(|owner| (*owner).clone())
+        .collect();
+    Ok(Json(views))
+}
+
+/// Looks up the owner of each address through the cache, fetching all misses
+/// in one query. Only verified links of public identities resolve; the rest
+/// map to `None` so they are indistinguishable from unlinked addresses.
+async fn resolve_wallet_owners(
+    state: &AppState,
+    addresses: Vec<String>,
+) -> Result<HashMap<String, Option<Arc<WalletOwnerView>>>, HttpError> {
+    assert!(
+        addresses.len() <= MAX_WALLET_OWNER_BATCH,
+        "Wallet owner batch exceeds bound"
+    );
+    let mut resolved = HashMap::with_capacity(addresses.len());
+    let mut misses = Vec::new();
+    for address in addresses {
+        match state.cache.wallet_owners.get(&address).await {
+            Some(owner) => {
+                resolved.insert(address, owner);
+            }
+            None => misses.push(address),
+        }
+    }
+    if misses.is_empty() {
+        return Ok(resolved);
+    }
+
+    let links = wallet_link::Entity::find()
+        .filter(wallet_link::Column::WalletAddress.is_in(misses.clone()))
+        .filter(wallet_link::Column::VerifiedAt.is_not_null())
+        .find_also_related(identity_profile::Entity)
+        .all(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    // An address verified by several identities belongs to the latest one.
+    let mut latest: HashMap<String, (wallet_link::Model, identity_profile::Model)> = HashMap::new();
+    for (link, profile) in links {
+        let Some(profile) = profile else {
+            continue;
+        };
+        let newer = latest
+            .get(&link.wallet_address)
+            .is_none_or(|(current, _)| link.verified_at > current.verified_at);
+        if newer {
+            latest.insert(link.wallet_address.clone(), (link, profile));
+        }
+    }
+
+    for address in misses {
+        let owner = latest
+            .remove(&address)
+            .filter(|(_, profile)| profile.stats_visibility == VISIBILITY_PUBLIC)
+            .map(|(link, profile)| {
+                Arc::new(WalletOwnerView {
+                    wallet_address: link.wallet_address,
+                    identity_id: encode_identity_id(&profile.identity_id),
+                    display_name: profile.display_name,
+                    link_type: link.link_type,
+                    verified_at: link.verified_at.expect("Filtered on verified links"),
+                })
+            });
+        state
+            .cache
+            .wallet_owners
+            .insert(address.clone(), owner.clone())
+            .await;
+        resolved.insert(address, owner);
+    }
+    Ok(resolved)
+}
+
+/// Issues a single-use nonce for linking a wallet. Once an identity has a
+/// verified wallet only its owner may request challenges, and every identity
+/// is limited to a few challenges per challenge TTL.
+async fn issue_wallet_challenge(
+    Path(identity_id): Path<String>,
+    State(state): State<AppState>,
+    viewer: Viewer,
+    Json(payload): Json<WalletChallengeRequest>,
+) -> Result<Json<WalletChallengeResponse>, HttpError> {
+    let identity_bytes = decode_identity_id(&identity_id)
+        .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
+    let canonical_id = encode_identity_id(&identity_bytes);
+    let wallet_address = sanitize_wallet_address(&payload.wallet_address)
+        .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
+    let link_type = normalize_link_type(payload.link_type.as_deref().unwrap_or("main"))
+        .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?
+        .into_owned();
+
+    let profile = identity_profile::Entity::find_by_id(identity_bytes.clone())
+        .one(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    if profile.is_none() {
+        return Err(HttpError::new(
+            StatusCode::NOT_FOUND,
+            format!("Identity {identity_id} not found"),
+        ));
+    }
+
+    let linked = wallet_link::Entity::find()
+        .filter(wallet_link::Column::IdentityId.eq(identity_bytes.clone()))
+        .all(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    let owned = linked.iter().any(|link| link.verified_at.is_some());
+    if owned && viewer.identity_id() != Some(identity_bytes.as_slice()) {
+        return Err(HttpError::new(
+            StatusCode::UNAUTHORIZED,
+            "Wallet challenges for this identity require the owner's authorization".to_string(),
+        ));
+    }
+    let relinking = linked
+        .iter()
+        .any(|link| link.wallet_address == wallet_address);
+    if !relinking && linked.len() >= MAX_WALLET_LINKS {
+        return Err(HttpError::new(
+            StatusCode::CONFLICT,
+            format!("Identity already links the maximum of {MAX_WALLET_LINKS} wallets"),
+        ));
+    }
+
+    let issued = state
+        .cache
+        .wallet_challenge_counts
+        .get_with(canonical_id.clone(), async { Arc::new(AtomicU32::new(0)) })
+        .await
+        .fetch_add(1, AtomicOrdering::Relaxed);
+    if issued >= state.cache.wallet_challenges_per_identity {
+        return Err(HttpError::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            format!("Too many wallet challenges for identity {canonical_id}; retry later"),
+        ));
+    }
+
+    let mut nonce = [0u8; WALLET_CHALLENGE_NONCE_BYTES];
+    OsRng.fill_bytes(&mut nonce);
+    let ttl = i64::try_from(state.cache.wallet_challenge_ttl.as_secs())
+        .expect("Challenge TTL exceeds i64 bounds");
+    let challenge = WalletChallenge {
+        identity_id: identity_bytes,
+        wallet_address,
+        link_type,
+        nonce,
+        expires_at: Utc::now().timestamp() + ttl,
+    };
+
+    let response = WalletChallengeResponse {
+        identity_id: canonical_id,
+        wallet_address: challenge.wallet_address.clone(),
+        link_type: challenge.link_type.clone(),
+        nonce: hex::encode(challenge.nonce),
+        challenge: String::from_utf8(challenge.message()).expect("Challenge message is UTF-8"),
+        link_message: String::from_utf8(challenge.link_message()).expect("Link message is UTF-8"),
+        expires_at: challenge.expires_at,
     };
+    state
+        .cache
+        .wallet_challenges
+        .insert(response.nonce.clone(), Arc::new(challenge))
+        .await;
+
     Ok(Json(response))
 }
 
+/// Redeems a challenge. The wallet signs both the challenge, proving it holds
+/// the key now, and the bytes from [`wallet_link_message`], whose signature
+/// becomes the proof it broadcasts.
+async fn submit_wallet_link(
+    Path(identity_id): Path<String>,
+    State(state): State<AppState>,
+    Json(payload): Json<WalletLinkSubmission>,
+) -> Result<Json<WalletLinkProofResponse>, HttpError> {
+    let identity_bytes = decode_identity_id(&identity_id)
+        .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
+    let nonce = decode_hex_with_expected(&payload.nonce, WALLET_CHALLENGE_NONCE_BYTES, "nonce")
+        .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
+    let challenge_signature = decode_signature(&payload.challenge_signature)
+        .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
+    let proof_signature = decode_signature(&payload.proof_signature)
+        .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
+    let key = WalletKey::decode(&payload.public_key, payload.signature_scheme.as_deref())?;
+
+    // Removing before verifying makes every nonce single use, including
+    // after a failed attempt.
+    let challenge = state
+        .cache
+        .wallet_challenges
+        .remove(&hex::encode(&nonce))
+        .await
+        .filter(|challenge| challenge.expires_at > Utc::now().timestamp())
+        .ok_or_else(|| {
+            HttpError::new(
+                StatusCode::NOT_FOUND,
+                "Wallet challenge not found or expired".to_string(),
+            )
+        })?;
+    if challenge.identity_id != identity_bytes {
+        return Err(HttpError::new(
+            StatusCode::BAD_REQUEST,
+            format!("Challenge was not issued for identity {identity_id}"),
+        ));
+    }
+
+    check_link_proof(
+        &challenge.wallet_address,
+        &key,
+        &challenge.message(),
+        &challenge_signature,
+    )
+    .map_err(|err| {
+        HttpError::new(
+            StatusCode::UNAUTHORIZED,
+            format!("Challenge signature rejected: {err}"),
+        )
+    })?;
+    let link_message = challenge.link_message();
+    let scheme = check_link_proof(
+        &challenge.wallet_address,
+        &key,
+        &link_message,
+        &proof_signature,
+    )
+    .map_err(|err| {
+        HttpError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Link proof rejected: {err}"),
+        )
+    })?;
+
+    let response = WalletLinkProofResponse {
+        identity_id: encode_identity_id(&challenge.identity_id),
+        wallet_address: challenge.wallet_address.clone(),
+        link_type: challenge.link_type.clone(),
+        proof_signature: hex::encode(&proof_signature),
+        signature_scheme: scheme.as_str(),
+        link_message: String::from_utf8(link_message).expect("Link message is UTF-8"),
+    };
+    Ok(Json(response))
+}
+
+/// Verifies a wallet link signature with the public key the caller presented
+/// for `wallet_address`. The key must derive the address, so only the
+/// wallet's own key can produce an accepted proof.
+pub(super) fn check_link_proof(
+    wallet_address: &str,
+    key: &WalletKey,
+    message: &[u8],
+    signature: &[u8],
+) -> Result<WalletSignatureScheme, LinkProofError> {
+    resolve_wallet_key(wallet_address, &key.public_key, key.scheme.as_deref())
+        .and_then(|scheme| verify_link_proof(scheme, &key.public_key, message, signature))
+}
+
+/// A wallet public key supplied with a request, hex encoded on the wire.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct WalletKey {
+    pub(super) public_key: Vec<u8>,
+    pub(super) scheme: Option<String>,
+}
+
+impl WalletKey {
+    pub(super) fn decode(public_key: &str, scheme: Option<&str>) -> Result<Self, HttpError> {
+        let public_key = decode_public_key(public_key)
+            .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?;
+        Ok(Self {
+            public_key,
+            scheme: scheme.map(str::to_string),
+        })
+    }
+}
+
+/// Lists identities whose display names are confusable with `name`, for
+/// flagging likely impersonation before a user trusts a name.
+async fn find_confusable_names(
+    Query(params): Query<ConfusableNameParams>,
+    State(state): State<AppState>,
+) -> Result<Json<ConfusableNameResponse>, HttpError> {
+    let name = canonicalize_display_name(&params.name)
+        .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))?
+        .ok_or_else(|| {
+            HttpError::new(
+                StatusCode::BAD_REQUEST,
+                "Query parameter 'name' must not be empty".to_string(),
+            )
+        })?;
+    let skeleton = display_name_skeleton(&name).ok_or_else(|| {
+        HttpError::new(
+            StatusCode::BAD_REQUEST,
+            "Query parameter 'name' has no comparable characters".to_string(),
+        )
+    })?;
+
+    let limit = params.limit.unwrap_or(20);
+    if limit == 0 || limit > MAX_CONFUSABLE_MATCHES {
+        return Err(HttpError::new(
+            StatusCode::BAD_REQUEST,
+            format!("Limit must be between 1 and {MAX_CONFUSABLE_MATCHES}"),
+        ));
+    }
+
+    let profiles = identity_profile::Entity::find()
+        .filter(identity_profile::Column::DisplayNameSkeleton.eq(skeleton.clone()))
+        .order_by_asc(identity_profile::Column::CreatedAt)
+        .limit(u64::from(limit))
+        .all(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    let matches = profiles
+        .into_iter()
+        .map(|model| IdentitySearchResult {
+            identity_id: encode_identity_id(&model.identity_id),
+            display_name: model.display_name,
+            stats_visibility: model.stats_visibility,
+            updated_at: model.updated_at,
+        })
+        .collect();
+
+    Ok(Json(ConfusableNameResponse {
+        name,
+        skeleton,
+        matches,
+    }))
+}
+
 async fn search_profiles(
     Query(params): Query<IdentitySearchParams>,
     State(state): State<AppState>,
@@ -195,13 +1522,18 @@ async fn search_profiles(
             "Query parameter 'q' must not be empty".to_string(),
         ));
     }
-    assert!(
-        query.len() <= crate::identity::MAX_DISPLAY_NAME_LEN,
-        "Query too long"
-    );
+    if query.chars().count() > crate::identity::MAX_DISPLAY_NAME_LEN {
+        return Err(HttpError::new(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Query exceeds {} character limit",
+                crate::identity::MAX_DISPLAY_NAME_LEN
+            ),
+        ));
+    }
 
-    let normalized = query.to_ascii_lowercase();
-    if normalized.len() < 2 {
+    let normalized = display_name_search_key(query).unwrap_or_default();
+    if normalized.chars().count() < 2 {
         return Err(HttpError::new(
             StatusCode::BAD_REQUEST,
             "Query must be at least two characters".to_string(),
@@ -223,9 +1555,11 @@ async fn search_profiles(
     }
 
     let profiles = identity_profile::Entity::find()
-        .filter(identity_profile::Column::DisplayNameSearch.contains(&normalized))
-        .order_by_desc(identity_profile::Column::UpdatedAt)
-        .limit(u64::from(limit))
+        .from_raw_sql(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            SEARCH_PROFILES_SQL,
+            [normalized.clone().into(), i64::from(limit).into()],
+        ))
         .all(&state.database)
         .await
         .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
@@ -272,6 +1606,10 @@ struct IdentitySearchResponse {
 struct WalletVerificationRequest {
     wallet_address: String,
     signature: Option<String>,
+    /// Hex public key of the wallet; it must derive `wallet_address`.
+    public_key: String,
+    #[serde(default)]
+    signature_scheme: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -280,8 +1618,121 @@ struct WalletVerificationResponse {
     wallet_address: String,
     linked: bool,
     verified: bool,
+    signature_scheme: Option<&'static str>,
     proof_signature: Option<String>,
     verified_at: Option<i64>,
     last_synced_block: Option<i64>,
     reason: Option<String>,
 }
+
+#[derive(Debug, Deserialize, Default)]
+struct FollowPageQuery {
+    limit: Option<u64>,
+    offset: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HistoryPageQuery {
+    limit: Option<u64>,
+    offset: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FeedQuery {
+    cursor: Option<String>,
+    limit: Option<u64>,
+}
+
+impl FeedQuery {
+    fn validate(&self) -> Result<(Option<FeedCursor>, u64), HttpError> {
+        let limit = self.limit.unwrap_or(50);
+        if limit == 0 || limit > MAX_FEED_PAGE {
+            return Err(HttpError::new(
+                StatusCode::BAD_REQUEST,
+                format!("limit must be between 1 and {MAX_FEED_PAGE}"),
+            ));
+        }
+        let cursor = match self.cursor.as_deref() {
+            Some(raw) => Some(FeedCursor::parse(raw).ok_or_else(|| {
+                HttpError::new(StatusCode::BAD_REQUEST, "Malformed feed cursor".to_string())
+            })?),
+            None => None,
+        };
+        Ok((cursor, limit))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ConfusableNameParams {
+    name: String,
+    limit: Option<u32>,
+}
+
+/// Identities sharing the confusable skeleton of `name`, oldest first so the
+/// original holder of a name is listed before later lookalikes.
+#[derive(Debug, Serialize)]
+struct ConfusableNameResponse {
+    name: String,
+    skeleton: String,
+    matches: Vec<IdentitySearchResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AvatarQuery {
+    size: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdentityBatchRequest {
+    #[serde(default)]
+    identity_ids: Vec<String>,
+    #[serde(default)]
+    wallet_addresses: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WalletOwnerBatchRequest {
+    addresses: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WalletChallengeRequest {
+    wallet_address: String,
+    link_type: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct WalletChallengeResponse {
+    identity_id: String,
+    wallet_address: String,
+    link_type: String,
+    nonce: String,
+    /// Message to sign to redeem the challenge.
+    challenge: String,
+    /// Message to sign as the registry link proof.
+    link_message: String,
+    expires_at: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct WalletLinkSubmission {
+    nonce: String,
+    challenge_signature: String,
+    proof_signature: String,
+    /// Hex public key of the wallet; it must derive the challenged address.
+    public_key: String,
+    #[serde(default)]
+    signature_scheme: Option<String>,
+}
+
+/// A verified link proof; `link_message` echoes the exact bytes from
+/// [`wallet_link_message`] that `proof_signature` covers.
+#[derive(Debug, Serialize)]
+struct WalletLinkProofResponse {
+    identity_id: String,
+    wallet_address: String,
+    link_type: String,
+    proof_signature: String,
+    signature_scheme: &'static str,
+    link_message: String,
+}
@@ -19,6 +19,7 @@ mod governance;
 mod identity;
 mod privacy;
 mod faucet;
+mod viewer;
 
 pub fn router(state: AppState) -> Router {
     assert!(
@@ -31,7 +32,13 @@ pub fn router(state: AppState) -> Router {
         // Allow requests from any origin (for development)
         // In production, restrict to specific domains
         .allow_origin(Any)
-        .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
         .allow_headers([ACCEPT, AUTHORIZATION, CONTENT_TYPE])
         .max_age(Duration::from_secs(3600));
 
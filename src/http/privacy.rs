@@ -1,31 +1,70 @@
+use std::convert::Infallible;
+use std::sync::Arc;
 use std::sync::atomic::Ordering as AtomicOrdering;
 
-use axum::extract::State;
-use axum::http::StatusCode;
-use axum::routing::post;
+use axum::body::{Body, Bytes};
+use axum::extract::{Path, Query, State};
+use axum::http::header::{AUTHORIZATION, CACHE_CONTROL, CONTENT_TYPE, HeaderName};
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, post};
 use axum::{Json, Router};
-use silica::privacy::{SpendPublicKey, StealthKeyPair, ViewPublicKey};
+use chrono::{Duration as ChronoDuration, Utc};
+use futures::stream::{self, Stream, StreamExt};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{DbErr, EntityTrait};
+use serde::Deserialize;
+use silica::privacy::{SpendPublicKey, StealthKeyPair, ViewOnlyStealthKeys, ViewPublicKey};
 use silica_models::stealth::STEALTH_OUTPUT_MEMO_MAX_BYTES;
-use tracing::error;
+use tokio::sync::broadcast;
+use tracing::{error, warn};
 
+use crate::entities::stealth_scan_registration;
 use crate::models::privacy::{
-    StealthAddressRequestPayload, StealthAddressResponsePayload, StealthKeyBundlePayload,
-    StealthScanRangeSummary, StealthScanRequestPayload, StealthScanResponsePayload,
-    StealthTransferRequestPayload, StealthTransferResponsePayload,
+    StealthAddressRequestPayload, StealthAddressResponsePayload, StealthInboxEntryView,
+    StealthInboxView, StealthKeyBundlePayload, StealthRegistrationRequestPayload,
+    StealthRegistrationView, StealthScanRangeSummary, StealthScanRequestPayload,
+    StealthScanResponsePayload, StealthTransferRequestPayload, StealthTransferResponsePayload,
+    StealthViewKeyPayload,
 };
 use crate::state::AppState;
-use crate::stealth_scanner::{ScanError, scan_owned_outputs};
+use crate::stealth_registry::{
+    MAX_INBOX_PAGE, RegistrySealer, authorize_registration, count_registrations, load_inbox,
+    new_credentials, token_hash,
+};
+use crate::stealth_scanner::{ScanError, ScanKeys, scan_owned_outputs};
+use crate::stealth_stream::{
+    BINARY_STREAM_MAGIC, CompactStealthOutput, MAX_STEALTH_STREAM_BLOCK_RANGE, OutputCursor,
+    OutputPage, STEALTH_STREAM_PAGE_SIZE, StreamEncoding, load_output_page, page_cache_key,
+    stream_trailer,
+};
 
 use super::HttpError;
 
 const SEED_HEX_BYTES: usize = 32;
 const MAX_STEALTH_SCAN_RESULTS: u64 = 1_024;
 const MAX_STEALTH_SCAN_BLOCK_RANGE: u64 = 10_000;
+const MIN_REGISTRATION_TTL_SECONDS: i64 = 60;
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/stealth/address", post(generate_address))
         .route("/stealth/scan", post(scan_outputs))
+        .route("/stealth/outputs", get(stream_outputs))
+        .route("/stealth/registrations", post(register_scan))
+        .route(
+            "/stealth/registrations/:registration_id",
+            delete(revoke_registration),
+        )
+        .route(
+            "/stealth/registrations/:registration_id/inbox",
+            get(registration_inbox),
+        )
+        .route(
+            "/stealth/registrations/:registration_id/events",
+            get(registration_events),
+        )
         .route("/stealth/transfer", post(submit_transfer))
 }
 
@@ -51,8 +90,25 @@ async fn generate_address(
 async fn scan_outputs(
     State(state): State<AppState>,
     Json(payload): Json<StealthScanRequestPayload>,
-) -> Result<Json<StealthScanResponsePayload>, HttpError> {
-    let keys = parse_stealth_keypair(&payload.stealth_keys)?;
+) -> Result<(HeaderMap, Json<StealthScanResponsePayload>), HttpError> {
+    let mut headers = HeaderMap::new();
+    let keys = match (&payload.view_keys, &payload.stealth_keys) {
+        (Some(view_keys), None) => ScanKeys::ViewOnly(parse_view_only_keys(view_keys)?),
+        (None, Some(stealth_keys)) => {
+            warn!("Stealth scan used deprecated full-key mode");
+            headers.insert(
+                HeaderName::from_static("deprecation"),
+                HeaderValue::from_static("true"),
+            );
+            ScanKeys::Full(parse_stealth_keypair(stealth_keys)?)
+        }
+        _ => {
+            return Err(HttpError::new(
+                StatusCode::BAD_REQUEST,
+                "Provide exactly one of view_keys or stealth_keys".to_string(),
+            ));
+        }
+    };
 
     let limit = payload.limit.unwrap_or(MAX_STEALTH_SCAN_RESULTS);
     if limit == 0 || limit > MAX_STEALTH_SCAN_RESULTS {
@@ -100,9 +156,16 @@ async fn scan_outputs(
         )
     })?;
 
-    let outcome = scan_owned_outputs(&state.database, &keys, from_block, to_block, limit_usize)
-        .await
-        .map_err(map_scan_error)?;
+    let outcome = scan_owned_outputs(
+        &state.database,
+        &state.scan_pool,
+        keys,
+        from_block,
+        to_block,
+        limit_usize,
+    )
+    .await
+    .map_err(map_scan_error)?;
 
     let total_scanned = u64::try_from(outcome.total_scanned).map_err(|_| {
         HttpError::new(
@@ -125,7 +188,429 @@ async fn scan_outputs(
         transactions: outcome.transactions,
     };
 
-    Ok(Json(response))
+    Ok((headers, Json(response)))
+}
+
+#[derive(Debug, Deserialize)]
+struct OutputStreamQuery {
+    from_block: Option<u64>,
+    to_block: Option<u64>,
+    cursor: Option<String>,
+    format: Option<String>,
+}
+
+/// Streams the public fields of every stealth output in a block range so
+/// wallets can detect ownership locally without sending any key material.
+/// Each record carries the cursor to resume after it; a stream that ends
+/// without its trailer was interrupted.
+async fn stream_outputs(
+    State(state): State<AppState>,
+    Query(query): Query<OutputStreamQuery>,
+) -> Result<Response, HttpError> {
+    let encoding = match query.format.as_deref() {
+        None => StreamEncoding::Ndjson,
+        Some(raw) => StreamEncoding::parse(raw).ok_or_else(|| {
+            HttpError::new(
+                StatusCode::BAD_REQUEST,
+                format!("Unsupported stream format {}", raw.trim()),
+            )
+        })?,
+    };
+    let cursor = match query.cursor.as_deref() {
+        Some(raw) => Some(OutputCursor::parse(raw).ok_or_else(|| {
+            HttpError::new(
+                StatusCode::BAD_REQUEST,
+                "Malformed output cursor".to_string(),
+            )
+        })?),
+        None => None,
+    };
+
+    let latest_block = state.last_indexed_block.load(AtomicOrdering::SeqCst);
+    let from_block = query.from_block.unwrap_or(0);
+    if from_block > latest_block {
+        return Err(HttpError::new(
+            StatusCode::BAD_REQUEST,
+            format!("from_block {from_block} exceeds latest indexed block {latest_block}"),
+        ));
+    }
+    let to_block = query.to_block.unwrap_or(latest_block).min(latest_block);
+    if to_block < from_block {
+        return Err(HttpError::new(
+            StatusCode::BAD_REQUEST,
+            "to_block must be greater than or equal to from_block".to_string(),
+        ));
+    }
+    let span = to_block - from_block;
+    if span > MAX_STEALTH_STREAM_BLOCK_RANGE {
+        return Err(HttpError::new(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Requested stream range {span} exceeds limit of {MAX_STEALTH_STREAM_BLOCK_RANGE} blocks"
+            ),
+        ));
+    }
+    // A fixed, fully indexed range always yields the same bytes.
+    let immutable = query.to_block.is_some_and(|to| to <= latest_block);
+
+    struct StreamProgress {
+        cursor: Option<OutputCursor>,
+        finished: bool,
+    }
+
+    let progress = StreamProgress {
+        cursor,
+        finished: false,
+    };
+    let pages = stream::unfold(progress, move |mut progress| {
+        let state = state.clone();
+        async move {
+            if progress.finished {
+                return None;
+            }
+            let page =
+                match cached_output_page(&state, from_block, to_block, progress.cursor.as_ref())
+                    .await
+                {
+                    Ok(page) => page,
+                    Err(err) => {
+                        warn!("Stealth output stream aborted: {err}");
+                        progress.finished = true;
+                        return Some((Err(std::io::Error::other(err.to_string())), progress));
+                    }
+                };
+            let mut chunk = match encode_outputs(encoding, &page.outputs) {
+                Ok(chunk) => chunk,
+                Err(err) => {
+                    warn!("Stealth output stream aborted: {err}");
+                    progress.finished = true;
+                    return Some((Err(std::io::Error::other(err)), progress));
+                }
+            };
+            progress.cursor = page.next_cursor.clone();
+            if progress.cursor.is_none() {
+                progress.finished = true;
+                chunk.extend_from_slice(&stream_trailer(encoding, to_block));
+            }
+            Some((Ok(Bytes::from(chunk)), progress))
+        }
+    });
+    let header = match encoding {
+        StreamEncoding::Binary => Some(Ok(Bytes::from_static(BINARY_STREAM_MAGIC))),
+        StreamEncoding::Ndjson => None,
+    };
+    let body = stream::iter(header).chain(pages);
+
+    let mut response = Body::from_stream(body).into_response();
+    let headers = response.headers_mut();
+    headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static(encoding.content_type()),
+    );
+    headers.insert(
+        CACHE_CONTROL,
+        HeaderValue::from_static(if immutable {
+            "public, max-age=86400, immutable"
+        } else {
+            "no-cache"
+        }),
+    );
+    Ok(response)
+}
+
+async fn cached_output_page(
+    state: &AppState,
+    from_block: u64,
+    to_block: u64,
+    cursor: Option<&OutputCursor>,
+) -> Result<Arc<OutputPage>, DbErr> {
+    let key = page_cache_key(from_block, to_block, cursor);
+    if let Some(page) = state.cache.stealth_output_pages.get(&key).await {
+        return Ok(page);
+    }
+    let page = Arc::new(load_output_page(&state.database, from_block, to_block, cursor).await?);
+    assert!(
+        page.outputs.len() as u64 <= STEALTH_STREAM_PAGE_SIZE,
+        "Stealth output page exceeds page size"
+    );
+    state
+        .cache
+        .stealth_output_pages
+        .insert(key, Arc::clone(&page))
+        .await;
+    Ok(page)
+}
+
+fn encode_outputs(
+    encoding: StreamEncoding,
+    outputs: &[CompactStealthOutput],
+) -> Result<Vec<u8>, String> {
+    let mut chunk = Vec::new();
+    for output in outputs {
+        match encoding {
+            StreamEncoding::Ndjson => {
+                serde_json::to_writer(&mut chunk, &output.view()).map_err(|err| err.to_string())?;
+                chunk.push(b'\n');
+            }
+            StreamEncoding::Binary => output.encode_frame(&mut chunk).ok_or_else(|| {
+                format!(
+                    "Stealth output {} overflows the binary layout",
+                    output.cursor.encode()
+                )
+            })?,
+        }
+    }
+    Ok(chunk)
+}
+
+/// Registers view keys for background scanning of every newly indexed
+/// block. The access token in the response is never shown again.
+async fn register_scan(
+    State(state): State<AppState>,
+    Json(payload): Json<StealthRegistrationRequestPayload>,
+) -> Result<(StatusCode, Json<StealthRegistrationView>), HttpError> {
+    let registry = &state.stealth_registry;
+    let sealer = registry_sealer(&state)?;
+    parse_view_only_keys(&payload.view_keys)?;
+
+    let ttl_seconds = payload
+        .ttl_seconds
+        .unwrap_or(registry.config.default_ttl_seconds);
+    if !(MIN_REGISTRATION_TTL_SECONDS..=registry.config.max_ttl_seconds).contains(&ttl_seconds) {
+        return Err(HttpError::new(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "ttl_seconds must be between {MIN_REGISTRATION_TTL_SECONDS} and {}",
+                registry.config.max_ttl_seconds
+            ),
+        ));
+    }
+
+    let latest_block = state.last_indexed_block.load(AtomicOrdering::SeqCst);
+    let start_block = payload.from_block.unwrap_or(latest_block + 1);
+    if start_block > latest_block + 1 {
+        return Err(HttpError::new(
+            StatusCode::BAD_REQUEST,
+            format!("from_block {start_block} is beyond the next indexed block"),
+        ));
+    }
+    let backfill = latest_block.saturating_sub(start_block);
+    if backfill > registry.config.max_backfill_blocks {
+        return Err(HttpError::new(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "from_block may be at most {} blocks behind the latest indexed block",
+                registry.config.max_backfill_blocks
+            ),
+        ));
+    }
+    let next_block = i64::try_from(start_block).map_err(|_| {
+        HttpError::new(
+            StatusCode::BAD_REQUEST,
+            "from_block exceeds storage bounds".to_string(),
+        )
+    })?;
+
+    let registrations = count_registrations(&state.database, Utc::now())
+        .await
+        .map_err(registry_db_error)?;
+    if registrations >= registry.config.max_registrations {
+        return Err(HttpError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Stealth scan registration capacity reached".to_string(),
+        ));
+    }
+
+    let (registration_id, access_token) = new_credentials();
+    let (key_ciphertext, key_nonce) = sealer.seal_keys(&registration_id, &payload.view_keys);
+    let now = Utc::now();
+    let expires_at = now + ChronoDuration::seconds(ttl_seconds);
+    let model = stealth_scan_registration::ActiveModel {
+        registration_id: Set(registration_id.clone()),
+        token_hash: Set(token_hash(&access_token)),
+        key_ciphertext: Set(key_ciphertext),
+        key_nonce: Set(key_nonce),
+        next_block: Set(next_block),
+        created_at: Set(now.fixed_offset()),
+        expires_at: Set(expires_at.fixed_offset()),
+    };
+    stealth_scan_registration::Entity::insert(model)
+        .exec_without_returning(&state.database)
+        .await
+        .map_err(registry_db_error)?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(StealthRegistrationView {
+            registration_id,
+            access_token,
+            start_block,
+            expires_at: expires_at.timestamp(),
+        }),
+    ))
+}
+
+/// Deletes the registration, its sealed keys and its inbox.
+async fn revoke_registration(
+    State(state): State<AppState>,
+    Path(registration_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<StatusCode, HttpError> {
+    let (_, registration) = authorized_registration(&state, &headers, &registration_id).await?;
+    stealth_scan_registration::Entity::delete_by_id(registration.registration_id)
+        .exec(&state.database)
+        .await
+        .map_err(registry_db_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+struct InboxQuery {
+    cursor: Option<String>,
+    limit: Option<u64>,
+}
+
+async fn registration_inbox(
+    State(state): State<AppState>,
+    Path(registration_id): Path<String>,
+    Query(query): Query<InboxQuery>,
+    headers: HeaderMap,
+) -> Result<Json<StealthInboxView>, HttpError> {
+    let limit = query.limit.unwrap_or(50);
+    if limit == 0 || limit > MAX_INBOX_PAGE {
+        return Err(HttpError::new(
+            StatusCode::BAD_REQUEST,
+            format!("limit must be between 1 and {MAX_INBOX_PAGE}"),
+        ));
+    }
+    let after = match query.cursor.as_deref() {
+        Some(raw) => Some(raw.parse::<i64>().map_err(|_| {
+            HttpError::new(
+                StatusCode::BAD_REQUEST,
+                "Malformed inbox cursor".to_string(),
+            )
+        })?),
+        None => None,
+    };
+    let (sealer, registration) =
+        authorized_registration(&state, &headers, &registration_id).await?;
+
+    let page = load_inbox(
+        &state.database,
+        &sealer,
+        &registration.registration_id,
+        after,
+        limit,
+    )
+    .await
+    .map_err(registry_db_error)?;
+
+    let scanned_through_block = u64::try_from(registration.next_block)
+        .ok()
+        .and_then(|next| next.checked_sub(1));
+    Ok(Json(StealthInboxView {
+        registration_id: registration.registration_id,
+        scanned_through_block,
+        expires_at: registration.expires_at.timestamp(),
+        entries: page
+            .entries
+            .into_iter()
+            .map(|entry| StealthInboxEntryView {
+                cursor: entry.sequence.to_string(),
+                detected_at: entry.detected_at.timestamp(),
+                transaction: entry.output,
+            })
+            .collect(),
+        next_cursor: page.next_cursor.map(|cursor| cursor.to_string()),
+    }))
+}
+
+/// Live "new outputs" events for one registration, ending when it expires.
+async fn registration_events(
+    State(state): State<AppState>,
+    Path(registration_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, HttpError> {
+    let (_, registration) = authorized_registration(&state, &headers, &registration_id).await?;
+    let remaining = (registration.expires_at.with_timezone(&Utc) - Utc::now())
+        .to_std()
+        .unwrap_or_default();
+
+    let receiver = state.stealth_registry.notifier.subscribe();
+    let events = stream::unfold(
+        (receiver, registration.registration_id),
+        |(mut receiver, registration_id)| async move {
+            loop {
+                let notification = match receiver.recv().await {
+                    Ok(notification) => notification,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Stealth inbox stream client lagged; {skipped} events dropped");
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                };
+                if notification.registration_id != registration_id {
+                    continue;
+                }
+                match Event::default().event("inbox").json_data(&*notification) {
+                    Ok(event) => return Some((Ok(event), (receiver, registration_id))),
+                    Err(err) => warn!("Failed to encode stealth inbox notification: {err}"),
+                }
+            }
+        },
+    )
+    .take_until(tokio::time::sleep(remaining));
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+fn registry_sealer(state: &AppState) -> Result<RegistrySealer, HttpError> {
+    state.stealth_registry.sealer.clone().ok_or_else(|| {
+        HttpError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Registered stealth scanning is not enabled".to_string(),
+        )
+    })
+}
+
+/// Checks the `Authorization: Bearer <token>` header against the
+/// registration. Unknown, expired and mismatched registrations all read as
+/// not found.
+async fn authorized_registration(
+    state: &AppState,
+    headers: &HeaderMap,
+    registration_id: &str,
+) -> Result<(RegistrySealer, stealth_scan_registration::Model), HttpError> {
+    let sealer = registry_sealer(state)?;
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .filter(|token| !token.trim().is_empty())
+        .ok_or_else(|| {
+            HttpError::new(
+                StatusCode::UNAUTHORIZED,
+                "Registration access requires a bearer token".to_string(),
+            )
+        })?;
+    let registration = authorize_registration(&state.database, registration_id, token, Utc::now())
+        .await
+        .map_err(registry_db_error)?
+        .ok_or_else(|| {
+            HttpError::new(
+                StatusCode::NOT_FOUND,
+                format!("Stealth scan registration {registration_id} not found"),
+            )
+        })?;
+    Ok((sealer, registration))
+}
+
+fn registry_db_error(err: DbErr) -> HttpError {
+    error!(?err, "Stealth registration database error");
+    HttpError::new(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Failed to access stealth scan registrations".to_string(),
+    )
 }
 
 fn map_scan_error(err: ScanError) -> HttpError {
@@ -141,6 +626,10 @@ fn map_scan_error(err: ScanError) -> HttpError {
             StatusCode::BAD_REQUEST,
             format!("Block number {block} exceeds storage bounds"),
         ),
+        ScanError::Busy => HttpError::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too many stealth scans in progress; retry shortly".to_string(),
+        ),
         ScanError::OutputOverflow { observed, limit } => HttpError::new(
             StatusCode::BAD_REQUEST,
             format!(
@@ -219,6 +708,11 @@ fn parse_stealth_keypair(keys: &StealthKeyBundlePayload) -> Result<StealthKeyPai
     .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))
 }
 
+fn parse_view_only_keys(keys: &StealthViewKeyPayload) -> Result<ViewOnlyStealthKeys, HttpError> {
+    ViewOnlyStealthKeys::from_hex_components(&keys.view_secret, &keys.spend_public_key)
+        .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))
+}
+
 fn parse_view_key(value: &str) -> Result<ViewPublicKey, HttpError> {
     ViewPublicKey::from_hex(value)
         .map_err(|err| HttpError::new(StatusCode::BAD_REQUEST, err.to_string()))
@@ -0,0 +1,324 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::extract::{FromRequestParts, OriginalUri};
+use axum::http::StatusCode;
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+use chrono::Utc;
+use sea_orm::{ColumnTrait, Condition, EntityTrait, QueryFilter};
+
+use crate::entities::{identity_profile, wallet_link};
+use crate::identity::{
+    MAX_CALLER_AUTH_SKEW_SECONDS, VISIBILITY_FRIENDS_ONLY, VISIBILITY_PUBLIC, caller_auth_message,
+    decode_identity_id, decode_public_key, decode_signature, sanitize_wallet_address,
+};
+use crate::social::is_mutual_follow;
+use crate::state::AppState;
+
+use super::HttpError;
+use super::identity::{WalletKey, check_link_proof};
+
+const AUTH_SCHEME: &str = "Identity";
+
+/// The caller of a request, as far as visibility is concerned.
+///
+/// Callers authenticate with
+/// `Authorization: Identity id=<hex>, wallet=<address>, key=<hex>, ts=<unix>, sig=<signature>`
+/// where `sig` is made by a verified wallet of the identity over
+/// [`caller_auth_message`] for this request's method and path, and `key` is
+/// that wallet's public key, optionally followed by `scheme=<name>`. Requests
+/// without the header are anonymous.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Viewer {
+    identity_id: Option<Vec<u8>>,
+}
+
+impl FromRequestParts<AppState> for Viewer {
+    type Rejection = HttpError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, HttpError> {
+        let Some(header) = parts.headers.get(AUTHORIZATION) else {
+            return Ok(Viewer::default());
+        };
+        let header = header
+            .to_str()
+            .map_err(|_| unauthorized("Authorization header is not valid ASCII"))?;
+        let Some(credentials) = header
+            .strip_prefix(AUTH_SCHEME)
+            .filter(|rest| rest.starts_with(' '))
+        else {
+            // Other schemes belong to other layers and do not identify a viewer.
+            return Ok(Viewer::default());
+        };
+        let credentials = CallerCredentials::parse(credentials)?;
+
+        let skew = (Utc::now().timestamp() - credentials.issued_at).abs();
+        if skew > MAX_CALLER_AUTH_SKEW_SECONDS {
+            return Err(unauthorized(
+                "Authorization timestamp is outside the allowed window",
+            ));
+        }
+        let link = wallet_link::Entity::find_by_id((
+            credentials.identity_id.clone(),
+            credentials.wallet_address.clone(),
+        ))
+        .one(&state.database)
+        .await
+        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+        .filter(|link| link.verified_at.is_some())
+        .ok_or_else(|| unauthorized("Wallet is not a verified link of the identity"))?;
+
+        // The signature covers the request line, so a captured header only
+        // replays the same request, and only within the skew window.
+        let uri = parts
+            .extensions
+            .get::<OriginalUri>()
+            .map_or(&parts.uri, |original| &original.0);
+        let path = uri
+            .path_and_query()
+            .map_or(uri.path(), |path| path.as_str());
+        let message = caller_auth_message(
+            &link.identity_id,
+            &link.wallet_address,
+            &format!("{} {path}", parts.method),
+            credentials.issued_at,
+        );
+        check_link_proof(
+            &link.wallet_address,
+            &credentials.key,
+            &message,
+            &credentials.signature,
+        )
+        .map_err(|err| unauthorized(&format!("Authorization signature rejected: {err}")))?;
+
+        Ok(Viewer {
+            identity_id: Some(link.identity_id),
+        })
+    }
+}
+
+impl Viewer {
+    pub fn identity_id(&self) -> Option<&[u8]> {
+        self.identity_id.as_deref()
+    }
+
+    /// Whether this viewer may see the stats and linked-wallet activity of
+    /// `owner`. Owners always see their own data; `friends_only` data is
+    /// also shown to identities in a mutual follow with the owner.
+    pub async fn may_view(
+        &self,
+        state: &AppState,
+        owner: &identity_profile::Model,
+    ) -> Result<bool, HttpError> {
+        if owner.stats_visibility == VISIBILITY_PUBLIC {
+            return Ok(true);
+        }
+        let Some(viewer_id) = self.identity_id() else {
+            return Ok(false);
+        };
+        if viewer_id == owner.identity_id.as_slice() {
+            return Ok(true);
+        }
+        if owner.stats_visibility != VISIBILITY_FRIENDS_ONLY {
+            return Ok(false);
+        }
+        is_mutual_follow(&state.database, viewer_id, &owner.identity_id)
+            .await
+            .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))
+    }
+
+    pub async fn ensure_identity_visible(
+        &self,
+        state: &AppState,
+        owner: &identity_profile::Model,
+    ) -> Result<(), HttpError> {
+        if self.may_view(state, owner).await? {
+            Ok(())
+        } else {
+            Err(HttpError::new(
+                StatusCode::FORBIDDEN,
+                "Identity activity is not visible to this caller".to_string(),
+            ))
+        }
+    }
+
+    /// Rejects reads of `address` activity when a verified owner of the
+    /// address restricts its visibility from this viewer.
+    pub async fn ensure_address_visible(
+        &self,
+        state: &AppState,
+        address: &str,
+    ) -> Result<(), HttpError> {
+        let hidden = self
+            .hidden_addresses(
+                state,
+                Condition::all().add(wallet_link::Column::WalletAddress.eq(address)),
+            )
+            .await?;
+        if hidden.is_empty() {
+            Ok(())
+        } else {
+            Err(HttpError::new(
+                StatusCode::FORBIDDEN,
+                format!("Activity of {address} is not visible to this caller"),
+            ))
+        }
+    }
+
+    /// Addresses among the wallet links matching `scope` whose verified
+    /// owner restricts visibility from this viewer. Lists drop these rows
+    /// instead of failing the whole request.
+    pub async fn hidden_addresses(
+        &self,
+        state: &AppState,
+        scope: Condition,
+    ) -> Result<HashSet<String>, HttpError> {
+        let owners = wallet_link::Entity::find()
+            .filter(scope)
+            .filter(wallet_link::Column::VerifiedAt.is_not_null())
+            .find_also_related(identity_profile::Entity)
+            .filter(identity_profile::Column::StatsVisibility.ne(VISIBILITY_PUBLIC))
+            .all(&state.database)
+            .await
+            .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+        let mut decisions = HashMap::<Vec<u8>, bool>::new();
+        let mut hidden = HashSet::new();
+        for (link, owner) in owners {
+            let Some(owner) = owner else {
+                continue;
+            };
+            let visible = match decisions.get(&owner.identity_id) {
+                Some(visible) => *visible,
+                None => {
+                    let visible = self.may_view(state, &owner).await?;
+                    decisions.insert(owner.identity_id.clone(), visible);
+                    visible
+                }
+            };
+            if !visible {
+                hidden.insert(link.wallet_address);
+            }
+        }
+        Ok(hidden)
+    }
+}
+
+#[derive(Debug)]
+struct CallerCredentials {
+    identity_id: Vec<u8>,
+    wallet_address: String,
+    key: WalletKey,
+    issued_at: i64,
+    signature: Vec<u8>,
+}
+
+impl CallerCredentials {
+    fn parse(raw: &str) -> Result<Self, HttpError> {
+        let mut identity_id = None;
+        let mut wallet_address = None;
+        let mut public_key = None;
+        let mut scheme = None;
+        let mut issued_at = None;
+        let mut signature = None;
+        for field in raw.split(',') {
+            let (key, value) = field
+                .trim()
+                .split_once('=')
+                .ok_or_else(|| unauthorized("Malformed Identity authorization field"))?;
+            let value = value.trim();
+            match key.trim() {
+                "id" => {
+                    identity_id = Some(
+                        decode_identity_id(value).map_err(|err| unauthorized(&err.to_string()))?,
+                    )
+                }
+                "wallet" => {
+                    wallet_address = Some(
+                        sanitize_wallet_address(value)
+                            .map_err(|err| unauthorized(&err.to_string()))?,
+                    )
+                }
+                "key" => {
+                    public_key = Some(
+                        decode_public_key(value).map_err(|err| unauthorized(&err.to_string()))?,
+                    )
+                }
+                "scheme" => scheme = Some(value.to_string()),
+                "ts" => {
+                    issued_at = Some(
+                        value
+                            .parse::<i64>()
+                            .map_err(|_| unauthorized("Authorization ts must be unix seconds"))?,
+                    )
+                }
+                "sig" => {
+                    signature = Some(
+                        decode_signature(value).map_err(|err| unauthorized(&err.to_string()))?,
+                    )
+                }
+                other => {
+                    return Err(unauthorized(&format!(
+                        "Unknown Identity authorization field {other}"
+                    )));
+                }
+            }
+        }
+        match (
+            identity_id,
+            wallet_address,
+            public_key,
+            issued_at,
+            signature,
+        ) {
+            (
+                Some(identity_id),
+                Some(wallet_address),
+                Some(public_key),
+                Some(issued_at),
+                Some(signature),
+            ) => Ok(Self {
+                identity_id,
+                wallet_address,
+                key: WalletKey { public_key, scheme },
+                issued_at,
+                signature,
+            }),
+            _ => Err(unauthorized(
+                "Identity authorization requires id, wallet, key, ts and sig",
+            )),
+        }
+    }
+}
+
+fn unauthorized(message: &str) -> HttpError {
+    HttpError::new(StatusCode::UNAUTHORIZED, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn credentials_parse_all_fields() {
+        let raw = format!(
+            " id=0x{}, wallet=0xabc, key=0x0102, scheme=ed25519, ts=1700000000, sig=0xdeadbeef",
+            "11".repeat(32)
+        );
+        let credentials = CallerCredentials::parse(&raw).expect("valid credentials");
+        assert_eq!(credentials.identity_id, vec![0x11; 32]);
+        assert_eq!(credentials.wallet_address, "0xabc");
+        assert_eq!(credentials.key.public_key, vec![0x01, 0x02]);
+        assert_eq!(credentials.key.scheme.as_deref(), Some("ed25519"));
+        assert_eq!(credentials.issued_at, 1_700_000_000);
+        assert_eq!(credentials.signature, vec![0xde, 0xad, 0xbe, 0xef]);
+    }
+
+    #[test]
+    fn credentials_require_every_field() {
+        assert!(CallerCredentials::parse("wallet=0xabc, ts=1").is_err());
+        let keyless = format!("id={}, wallet=0xabc, ts=1, sig=0x01", "11".repeat(32));
+        assert!(CallerCredentials::parse(&keyless).is_err());
+        assert!(CallerCredentials::parse("id").is_err());
+    }
+}
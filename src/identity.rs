@@ -1,8 +1,12 @@
 use std::borrow::Cow;
+use std::collections::BTreeSet;
 
 use anyhow::{Result, anyhow};
 use base64::Engine as _;
 use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use silica::crypto::{address_from_public_key, dilithium, ed25519};
+use silica::identity::wallet_link_signing_bytes;
+use unicode_normalization::UnicodeNormalization;
 
 pub const IDENTITY_ID_BYTES: usize = 32;
 pub const AVATAR_HASH_BYTES: usize = 32;
@@ -15,6 +19,15 @@ pub const MAX_SIGNATURE_LEN: usize = 4096;
 const _: [(); 16_384 - MAX_SIGNATURE_LEN] = [(); 16_384 - MAX_SIGNATURE_LEN];
 const _: [(); 64 - MAX_WALLET_LINKS] = [(); 64 - MAX_WALLET_LINKS];
 
+/// Separate domain for challenge messages so a signed challenge is never a
+/// valid link proof.
+pub const WALLET_CHALLENGE_DOMAIN: &str = "silica-identity-wallet-challenge:v1";
+pub const WALLET_CHALLENGE_NONCE_BYTES: usize = 32;
+/// Domain of the message a caller signs to act as an identity.
+pub const CALLER_AUTH_DOMAIN: &str = "silica-identity-auth:v1";
+/// How far a caller's signed timestamp may drift from the server clock.
+pub const MAX_CALLER_AUTH_SKEW_SECONDS: i64 = 300;
+
 pub const VISIBILITY_PUBLIC: &str = "public";
 pub const VISIBILITY_FRIENDS_ONLY: &str = "friends_only";
 pub const VISIBILITY_PRIVATE: &str = "private";
@@ -80,6 +93,21 @@ pub fn decode_signature(value: &str) -> Result<Vec<u8>> {
     Ok(decoded)
 }
 
+pub fn decode_public_key(value: &str) -> Result<Vec<u8>> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Err(anyhow!("Public key cannot be empty"));
+    }
+    let bytes = hex::decode(strip_hex_prefix(trimmed))
+        .map_err(|err| anyhow!("Failed to decode public key as hex: {err}"))?;
+    if bytes.len() > MAX_SIGNATURE_LEN {
+        return Err(anyhow!(
+            "Public key exceeds {MAX_SIGNATURE_LEN} byte defensive limit"
+        ));
+    }
+    Ok(bytes)
+}
+
 pub fn canonicalize_display_name(value: &str) -> Result<Option<String>> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
@@ -104,12 +132,28 @@ pub fn canonicalize_bio(value: &str) -> Result<Option<String>> {
     Ok(Some(trimmed.to_string()))
 }
 
+/// UTS #39 confusable skeleton of a display name. Names that render alike,
+/// such as Latin "a" and Cyrillic "а", share a skeleton.
+pub fn display_name_skeleton(name: &str) -> Option<String> {
+    let key = display_name_search_key(name)?;
+    Some(unicode_security::skeleton(&key).collect())
+}
+
+/// Search form of a display name: NFKC normalized, fully case folded and
+/// with whitespace runs collapsed, so compatibility forms and any-script
+/// casing compare equal ("Straße" matches "STRASSE", "ς" matches "σ").
+///
+/// Stored keys are computed here only; SQL has no equivalent of full case
+/// folding, so existing rows are backfilled by the indexer.
 pub fn display_name_search_key(name: &str) -> Option<String> {
-    let trimmed = name.trim();
-    if trimmed.is_empty() {
-        return None;
-    }
-    Some(trimmed.to_ascii_lowercase())
+    let folded = caseless::default_case_fold_str(&name.nfkc().collect::<String>());
+    let key = folded
+        .nfkc()
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if key.is_empty() { None } else { Some(key) }
 }
 
 pub fn normalize_visibility(value: &str) -> Result<&'static str> {
@@ -157,6 +201,207 @@ pub fn sanitize_wallet_address(value: &str) -> Result<String> {
     Ok(trimmed.to_string())
 }
 
+/// Addresses linked and unlinked between two sets of wallet links, each
+/// sorted. Used to record wallet changes in the profile history.
+pub fn wallet_link_changes(previous: &[String], current: &[String]) -> (Vec<String>, Vec<String>) {
+    let previous = previous.iter().collect::<BTreeSet<_>>();
+    let current = current.iter().collect::<BTreeSet<_>>();
+    let added = current
+        .difference(&previous)
+        .map(|address| address.to_string())
+        .collect();
+    let removed = previous
+        .difference(&current)
+        .map(|address| address.to_string())
+        .collect();
+    (added, removed)
+}
+
+/// Signature schemes a wallet can prove a link with. The scheme is the one the
+/// caller names for its key, falling back to the key length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalletSignatureScheme {
+    Ed25519,
+    Dilithium3,
+}
+
+impl WalletSignatureScheme {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WalletSignatureScheme::Ed25519 => "ed25519",
+            WalletSignatureScheme::Dilithium3 => "dilithium3",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "ed25519" => Some(WalletSignatureScheme::Ed25519),
+            "dilithium3" | "dilithium" | "ml-dsa-65" => Some(WalletSignatureScheme::Dilithium3),
+            _ => None,
+        }
+    }
+
+    pub fn infer(public_key: &[u8]) -> Option<Self> {
+        match public_key.len() {
+            ed25519::PUBLIC_KEY_LEN => Some(WalletSignatureScheme::Ed25519),
+            dilithium::PUBLIC_KEY_LEN => Some(WalletSignatureScheme::Dilithium3),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum LinkProofError {
+    #[error("public key does not control wallet {0}")]
+    AddressMismatch(String),
+    #[error("unsupported signature scheme {0}")]
+    UnsupportedScheme(String),
+    #[error("public key is not a valid {scheme} key")]
+    MalformedPublicKey { scheme: &'static str },
+    #[error("signature is not a valid {scheme} signature")]
+    MalformedSignature { scheme: &'static str },
+    #[error("{scheme} signature does not match the wallet link message")]
+    SignatureMismatch { scheme: &'static str },
+}
+
+impl LinkProofError {
+    /// Scheme the failed check was attempted with, when one was determined.
+    pub fn scheme(&self) -> Option<&'static str> {
+        match self {
+            LinkProofError::AddressMismatch(_) | LinkProofError::UnsupportedScheme(_) => None,
+            LinkProofError::MalformedPublicKey { scheme }
+            | LinkProofError::MalformedSignature { scheme }
+            | LinkProofError::SignatureMismatch { scheme } => Some(scheme),
+        }
+    }
+}
+
+/// Bytes a wallet signs to prove it belongs to an identity. Inputs must
+/// already be canonical: a decoded identity id, a sanitized address and a
+/// normalized link type.
+///
+/// The layout belongs to the identity registry and is defined by
+/// `silica::identity::wallet_link_signing_bytes`; building it there keeps
+/// proofs checked here byte-identical to the ones the registry accepts.
+pub fn wallet_link_message(identity_id: &[u8], wallet_address: &str, link_type: &str) -> Vec<u8> {
+    assert_eq!(
+        identity_id.len(),
+        IDENTITY_ID_BYTES,
+        "Identity id must be canonical"
+    );
+    wallet_link_signing_bytes(identity_id, wallet_address, link_type)
+}
+
+/// Bytes a linked wallet signs to authenticate one request as
+/// `identity_id`. `request` is the method and path with query, such as
+/// `GET /identity/<id>/stats?window=30d`, so a signature only authorizes the
+/// request it was made for.
+pub fn caller_auth_message(
+    identity_id: &[u8],
+    wallet_address: &str,
+    request: &str,
+    issued_at: i64,
+) -> Vec<u8> {
+    assert_eq!(
+        identity_id.len(),
+        IDENTITY_ID_BYTES,
+        "Identity id must be canonical"
+    );
+    format!(
+        "{CALLER_AUTH_DOMAIN}\nidentity:{}\nwallet:{wallet_address}\nrequest:{request}\nissued_at:{issued_at}",
+        encode_identity_id(identity_id)
+    )
+    .into_bytes()
+}
+
+/// A pending wallet link issued by the challenge endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletChallenge {
+    pub identity_id: Vec<u8>,
+    pub wallet_address: String,
+    pub link_type: String,
+    pub nonce: [u8; WALLET_CHALLENGE_NONCE_BYTES],
+    pub expires_at: i64,
+}
+
+impl WalletChallenge {
+    /// Bytes the wallet signs to prove it controls its key right now.
+    pub fn message(&self) -> Vec<u8> {
+        assert_eq!(
+            self.identity_id.len(),
+            IDENTITY_ID_BYTES,
+            "Identity id must be canonical"
+        );
+        format!(
+            "{WALLET_CHALLENGE_DOMAIN}\nidentity:{}\nwallet:{}\nlink_type:{}\nnonce:{}\nexpires_at:{}",
+            encode_identity_id(&self.identity_id),
+            self.wallet_address,
+            self.link_type,
+            hex::encode(self.nonce),
+            self.expires_at
+        )
+        .into_bytes()
+    }
+
+    /// The [`wallet_link_message`] this challenge leads to.
+    pub fn link_message(&self) -> Vec<u8> {
+        wallet_link_message(&self.identity_id, &self.wallet_address, &self.link_type)
+    }
+}
+
+/// Resolves the key a caller presented for `wallet_address`: the scheme is
+/// parsed or inferred, and the key must derive the address, so a proof can
+/// only be made by the wallet's own key.
+pub fn resolve_wallet_key(
+    wallet_address: &str,
+    public_key: &[u8],
+    scheme: Option<&str>,
+) -> Result<WalletSignatureScheme, LinkProofError> {
+    let scheme = match scheme {
+        Some(raw) => WalletSignatureScheme::parse(raw)
+            .ok_or_else(|| LinkProofError::UnsupportedScheme(raw.to_string()))?,
+        None => WalletSignatureScheme::infer(public_key).ok_or_else(|| {
+            LinkProofError::UnsupportedScheme(format!("{} byte public key", public_key.len()))
+        })?,
+    };
+    if address_from_public_key(public_key) != wallet_address {
+        return Err(LinkProofError::AddressMismatch(wallet_address.to_string()));
+    }
+    Ok(scheme)
+}
+
+/// Checks `signature` over `message` with the wallet's public key and returns
+/// the scheme that verified it.
+pub fn verify_link_proof(
+    scheme: WalletSignatureScheme,
+    public_key: &[u8],
+    message: &[u8],
+    signature: &[u8],
+) -> Result<WalletSignatureScheme, LinkProofError> {
+    let name = scheme.as_str();
+    let valid = match scheme {
+        WalletSignatureScheme::Ed25519 => {
+            let key = ed25519::PublicKey::from_bytes(public_key)
+                .map_err(|_| LinkProofError::MalformedPublicKey { scheme: name })?;
+            let signature = ed25519::Signature::from_bytes(signature)
+                .map_err(|_| LinkProofError::MalformedSignature { scheme: name })?;
+            key.verify(message, &signature)
+        }
+        WalletSignatureScheme::Dilithium3 => {
+            let key = dilithium::PublicKey::from_bytes(public_key)
+                .map_err(|_| LinkProofError::MalformedPublicKey { scheme: name })?;
+            let signature = dilithium::Signature::from_bytes(signature)
+                .map_err(|_| LinkProofError::MalformedSignature { scheme: name })?;
+            key.verify(message, &signature)
+        }
+    };
+    if valid {
+        Ok(scheme)
+    } else {
+        Err(LinkProofError::SignatureMismatch { scheme: name })
+    }
+}
+
 fn strip_hex_prefix(value: &str) -> &str {
     if value.starts_with("0x") || value.starts_with("0X") {
         &value[2..]
@@ -169,6 +414,16 @@ fn strip_hex_prefix(value: &str) -> &str {
 mod tests {
     use super::*;
 
+    #[test]
+    fn wallet_link_changes_diff_addresses() {
+        let previous = vec!["0xb".to_string(), "0xa".to_string()];
+        let current = vec!["0xc".to_string(), "0xa".to_string(), "0xc".to_string()];
+        let (added, removed) = wallet_link_changes(&previous, &current);
+        assert_eq!(added, vec!["0xc".to_string()]);
+        assert_eq!(removed, vec!["0xb".to_string()]);
+        assert_eq!(wallet_link_changes(&current, &current), (vec![], vec![]));
+    }
+
     #[test]
     fn identity_roundtrip() {
         let id = "0x0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
@@ -201,6 +456,40 @@ mod tests {
         assert!(canonicalize_bio(&long_bio).is_err());
     }
 
+    #[test]
+    fn search_key_folds_unicode() {
+        assert_eq!(display_name_search_key("  Ana  Lía ").unwrap(), "ana lía");
+        assert_eq!(display_name_search_key("ÉLODIE").unwrap(), "élodie");
+        assert_eq!(display_name_search_key("ＡＬＩＣＥ").unwrap(), "alice");
+        assert_eq!(
+            display_name_search_key("Ｍ\u{0301}"),
+            display_name_search_key("ḿ")
+        );
+        assert_eq!(
+            display_name_search_key("Straße"),
+            display_name_search_key("STRASSE")
+        );
+        assert_eq!(
+            display_name_search_key("ΟΔΟΣ"),
+            display_name_search_key("οδοσ")
+        );
+        assert!(display_name_search_key("   ").is_none());
+    }
+
+    #[test]
+    fn skeletons_collide_for_confusable_names() {
+        assert_eq!(
+            display_name_skeleton("paypal"),
+            display_name_skeleton("p\u{0430}yp\u{0430}l")
+        );
+        assert_eq!(
+            display_name_skeleton("Alice"),
+            display_name_skeleton("ALICE")
+        );
+        assert_ne!(display_name_skeleton("alice"), display_name_skeleton("bob"));
+        assert!(display_name_skeleton(" ").is_none());
+    }
+
     #[test]
     fn visibility_normalization() {
         assert_eq!(normalize_visibility("PUBLIC").unwrap(), VISIBILITY_PUBLIC);
@@ -214,6 +503,88 @@ mod tests {
         assert!(normalize_link_type("").is_err());
     }
 
+    #[test]
+    fn link_message_binds_every_field() {
+        let id = [7u8; IDENTITY_ID_BYTES];
+        let message = wallet_link_message(&id, "0xabc", "main");
+        assert_eq!(message, wallet_link_signing_bytes(&id, "0xabc", "main"));
+        assert_ne!(
+            message,
+            wallet_link_message(&[8u8; IDENTITY_ID_BYTES], "0xabc", "main")
+        );
+        assert_ne!(message, wallet_link_message(&id, "0xabc", "mining"));
+        assert_ne!(message, wallet_link_message(&id, "0xabd", "main"));
+    }
+
+    #[test]
+    fn challenge_message_is_not_a_link_message() {
+        let challenge = WalletChallenge {
+            identity_id: vec![7u8; IDENTITY_ID_BYTES],
+            wallet_address: "0xabc".to_string(),
+            link_type: "main".to_string(),
+            nonce: [1u8; WALLET_CHALLENGE_NONCE_BYTES],
+            expires_at: 1_700_000_300,
+        };
+        let message = String::from_utf8(challenge.message()).unwrap();
+        assert!(message.starts_with(WALLET_CHALLENGE_DOMAIN));
+        assert!(message.ends_with(&format!(
+            "nonce:{}\nexpires_at:1700000300",
+            hex::encode([1u8; WALLET_CHALLENGE_NONCE_BYTES])
+        )));
+        assert_ne!(challenge.message(), challenge.link_message());
+    }
+
+    #[test]
+    fn caller_auth_binds_the_request() {
+        let id = [7u8; IDENTITY_ID_BYTES];
+        let message = caller_auth_message(&id, "0xabc", "GET /identity/x/stats", 1_700_000_000);
+        assert!(
+            String::from_utf8(message.clone())
+                .unwrap()
+                .starts_with(CALLER_AUTH_DOMAIN)
+        );
+        assert_ne!(
+            message,
+            caller_auth_message(&id, "0xabc", "GET /identity/x/wallets", 1_700_000_000)
+        );
+        assert_ne!(
+            message,
+            caller_auth_message(&id, "0xabc", "POST /identity/x/stats", 1_700_000_000)
+        );
+    }
+
+    #[test]
+    fn signature_scheme_parsing() {
+        assert_eq!(
+            WalletSignatureScheme::parse("Ed25519"),
+            Some(WalletSignatureScheme::Ed25519)
+        );
+        assert_eq!(
+            WalletSignatureScheme::parse("ml-dsa-65"),
+            Some(WalletSignatureScheme::Dilithium3)
+        );
+        assert!(WalletSignatureScheme::parse("rsa").is_none());
+        assert!(WalletSignatureScheme::infer(&[0u8; 5]).is_none());
+    }
+
+    #[test]
+    fn wallet_keys_must_derive_their_address() {
+        let key = [3u8; ed25519::PUBLIC_KEY_LEN];
+        let address = address_from_public_key(&key);
+        assert_eq!(
+            resolve_wallet_key(&address, &key, None),
+            Ok(WalletSignatureScheme::Ed25519)
+        );
+        assert_eq!(
+            resolve_wallet_key("0xabc", &key, Some("ed25519")),
+            Err(LinkProofError::AddressMismatch("0xabc".to_string()))
+        );
+        assert!(matches!(
+            resolve_wallet_key(&address, &key, Some("rsa")),
+            Err(LinkProofError::UnsupportedScheme(_))
+        ));
+    }
+
     #[test]
     fn wallet_address_validation() {
         assert!(sanitize_wallet_address("0xabc").is_ok());
@@ -0,0 +1,196 @@
+use std::collections::BTreeMap;
+
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, PaginatorTrait, QueryFilter,
+    QuerySelect, Select,
+};
+
+use crate::entities::{
+    chain_transaction, governance_delegation, governance_delegation_event, governance_proposal,
+    governance_vote,
+};
+
+/// Activity of one identity summed over its wallets. Delegations between
+/// two wallets of the same identity are internal and count towards neither
+/// `delegated_in` nor `delegated_out`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IdentityActivityTotals {
+    pub proposals_submitted: u64,
+    pub votes_cast: u64,
+    /// Distinct proposals voted on by any wallet, so two wallets voting on
+    /// the same proposal count once towards participation.
+    pub proposals_voted: u64,
+    pub delegated_in: i64,
+    pub delegated_out: i64,
+    pub wallets: BTreeMap<String, WalletTransactionCounts>,
+    pub first_activity_at: Option<i64>,
+    pub last_activity_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WalletTransactionCounts {
+    pub sent: u64,
+    pub received: u64,
+}
+
+pub async fn identity_activity_totals<C: ConnectionTrait>(
+    db: &C,
+    wallets: &[String],
+) -> Result<IdentityActivityTotals, DbErr> {
+    let mut totals = IdentityActivityTotals {
+        wallets: wallets
+            .iter()
+            .map(|wallet| (wallet.clone(), WalletTransactionCounts::default()))
+            .collect(),
+        ..Default::default()
+    };
+    if wallets.is_empty() {
+        return Ok(totals);
+    }
+
+    let proposals = governance_proposal::Entity::find()
+        .filter(governance_proposal::Column::Proposer.is_in(wallets.to_vec()));
+    let votes = governance_vote::Entity::find()
+        .filter(governance_vote::Column::Voter.is_in(wallets.to_vec()));
+    totals.proposals_submitted = proposals.clone().count(db).await?;
+    totals.votes_cast = votes.clone().count(db).await?;
+    totals.proposals_voted = votes
+        .clone()
+        .select_only()
+        .column(governance_vote::Column::ProposalId)
+        .distinct()
+        .count(db)
+        .await?;
+
+    totals.delegated_in = delegation_total(
+        db,
+        governance_delegation::Column::Delegatee,
+        governance_delegation::Column::Delegator,
+        wallets,
+    )
+    .await?;
+    totals.delegated_out = delegation_total(
+        db,
+        governance_delegation::Column::Delegator,
+        governance_delegation::Column::Delegatee,
+        wallets,
+    )
+    .await?;
+
+    for (column, outgoing) in [
+        (chain_transaction::Column::Sender, true),
+        (chain_transaction::Column::Recipient, false),
+    ] {
+        let counts = chain_transaction::Entity::find()
+            .filter(column.is_in(wallets.to_vec()))
+            .select_only()
+            .column(column)
+            .column_as(chain_transaction::Column::TxId.count(), "transactions")
+            .group_by(column)
+            .into_tuple::<(String, i64)>()
+            .all(db)
+            .await?;
+        for (wallet, count) in counts {
+            let entry = totals.wallets.entry(wallet).or_default();
+            let count = u64::try_from(count).unwrap_or(0);
+            if outgoing {
+                entry.sent = count;
+            } else {
+                entry.received = count;
+            }
+        }
+    }
+
+    let transactions = chain_transaction::Entity::find().filter(
+        Condition::any()
+            .add(chain_transaction::Column::Sender.is_in(wallets.to_vec()))
+            .add(chain_transaction::Column::Recipient.is_in(wallets.to_vec())),
+    );
+    let delegation_events = governance_delegation_event::Entity::find().filter(
+        Condition::any()
+            .add(governance_delegation_event::Column::Delegator.is_in(wallets.to_vec()))
+            .add(governance_delegation_event::Column::Delegatee.is_in(wallets.to_vec())),
+    );
+    let ranges = [
+        activity_range(db, transactions, chain_transaction::Column::Timestamp).await?,
+        activity_range(db, votes, governance_vote::Column::VotedAt).await?,
+        activity_range(db, proposals, governance_proposal::Column::CreatedAt).await?,
+        activity_range(
+            db,
+            delegation_events,
+            governance_delegation_event::Column::OccurredAt,
+        )
+        .await?,
+    ];
+    totals.first_activity_at = ranges.iter().filter_map(|(first, _)| *first).min();
+    totals.last_activity_at = ranges.iter().filter_map(|(_, last)| *last).max();
+
+    Ok(totals)
+}
+
+/// Sum of current delegations where `own` is one of `wallets` and `other`
+/// is not.
+async fn delegation_total<C: ConnectionTrait>(
+    db: &C,
+    own: governance_delegation::Column,
+    other: governance_delegation::Column,
+    wallets: &[String],
+) -> Result<i64, DbErr> {
+    let total = governance_delegation::Entity::find()
+        .filter(own.is_in(wallets.to_vec()))
+        .filter(other.is_not_in(wallets.to_vec()))
+        .select_only()
+        .column_as(governance_delegation::Column::Amount.sum(), "total")
+        .into_tuple::<Option<i64>>()
+        .one(db)
+        .await?
+        .flatten()
+        .unwrap_or(0);
+    assert!(total >= 0, "Delegation aggregate must be non-negative");
+    Ok(total)
+}
+
+async fn activity_range<E, C>(
+    db: &C,
+    query: Select<E>,
+    column: E::Column,
+) -> Result<(Option<i64>, Option<i64>), DbErr>
+where
+    E: EntityTrait,
+    C: ConnectionTrait,
+{
+    let (first, last) = query
+        .select_only()
+        .column_as(column.min(), "first")
+        .column_as(column.max(), "last")
+        .into_tuple::<(Option<DateTimeWithTimeZone>, Option<DateTimeWithTimeZone>)>()
+        .one(db)
+        .await?
+        .unwrap_or((None, None));
+    Ok((
+        first.map(|time| time.timestamp()),
+        last.map(|time| time.timestamp()),
+    ))
+}
+
+/// Balances summed across wallets; `None` as soon as one wallet's balance is
+/// unknown, since a partial sum would understate the total.
+pub fn total_balance(balances: &[Option<u64>]) -> Option<u64> {
+    balances.iter().try_fold(0u64, |total, balance| {
+        balance.map(|balance| total.saturating_add(balance))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn total_balance_requires_every_wallet() {
+        assert_eq!(total_balance(&[Some(5), Some(7)]), Some(12));
+        assert_eq!(total_balance(&[Some(5), None]), None);
+        assert_eq!(total_balance(&[]), Some(0));
+        assert_eq!(total_balance(&[Some(u64::MAX), Some(1)]), Some(u64::MAX));
+    }
+}
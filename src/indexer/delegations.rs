@@ -0,0 +1,251 @@
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+
+use anyhow::{Context, Result, anyhow};
+use chrono::DateTime;
+use sea_orm::ActiveValue::{NotSet, Set};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, DatabaseTransaction, EntityTrait, QueryFilter, TransactionTrait};
+use tracing::error;
+
+use crate::entities::{governance_delegation, governance_delegation_event};
+use crate::governance::delegation::{load_ledger, positions_from_events};
+use crate::governance::directory::DelegateRanking;
+use crate::models::governance::DelegationEventKind;
+use crate::rpc::{DelegationEventRecord, DelegationEventsResponse};
+
+use super::{ChainIndexer, fixed_now, to_fixed_offset};
+
+const DELEGATION_CHECKPOINT_ID: &str = "governance_delegations";
+const MAX_DELEGATION_SYNC_ITERATIONS: usize = 2048;
+const MAX_ADDRESS_LEN: usize = 128;
+
+impl ChainIndexer {
+    pub(super) async fn sync_delegation_events(&self, chain_tip: u64) -> Result<()> {
+        let mut checkpoint = self.load_checkpoint_for(DELEGATION_CHECKPOINT_ID).await?;
+        if checkpoint >= chain_tip {
+            return Ok(());
+        }
+
+        let mut iterations = 0usize;
+        let batch_size = self.config.delegation_batch_size();
+        assert!(batch_size > 0, "Delegation batch size must be positive");
+
+        while checkpoint < chain_tip {
+            iterations += 1;
+            assert!(
+                iterations <= MAX_DELEGATION_SYNC_ITERATIONS,
+                "Delegation event sync exceeded iteration bound"
+            );
+
+            let response = self
+                .rpc
+                .fetch_delegation_events(checkpoint, batch_size)
+                .await?;
+
+            let next_checkpoint = self.apply_delegation_events(checkpoint, &response).await?;
+
+            if next_checkpoint <= checkpoint {
+                // No progress reported by RPC, avoid infinite loop.
+                break;
+            }
+            checkpoint = next_checkpoint;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_delegation_events(
+        &self,
+        previous_checkpoint: u64,
+        response: &DelegationEventsResponse,
+    ) -> Result<u64> {
+        assert!(
+            response.latest_block >= previous_checkpoint,
+            "Delegation event checkpoint regressed"
+        );
+        assert!(
+            response.latest_block <= i64::MAX as u64,
+            "Delegation event latest block exceeds bounds"
+        );
+
+        if response.events.is_empty() {
+            self.persist_checkpoint_for(DELEGATION_CHECKPOINT_ID, response.latest_block)
+                .await?;
+            return Ok(response.latest_block);
+        }
+
+        let mut records = response.events.iter().collect::<Vec<_>>();
+        records.sort_by_key(|record| (record.block_number, record.event_index));
+
+        // Events are applied in chain order up to the first one that cannot
+        // be parsed. The checkpoint then stops just before that event's block
+        // so it is fetched again instead of being silently dropped; events
+        // already stored from that block are deduplicated on replay.
+        let mut next_checkpoint = response.latest_block;
+        let mut models = Vec::with_capacity(records.len());
+        let mut affected = BTreeSet::new();
+        for (index, record) in records.into_iter().enumerate() {
+            assert!(
+                index < MAX_DELEGATION_SYNC_ITERATIONS,
+                "Delegation event loop exceeded defensive bound"
+            );
+            match delegation_event_model(record) {
+                Ok(model) => {
+                    if let Set(delegator) = &model.delegator {
+                        affected.insert(delegator.clone());
+                    }
+                    models.push(model);
+                }
+                Err(err) => {
+                    error!(
+                        tx_id = %record.tx_id,
+                        event_index = record.event_index,
+                        block = record.block_number,
+                        "Halting delegation sync at malformed event: {err}"
+                    );
+                    next_checkpoint = record
+                        .block_number
+                        .saturating_sub(1)
+                        .clamp(previous_checkpoint, response.latest_block);
+                    break;
+                }
+            }
+        }
+
+        let txn = self.database.begin().await?;
+        if !models.is_empty() {
+            governance_delegation_event::Entity::insert_many(models)
+                .on_conflict(
+                    OnConflict::columns([
+                        governance_delegation_event::Column::TxId,
+                        governance_delegation_event::Column::EventIndex,
+                    ])
+                    .do_nothing()
+                    .to_owned(),
+                )
+                .exec_without_returning(&txn)
+                .await
+                .context("Failed to persist delegation events")?;
+        }
+
+        for delegator in &affected {
+            rebuild_delegations(&txn, delegator).await?;
+        }
+        txn.commit().await?;
+
+        if !affected.is_empty() {
+            for ranking in DelegateRanking::ALL {
+                self.cache
+                    .leaderboards
+                    .invalidate(&ranking.cache_key())
+                    .await;
+            }
+        }
+
+        if next_checkpoint > previous_checkpoint {
+            self.persist_checkpoint_for(DELEGATION_CHECKPOINT_ID, next_checkpoint)
+                .await?;
+        }
+
+        Ok(next_checkpoint)
+    }
+}
+
+/// Re-derives a delegator's current positions from the full event ledger.
+async fn rebuild_delegations(txn: &DatabaseTransaction, delegator: &str) -> Result<()> {
+    let delegators = [delegator.to_string()];
+    let ledger = load_ledger(txn, Some(&delegators), None)
+        .await
+        .with_context(|| format!("Failed to load delegation events for {delegator}"))?;
+    let positions = ledger
+        .get(delegator)
+        .map(|events| positions_from_events(events))
+        .unwrap_or_default();
+
+    governance_delegation::Entity::delete_many()
+        .filter(governance_delegation::Column::Delegator.eq(delegator))
+        .exec(txn)
+        .await
+        .with_context(|| format!("Failed to clear delegations for {delegator}"))?;
+
+    if positions.is_empty() {
+        return Ok(());
+    }
+
+    let models = positions
+        .into_iter()
+        .map(|(delegatee, position)| governance_delegation::ActiveModel {
+            delegator: Set(delegator.to_string()),
+            delegatee: Set(delegatee),
+            amount: Set(position.amount),
+            delegated_at: Set(position.updated_at),
+        })
+        .collect::<Vec<_>>();
+
+    governance_delegation::Entity::insert_many(models)
+        .exec(txn)
+        .await
+        .with_context(|| format!("Failed to persist delegations for {delegator}"))?;
+
+    Ok(())
+}
+
+fn delegation_event_model(
+    record: &DelegationEventRecord,
+) -> Result<governance_delegation_event::ActiveModel> {
+    let kind = DelegationEventKind::parse(&record.event_type)
+        .ok_or_else(|| anyhow!("Unsupported delegation event type {}", record.event_type))?;
+    let delegator = sanitize_address(&record.delegator, "delegator")?;
+    let delegatee = sanitize_address(&record.delegatee, "delegatee")?;
+    let previous_delegatee = record
+        .previous_delegatee
+        .as_deref()
+        .map(|address| sanitize_address(address, "previous_delegatee"))
+        .transpose()?;
+
+    if kind == DelegationEventKind::Redelegate && previous_delegatee.is_none() {
+        return Err(anyhow!("Redelegate event requires previous_delegatee"));
+    }
+
+    let tx_id = record.tx_id.trim();
+    if tx_id.is_empty() || tx_id.len() > MAX_ADDRESS_LEN {
+        return Err(anyhow!("Delegation event tx_id is empty or oversized"));
+    }
+
+    let amount = i64::try_from(record.amount)
+        .map_err(|_| anyhow!("Delegation amount {} overflows i64", record.amount))?;
+    let block_number = i64::try_from(record.block_number)
+        .map_err(|_| anyhow!("Block number {} overflows i64", record.block_number))?;
+    let event_index = i32::try_from(record.event_index)
+        .map_err(|_| anyhow!("Event index {} overflows i32", record.event_index))?;
+    let timestamp = i64::try_from(record.timestamp)
+        .ok()
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+        .ok_or_else(|| anyhow!("Invalid delegation event timestamp {}", record.timestamp))?;
+
+    Ok(governance_delegation_event::ActiveModel {
+        id: NotSet,
+        delegator: Set(delegator),
+        delegatee: Set(delegatee),
+        previous_delegatee: Set(previous_delegatee),
+        event_type: Set(kind.as_str().to_string()),
+        amount: Set(amount),
+        block_number: Set(block_number),
+        tx_id: Set(tx_id.to_string()),
+        event_index: Set(event_index),
+        occurred_at: Set(to_fixed_offset(timestamp)),
+        indexed_at: Set(fixed_now()),
+    })
+}
+
+pub(super) fn sanitize_address(value: &str, label: &str) -> Result<String> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Err(anyhow!("{label} cannot be empty"));
+    }
+    if trimmed.len() > MAX_ADDRESS_LEN {
+        return Err(anyhow!("{label} exceeds {MAX_ADDRESS_LEN} character limit"));
+    }
+    Ok(trimmed.to_string())
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::convert::TryFrom;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
@@ -7,12 +8,15 @@ use chrono::{DateTime, Datelike, FixedOffset, Utc};
 use sea_orm::ActiveModelTrait;
 use sea_orm::ActiveValue::Set;
 use sea_orm::ColumnTrait;
+use sea_orm::Condition;
 use sea_orm::DatabaseConnection;
 use sea_orm::DatabaseTransaction;
 use sea_orm::EntityTrait;
 use sea_orm::IntoActiveModel;
 use sea_orm::QueryFilter;
+use sea_orm::QuerySelect;
 use sea_orm::TransactionTrait;
+use sea_orm::sea_query::OnConflict;
 use silica_models::stealth::STEALTH_OUTPUT_MEMO_MAX_BYTES;
 use tokio::sync::watch;
 use tokio::time::sleep;
@@ -25,21 +29,30 @@ use crate::config::IndexerConfig;
 use crate::entities::chain_block;
 use crate::entities::chain_transaction;
 use crate::entities::identity_profile;
+use crate::entities::identity_profile_history;
 use crate::entities::indexer_checkpoint;
 use crate::entities::prelude::*;
 use crate::entities::stealth_output;
 use crate::entities::wallet_link;
+use crate::governance::directory::DelegateRanking;
 use crate::identity::{
     AVATAR_HASH_BYTES, MAX_WALLET_LINKS, canonicalize_bio, canonicalize_display_name,
     decode_hex_with_expected, decode_identity_id, decode_signature, display_name_search_key,
-    encode_identity_id, normalize_link_type, normalize_visibility, sanitize_wallet_address,
+    display_name_skeleton, encode_identity_id, normalize_link_type, normalize_visibility,
+    sanitize_wallet_address, wallet_link_changes,
 };
 use crate::rpc::{IdentityRecord, IdentityRegistryResponse, RpcClient, WalletLinkRecord};
 use crate::state::ApiCache;
 
+mod delegations;
+mod proposals;
+mod social;
+
 const CHAIN_CHECKPOINT_ID: &str = "chain";
 const IDENTITY_CHECKPOINT_ID: &str = "identity_registry";
 const MAX_IDENTITY_SYNC_ITERATIONS: usize = 2048;
+const NAME_KEY_BACKFILL_BATCH: u64 = 500;
+const MAX_NAME_KEY_BACKFILL_BATCHES: usize = 10_000;
 
 pub struct ChainIndexer {
     database: DatabaseConnection,
@@ -77,6 +90,7 @@ impl ChainIndexer {
         self.last_indexed_block
             .store(checkpoint, AtomicOrdering::SeqCst);
         let _ = self.load_checkpoint_for(IDENTITY_CHECKPOINT_ID).await?;
+        self.backfill_name_keys().await?;
 
         loop {
             tokio::select! {
@@ -136,6 +150,17 @@ impl ChainIndexer {
             self.persist_checkpoint_for(CHAIN_CHECKPOINT_ID, processed)
                 .await?;
             self.sync_identity_registry(processed).await?;
+            // Side feed failures leave their checkpoints in place; the next
+            // tick retries without holding back block indexing.
+            if let Err(err) = self.sync_delegation_events(processed).await {
+                warn!("Delegation event sync failed: {err:#}");
+            }
+            if let Err(err) = self.sync_governance_events(processed).await {
+                warn!("Governance event sync failed: {err:#}");
+            }
+            if let Err(err) = self.sync_follow_events(processed).await {
+                warn!("Follow event sync failed: {err:#}");
+            }
         }
 
         Ok(processed)
@@ -530,12 +555,14 @@ impl ChainIndexer {
         }
 
         let txn = self.database.begin().await?;
+        let mut touched_wallets = BTreeSet::new();
         for (index, update) in response.updates.iter().enumerate() {
             assert!(
                 index < MAX_IDENTITY_SYNC_ITERATIONS,
                 "Identity update loop exceeded defensive bound"
             );
-            self.persist_identity_update(&txn, update).await?;
+            self.persist_identity_update(&txn, update, &mut touched_wallets)
+                .await?;
         }
         txn.commit().await?;
 
@@ -544,6 +571,18 @@ impl ChainIndexer {
 
         // Identity updates can invalidate cached search responses across queries.
         self.cache.identity_search.invalidate_all();
+        // Owners are only dropped once committed, so a concurrent lookup
+        // cannot cache the previous owner again.
+        for address in &touched_wallets {
+            self.cache.wallet_owners.invalidate(address).await;
+        }
+        // The delegate directory names identities subject to their visibility.
+        for ranking in DelegateRanking::ALL {
+            self.cache
+                .leaderboards
+                .invalidate(&ranking.cache_key())
+                .await;
+        }
 
         Ok(response.latest_block)
     }
@@ -552,6 +591,7 @@ impl ChainIndexer {
         &self,
         txn: &DatabaseTransaction,
         update: &IdentityRecord,
+        touched_wallets: &mut BTreeSet<String>,
     ) -> Result<()> {
         let identity_bytes = decode_identity_id(&update.identity_id)
             .with_context(|| format!("Invalid identity id {}", update.identity_id))?;
@@ -562,6 +602,7 @@ impl ChainIndexer {
             None => None,
         };
         let display_name_search = display_name.as_deref().and_then(display_name_search_key);
+        let display_name_skeleton = display_name.as_deref().and_then(display_name_skeleton);
 
         let avatar_hash = match update.avatar_hash.as_deref() {
             Some(hash) => Some(decode_hex_with_expected(
@@ -598,9 +639,19 @@ impl ChainIndexer {
         let profile_version = i32::try_from(update.profile_version.unwrap_or(1))
             .map_err(|_| anyhow!("profile_version exceeds i32 limits"))?;
 
-        let existing = identity_profile::Entity::find_by_id(identity_bytes.clone())
+        let previous = identity_profile::Entity::find_by_id(identity_bytes.clone())
             .one(txn)
-            .await?
+            .await?;
+        let previous_skeleton = previous
+            .as_ref()
+            .and_then(|profile| profile.display_name_skeleton.clone());
+        let profile_changed = previous.as_ref().is_none_or(|profile| {
+            profile.display_name != display_name
+                || profile.avatar_hash != avatar_hash
+                || profile.bio != bio
+                || profile.stats_visibility != visibility
+        });
+        let existing = previous
             .map(identity_profile::ActiveModel::from)
             .unwrap_or_else(|| identity_profile::ActiveModel {
                 identity_id: Set(identity_bytes.clone()),
@@ -611,6 +662,7 @@ impl ChainIndexer {
         model.identity_id = Set(identity_bytes.clone());
         model.display_name = Set(display_name.clone());
         model.display_name_search = Set(display_name_search);
+        model.display_name_skeleton = Set(display_name_skeleton.clone());
         model.avatar_hash = Set(avatar_hash.clone());
         model.bio = Set(bio.clone());
         model.stats_visibility = Set(visibility.to_string());
@@ -624,27 +676,144 @@ impl ChainIndexer {
             .await
             .with_context(|| format!("Failed to persist identity profile {canonical_id}"))?;
 
-        self.refresh_wallet_links(txn, &identity_bytes, &canonical_id, &update.wallet_links)
+        let (wallets_added, wallets_removed) = self
+            .refresh_wallet_links(txn, &identity_bytes, &canonical_id, &update.wallet_links)
             .await?;
+        // Both the unlinked and the (re)linked addresses may resolve to a
+        // different owner now, and a visibility change affects all of them.
+        touched_wallets.extend(wallets_removed.iter().cloned());
+        touched_wallets.extend(
+            update
+                .wallet_links
+                .iter()
+                .filter_map(|link| sanitize_wallet_address(&link.wallet_address).ok()),
+        );
+
+        if profile_changed || !wallets_added.is_empty() || !wallets_removed.is_empty() {
+            let entry = identity_profile_history::ActiveModel {
+                identity_id: Set(identity_bytes.clone()),
+                profile_version: Set(profile_version),
+                last_synced_block: Set(last_synced_block),
+                display_name: Set(display_name.clone()),
+                avatar_hash: Set(avatar_hash.clone()),
+                bio: Set(bio.clone()),
+                stats_visibility: Set(visibility.to_string()),
+                wallets_added: Set(serde_json::json!(wallets_added)),
+                wallets_removed: Set(serde_json::json!(wallets_removed)),
+                updated_at: Set(updated_at),
+            };
+            // Replaying a block must not rewrite history.
+            IdentityProfileHistory::insert(entry)
+                .on_conflict(
+                    OnConflict::columns([
+                        identity_profile_history::Column::IdentityId,
+                        identity_profile_history::Column::ProfileVersion,
+                        identity_profile_history::Column::LastSyncedBlock,
+                    ])
+                    .do_nothing()
+                    .to_owned(),
+                )
+                .exec_without_returning(txn)
+                .await
+                .with_context(|| format!("Failed to record profile history for {canonical_id}"))?;
+        }
 
         self.cache.identity_profiles.invalidate(&canonical_id).await;
         self.cache.identity_wallets.invalidate(&canonical_id).await;
+        self.cache.identity_stats.invalidate(&canonical_id).await;
+        if previous_skeleton != display_name_skeleton {
+            let skeletons = previous_skeleton.into_iter().chain(display_name_skeleton);
+            self.invalidate_name_collisions(txn, skeletons.collect())
+                .await?;
+        }
 
         Ok(())
     }
 
+    /// Drops cached profiles whose collision flag may have flipped because a
+    /// name entered or left one of `skeletons`.
+    async fn invalidate_name_collisions(
+        &self,
+        txn: &DatabaseTransaction,
+        skeletons: Vec<String>,
+    ) -> Result<()> {
+        if skeletons.is_empty() {
+            return Ok(());
+        }
+        let colliding = identity_profile::Entity::find()
+            .filter(identity_profile::Column::DisplayNameSkeleton.is_in(skeletons))
+            .all(txn)
+            .await
+            .context("Failed to load identities sharing a name skeleton")?;
+        for profile in colliding {
+            self.cache
+                .identity_profiles
+                .invalidate(&encode_identity_id(&profile.identity_id))
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Computes search keys and skeletons for profiles whose keys were
+    /// cleared by a migration or stored before they existed. Both come from
+    /// the application's normalization, which SQL cannot reproduce.
+    async fn backfill_name_keys(&self) -> Result<()> {
+        let mut backfilled = 0usize;
+        for _ in 0..MAX_NAME_KEY_BACKFILL_BATCHES {
+            let pending = identity_profile::Entity::find()
+                .filter(identity_profile::Column::DisplayName.is_not_null())
+                .filter(
+                    Condition::any()
+                        .add(identity_profile::Column::DisplayNameSearch.is_null())
+                        .add(identity_profile::Column::DisplayNameSkeleton.is_null()),
+                )
+                .limit(NAME_KEY_BACKFILL_BATCH)
+                .all(&self.database)
+                .await
+                .context("Failed to load profiles without display name keys")?;
+            if pending.is_empty() {
+                break;
+            }
+            for profile in pending {
+                let name = profile.display_name.clone().unwrap_or_default();
+                let search = display_name_search_key(&name).unwrap_or_default();
+                let skeleton = display_name_skeleton(&name).unwrap_or_default();
+                let mut model = profile.into_active_model();
+                model.display_name_search = Set(Some(search));
+                model.display_name_skeleton = Set(Some(skeleton));
+                model.update(&self.database).await?;
+                backfilled += 1;
+            }
+        }
+        if backfilled > 0 {
+            info!("Backfilled display name keys for {backfilled} identities");
+        }
+        Ok(())
+    }
+
+    /// Replaces the identity's wallet links, returning the addresses that
+    /// were added and removed.
     async fn refresh_wallet_links(
         &self,
         txn: &DatabaseTransaction,
         identity_bytes: &[u8],
         canonical_id: &str,
         links: &[WalletLinkRecord],
-    ) -> Result<()> {
+    ) -> Result<(Vec<String>, Vec<String>)> {
         assert!(
             links.len() <= MAX_WALLET_LINKS,
             "Wallet link batch exceeds limit"
         );
 
+        let previous_addresses = wallet_link::Entity::find()
+            .filter(wallet_link::Column::IdentityId.eq(identity_bytes.to_vec()))
+            .all(txn)
+            .await
+            .with_context(|| format!("Failed to load existing wallet links for {canonical_id}"))?
+            .into_iter()
+            .map(|link| link.wallet_address)
+            .collect::<Vec<_>>();
+
         wallet_link::Entity::delete_many()
             .filter(wallet_link::Column::IdentityId.eq(identity_bytes.to_vec()))
             .exec(txn)
@@ -654,7 +823,7 @@ impl ChainIndexer {
             })?;
 
         if links.is_empty() {
-            return Ok(());
+            return Ok(wallet_link_changes(&previous_addresses, &[]));
         }
 
         let mut models = Vec::with_capacity(links.len());
@@ -701,12 +870,16 @@ impl ChainIndexer {
             });
         }
 
+        let current_addresses = models
+            .iter()
+            .map(|model| model.wallet_address.clone().unwrap())
+            .collect::<Vec<_>>();
         wallet_link::Entity::insert_many(models)
             .exec(txn)
             .await
             .with_context(|| format!("Failed to persist wallet links for {canonical_id}"))?;
 
-        Ok(())
+        Ok(wallet_link_changes(&previous_addresses, &current_addresses))
     }
 }
 
@@ -0,0 +1,392 @@
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, FixedOffset};
+use sea_orm::ActiveValue::{NotSet, Set};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+    ColumnTrait, DatabaseTransaction, EntityTrait, QueryFilter, QuerySelect, TransactionTrait,
+};
+use serde_json::Value;
+use tracing::{error, warn};
+
+use crate::entities::{governance_proposal, governance_vote};
+use crate::governance::cache::{ProposalChange, invalidate_proposal};
+use crate::models::governance::ProposalState;
+use crate::rpc::{GovernanceEventsResponse, ProposalEventRecord, VoteEventRecord};
+
+use super::delegations::sanitize_address;
+use super::{ChainIndexer, fixed_now, to_fixed_offset};
+
+const GOVERNANCE_CHECKPOINT_ID: &str = "governance_events";
+const MAX_GOVERNANCE_SYNC_ITERATIONS: usize = 2048;
+const MAX_PROPOSAL_ACTIONS: usize = 64;
+const MAX_DESCRIPTION_LEN: usize = 10_000;
+const MAX_REASON_LEN: usize = 2_000;
+const MAX_TX_ID_LEN: usize = 128;
+
+impl ChainIndexer {
+    pub(super) async fn sync_governance_events(&self, chain_tip: u64) -> Result<()> {
+        let mut checkpoint = self.load_checkpoint_for(GOVERNANCE_CHECKPOINT_ID).await?;
+        if checkpoint >= chain_tip {
+            return Ok(());
+        }
+
+        let mut iterations = 0usize;
+        let batch_size = self.config.governance_batch_size();
+        assert!(batch_size > 0, "Governance batch size must be positive");
+
+        while checkpoint < chain_tip {
+            iterations += 1;
+            assert!(
+                iterations <= MAX_GOVERNANCE_SYNC_ITERATIONS,
+                "Governance event sync exceeded iteration bound"
+            );
+
+            let response = self
+                .rpc
+                .fetch_governance_events(checkpoint, batch_size)
+                .await?;
+
+            let next_checkpoint = self.apply_governance_events(checkpoint, &response).await?;
+
+            if next_checkpoint <= checkpoint {
+                // No progress reported by RPC, avoid infinite loop.
+                break;
+            }
+            checkpoint = next_checkpoint;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_governance_events(
+        &self,
+        previous_checkpoint: u64,
+        response: &GovernanceEventsResponse,
+    ) -> Result<u64> {
+        assert!(
+            response.latest_block >= previous_checkpoint,
+            "Governance event checkpoint regressed"
+        );
+        assert!(
+            response.latest_block <= i64::MAX as u64,
+            "Governance event latest block exceeds bounds"
+        );
+
+        // Records from the first block holding a malformed event onwards are
+        // left for the next fetch, so the checkpoint never passes an event
+        // that was not stored.
+        let mut halt_block: Option<u64> = None;
+        let mut proposals = Vec::with_capacity(response.proposals.len());
+        for record in &response.proposals {
+            match proposal_model(record) {
+                Ok(model) => proposals.push((record.block_number, model)),
+                Err(err) => {
+                    error!(
+                        proposal_id = record.proposal_id,
+                        tx_id = %record.tx_id,
+                        block = record.block_number,
+                        "Halting governance sync at malformed proposal: {err}"
+                    );
+                    halt_block = Some(
+                        halt_block
+                            .map_or(record.block_number, |block| block.min(record.block_number)),
+                    );
+                }
+            }
+        }
+        let mut votes = Vec::with_capacity(response.votes.len());
+        for record in &response.votes {
+            match vote_model(record) {
+                Ok(model) => votes.push((record.block_number, model)),
+                Err(err) => {
+                    error!(
+                        proposal_id = record.proposal_id,
+                        tx_id = %record.tx_id,
+                        block = record.block_number,
+                        "Halting governance sync at malformed vote: {err}"
+                    );
+                    halt_block = Some(
+                        halt_block
+                            .map_or(record.block_number, |block| block.min(record.block_number)),
+                    );
+                }
+            }
+        }
+
+        let next_checkpoint = match halt_block {
+            Some(block) => block
+                .saturating_sub(1)
+                .clamp(previous_checkpoint, response.latest_block),
+            None => response.latest_block,
+        };
+        let applies = |block: u64| halt_block.is_none_or(|halt| block < halt);
+        let proposals = proposals
+            .into_iter()
+            .filter(|(block, _)| applies(*block))
+            .map(|(_, model)| model)
+            .collect::<Vec<_>>();
+        let votes = votes
+            .into_iter()
+            .filter(|(block, _)| applies(*block))
+            .map(|(_, model)| model)
+            .collect::<Vec<_>>();
+
+        let mut affected = BTreeSet::new();
+        for model in &proposals {
+            if let Set(proposal_id) = &model.proposal_id {
+                affected.insert(*proposal_id);
+            }
+        }
+        for model in &votes {
+            if let Set(proposal_id) = &model.proposal_id {
+                affected.insert(*proposal_id);
+            }
+        }
+
+        if !affected.is_empty() {
+            let txn = self.database.begin().await?;
+            persist_proposals(&txn, proposals).await?;
+            persist_votes(&txn, votes).await?;
+            for proposal_id in &affected {
+                recompute_tally(&txn, *proposal_id).await?;
+            }
+            txn.commit().await?;
+            self.invalidate_proposals(&affected).await?;
+        }
+
+        if next_checkpoint > previous_checkpoint {
+            self.persist_checkpoint_for(GOVERNANCE_CHECKPOINT_ID, next_checkpoint)
+                .await?;
+        }
+
+        Ok(next_checkpoint)
+    }
+
+    async fn invalidate_proposals(&self, affected: &BTreeSet<i64>) -> Result<()> {
+        let proposals = governance_proposal::Entity::find()
+            .select_only()
+            .column(governance_proposal::Column::ProposalId)
+            .column(governance_proposal::Column::Proposer)
+            .column(governance_proposal::Column::State)
+            .filter(governance_proposal::Column::ProposalId.is_in(affected.iter().copied()))
+            .into_tuple::<(i64, String, String)>()
+            .all(&self.database)
+            .await
+            .context("Failed to load updated proposals")?;
+
+        for (proposal_id, proposer, state) in proposals {
+            let state = ProposalState::parse(&state).unwrap_or(ProposalState::Pending);
+            invalidate_proposal(
+                &self.cache.proposals,
+                ProposalChange {
+                    proposal_id,
+                    proposer,
+                    states: [state, state],
+                },
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Upserts proposal definitions. Lifecycle-owned columns (state, tallies,
+/// snapshot) are left untouched on conflict.
+async fn persist_proposals(
+    txn: &DatabaseTransaction,
+    models: Vec<governance_proposal::ActiveModel>,
+) -> Result<()> {
+    if models.is_empty() {
+        return Ok(());
+    }
+    governance_proposal::Entity::insert_many(models)
+        .on_conflict(
+            OnConflict::column(governance_proposal::Column::ProposalId)
+                .update_columns([
+                    governance_proposal::Column::Proposer,
+                    governance_proposal::Column::Targets,
+                    governance_proposal::Column::Values,
+                    governance_proposal::Column::Calldatas,
+                    governance_proposal::Column::Description,
+                    governance_proposal::Column::VoteStart,
+                    governance_proposal::Column::VoteEnd,
+                    governance_proposal::Column::ExecutedAt,
+                    governance_proposal::Column::BlockNumber,
+                    governance_proposal::Column::TxId,
+                    governance_proposal::Column::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec_without_returning(txn)
+        .await
+        .context("Failed to persist governance proposals")?;
+    Ok(())
+}
+
+/// Upserts votes; a later vote by the same voter replaces the earlier one.
+async fn persist_votes(
+    txn: &DatabaseTransaction,
+    models: Vec<governance_vote::ActiveModel>,
+) -> Result<()> {
+    if models.is_empty() {
+        return Ok(());
+    }
+    governance_vote::Entity::insert_many(models)
+        .on_conflict(
+            OnConflict::columns([
+                governance_vote::Column::ProposalId,
+                governance_vote::Column::Voter,
+            ])
+            .update_columns([
+                governance_vote::Column::Support,
+                governance_vote::Column::Weight,
+                governance_vote::Column::Reason,
+                governance_vote::Column::VotedAt,
+                governance_vote::Column::BlockNumber,
+                governance_vote::Column::TxId,
+            ])
+            .to_owned(),
+        )
+        .exec_without_returning(txn)
+        .await
+        .context("Failed to persist governance votes")?;
+    Ok(())
+}
+
+/// Re-sums a proposal's stored votes into its tally columns.
+async fn recompute_tally(txn: &DatabaseTransaction, proposal_id: i64) -> Result<()> {
+    let totals = governance_vote::Entity::find()
+        .select_only()
+        .column(governance_vote::Column::Support)
+        .column_as(governance_vote::Column::Weight.sum(), "total")
+        .filter(governance_vote::Column::ProposalId.eq(proposal_id))
+        .group_by(governance_vote::Column::Support)
+        .into_tuple::<(i32, Option<i64>)>()
+        .all(txn)
+        .await
+        .with_context(|| format!("Failed to tally votes for proposal {proposal_id}"))?;
+
+    let (mut votes_for, mut votes_against, mut votes_abstain) = (0i64, 0i64, 0i64);
+    for (support, total) in totals {
+        let total = total.unwrap_or(0);
+        match support {
+            0 => votes_against = total,
+            1 => votes_for = total,
+            2 => votes_abstain = total,
+            other => warn!(proposal_id, "Ignoring votes with unknown support {other}"),
+        }
+    }
+
+    governance_proposal::Entity::update_many()
+        .col_expr(governance_proposal::Column::VotesFor, votes_for.into())
+        .col_expr(
+            governance_proposal::Column::VotesAgainst,
+            votes_against.into(),
+        )
+        .col_expr(
+            governance_proposal::Column::VotesAbstain,
+            votes_abstain.into(),
+        )
+        .col_expr(governance_proposal::Column::UpdatedAt, fixed_now().into())
+        .filter(governance_proposal::Column::ProposalId.eq(proposal_id))
+        .exec(txn)
+        .await
+        .with_context(|| format!("Failed to update tally of proposal {proposal_id}"))?;
+    Ok(())
+}
+
+fn proposal_model(record: &ProposalEventRecord) -> Result<governance_proposal::ActiveModel> {
+    let proposal_id = i64::try_from(record.proposal_id)
+        .map_err(|_| anyhow!("Proposal id {} overflows i64", record.proposal_id))?;
+    let proposer = sanitize_address(&record.proposer, "proposer")?;
+    if record.targets.len() > MAX_PROPOSAL_ACTIONS
+        || record.targets.len() != record.values.len()
+        || record.targets.len() != record.calldatas.len()
+    {
+        return Err(anyhow!("Proposal actions are oversized or misaligned"));
+    }
+    if record.description.len() > MAX_DESCRIPTION_LEN {
+        return Err(anyhow!(
+            "Proposal description exceeds {MAX_DESCRIPTION_LEN} bytes"
+        ));
+    }
+    if record.vote_start < 0 || record.vote_start > record.vote_end {
+        return Err(anyhow!("Proposal voting window is invalid"));
+    }
+    let (block_number, tx_id) = chain_ref(record.block_number, &record.tx_id)?;
+    let created_at = event_time(record.timestamp)?;
+    let executed_at = record.executed_at.map(event_time).transpose()?;
+
+    Ok(governance_proposal::ActiveModel {
+        proposal_id: Set(proposal_id),
+        proposer: Set(proposer),
+        targets: Set(Value::from(record.targets.clone())),
+        values: Set(Value::from(record.values.clone())),
+        calldatas: Set(Value::from(record.calldatas.clone())),
+        description: Set(record.description.clone()),
+        vote_start: Set(record.vote_start),
+        vote_end: Set(record.vote_end),
+        votes_for: Set(0),
+        votes_against: Set(0),
+        votes_abstain: Set(0),
+        state: Set(ProposalState::Pending.as_str().to_string()),
+        executed_at: Set(executed_at),
+        created_at: Set(created_at),
+        updated_at: Set(fixed_now()),
+        snapshot_block: Set(None),
+        snapshot_eligible_power: Set(None),
+        block_number: Set(Some(block_number)),
+        tx_id: Set(Some(tx_id)),
+    })
+}
+
+fn vote_model(record: &VoteEventRecord) -> Result<governance_vote::ActiveModel> {
+    let proposal_id = i64::try_from(record.proposal_id)
+        .map_err(|_| anyhow!("Proposal id {} overflows i64", record.proposal_id))?;
+    let voter = sanitize_address(&record.voter, "voter")?;
+    if record.support > 2 {
+        return Err(anyhow!("Unsupported vote support {}", record.support));
+    }
+    let weight = i64::try_from(record.weight)
+        .map_err(|_| anyhow!("Vote weight {} overflows i64", record.weight))?;
+    if record
+        .reason
+        .as_ref()
+        .is_some_and(|reason| reason.len() > MAX_REASON_LEN)
+    {
+        return Err(anyhow!("Vote reason exceeds {MAX_REASON_LEN} bytes"));
+    }
+    let (block_number, tx_id) = chain_ref(record.block_number, &record.tx_id)?;
+
+    Ok(governance_vote::ActiveModel {
+        id: NotSet,
+        proposal_id: Set(proposal_id),
+        voter: Set(voter),
+        support: Set(i32::from(record.support)),
+        weight: Set(weight),
+        reason: Set(record.reason.clone()),
+        voted_at: Set(event_time(record.timestamp)?),
+        block_number: Set(Some(block_number)),
+        tx_id: Set(Some(tx_id)),
+    })
+}
+
+fn chain_ref(block_number: u64, tx_id: &str) -> Result<(i64, String)> {
+    let block_number = i64::try_from(block_number)
+        .map_err(|_| anyhow!("Block number {block_number} overflows i64"))?;
+    let tx_id = tx_id.trim();
+    if tx_id.is_empty() || tx_id.len() > MAX_TX_ID_LEN {
+        return Err(anyhow!("Governance event tx_id is empty or oversized"));
+    }
+    Ok((block_number, tx_id.to_string()))
+}
+
+fn event_time(seconds: u64) -> Result<DateTime<FixedOffset>> {
+    i64::try_from(seconds)
+        .ok()
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+        .map(to_fixed_offset)
+        .ok_or_else(|| anyhow!("Invalid governance event timestamp {seconds}"))
+}
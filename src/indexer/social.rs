@@ -0,0 +1,169 @@
+use std::convert::TryFrom;
+
+use anyhow::{Context, Result, anyhow};
+use sea_orm::ActiveValue::Set;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, DatabaseTransaction, EntityTrait, QueryFilter, TransactionTrait};
+use tracing::error;
+
+use crate::entities::social_connection;
+use crate::identity::decode_identity_id;
+use crate::rpc::{FollowEventRecord, FollowEventsResponse};
+use crate::social::FollowEventKind;
+
+use super::ChainIndexer;
+
+const FOLLOW_CHECKPOINT_ID: &str = "social_follows";
+const MAX_FOLLOW_SYNC_ITERATIONS: usize = 2048;
+
+struct FollowEvent {
+    kind: FollowEventKind,
+    follower_id: Vec<u8>,
+    following_id: Vec<u8>,
+    block_number: i64,
+    timestamp: i64,
+}
+
+impl ChainIndexer {
+    pub(super) async fn sync_follow_events(&self, chain_tip: u64) -> Result<()> {
+        let mut checkpoint = self.load_checkpoint_for(FOLLOW_CHECKPOINT_ID).await?;
+        if checkpoint >= chain_tip {
+            return Ok(());
+        }
+
+        let mut iterations = 0usize;
+        let batch_size = self.config.follow_batch_size();
+        assert!(batch_size > 0, "Follow batch size must be positive");
+
+        while checkpoint < chain_tip {
+            iterations += 1;
+            assert!(
+                iterations <= MAX_FOLLOW_SYNC_ITERATIONS,
+                "Follow event sync exceeded iteration bound"
+            );
+
+            let response = self.rpc.fetch_follow_events(checkpoint, batch_size).await?;
+            let next_checkpoint = self.apply_follow_events(checkpoint, &response).await?;
+
+            if next_checkpoint <= checkpoint {
+                // No progress reported by RPC, avoid infinite loop.
+                break;
+            }
+            checkpoint = next_checkpoint;
+        }
+
+        Ok(())
+    }
+
+    /// Applies a batch in chain order. Replaying a batch converges to the
+    /// same edges, so a crash between commit and checkpoint, or a replay
+    /// after a halt, is harmless.
+    async fn apply_follow_events(
+        &self,
+        previous_checkpoint: u64,
+        response: &FollowEventsResponse,
+    ) -> Result<u64> {
+        assert!(
+            response.latest_block >= previous_checkpoint,
+            "Follow event checkpoint regressed"
+        );
+        assert!(
+            response.latest_block <= i64::MAX as u64,
+            "Follow event latest block exceeds bounds"
+        );
+
+        let mut records = response.events.iter().collect::<Vec<_>>();
+        records.sort_by_key(|record| (record.block_number, record.event_index));
+
+        // Events are applied in chain order up to the first one that cannot
+        // be parsed, and the checkpoint stops just before that event's block
+        // so it is fetched again rather than dropped.
+        let mut next_checkpoint = response.latest_block;
+        if !records.is_empty() {
+            let txn = self.database.begin().await?;
+            for (index, record) in records.into_iter().enumerate() {
+                assert!(
+                    index < MAX_FOLLOW_SYNC_ITERATIONS,
+                    "Follow event loop exceeded defensive bound"
+                );
+                match follow_event(record) {
+                    Ok(event) => apply_follow_event(&txn, event).await?,
+                    Err(err) => {
+                        error!(
+                            block_number = record.block_number,
+                            event_index = record.event_index,
+                            "Halting follow sync at malformed event: {err}"
+                        );
+                        next_checkpoint = record
+                            .block_number
+                            .saturating_sub(1)
+                            .clamp(previous_checkpoint, response.latest_block);
+                        break;
+                    }
+                }
+            }
+            txn.commit().await?;
+        }
+
+        if next_checkpoint > previous_checkpoint {
+            self.persist_checkpoint_for(FOLLOW_CHECKPOINT_ID, next_checkpoint)
+                .await?;
+        }
+        Ok(next_checkpoint)
+    }
+}
+
+async fn apply_follow_event(txn: &DatabaseTransaction, event: FollowEvent) -> Result<()> {
+    match event.kind {
+        FollowEventKind::Follow => {
+            let model = social_connection::ActiveModel {
+                follower_id: Set(event.follower_id),
+                following_id: Set(event.following_id),
+                created_at: Set(event.timestamp),
+                last_synced_block: Set(event.block_number),
+            };
+            social_connection::Entity::insert(model)
+                .on_conflict(
+                    OnConflict::columns([
+                        social_connection::Column::FollowerId,
+                        social_connection::Column::FollowingId,
+                    ])
+                    .do_nothing()
+                    .to_owned(),
+                )
+                .exec_without_returning(txn)
+                .await
+                .context("Failed to persist follow")?;
+        }
+        FollowEventKind::Unfollow => {
+            social_connection::Entity::delete_many()
+                .filter(social_connection::Column::FollowerId.eq(event.follower_id))
+                .filter(social_connection::Column::FollowingId.eq(event.following_id))
+                .exec(txn)
+                .await
+                .context("Failed to remove follow")?;
+        }
+    }
+    Ok(())
+}
+
+fn follow_event(record: &FollowEventRecord) -> Result<FollowEvent> {
+    let kind = FollowEventKind::parse(&record.event_type)
+        .ok_or_else(|| anyhow!("Unsupported follow event type {}", record.event_type))?;
+    let follower_id = decode_identity_id(&record.follower_id)?;
+    let following_id = decode_identity_id(&record.following_id)?;
+    if follower_id == following_id {
+        return Err(anyhow!("Identity cannot follow itself"));
+    }
+    let block_number = i64::try_from(record.block_number)
+        .map_err(|_| anyhow!("Block number {} overflows i64", record.block_number))?;
+    let timestamp = i64::try_from(record.timestamp)
+        .map_err(|_| anyhow!("Follow timestamp {} overflows i64", record.timestamp))?;
+    Ok(FollowEvent {
+        kind,
+        follower_id,
+        following_id,
+        block_number,
+        timestamp,
+    })
+}
@@ -1,21 +1,36 @@
+mod avatar;
 mod config;
 mod entities;
+mod feed;
+mod governance;
 mod http;
 mod identity;
+mod identity_stats;
 mod indexer;
 mod models;
 mod rpc;
+mod social;
 mod state;
+mod stealth_registry;
 mod stealth_scanner;
+mod stealth_stream;
 
 use std::sync::Arc;
 use std::sync::atomic::AtomicU64;
 use std::time::Duration;
 
+use crate::avatar::Avatars;
 use crate::config::ApiConfig;
+use crate::governance::calldata::ContractRegistry;
+use crate::governance::lifecycle::ProposalLifecycle;
+use crate::governance::notifier::GovernanceNotifier;
+use crate::governance::webhooks::WebhookDispatcher;
 use crate::indexer::ChainIndexer;
 use crate::rpc::RpcClient;
-use crate::state::{ApiCache, AppState};
+use crate::state::{ApiCache, AppState, GovernanceServices, StealthServices};
+use crate::stealth_registry::StealthRegistry;
+use crate::stealth_registry::worker::StealthScanWorker;
+use crate::stealth_scanner::ScanPool;
 use anyhow::{Context, Result};
 use axum::Router;
 use migration::MigratorTrait;
@@ -38,11 +53,27 @@ async fn main() -> Result<()> {
 
     let cache = Arc::new(ApiCache::new(&config.cache));
     let last_indexed_block = Arc::new(AtomicU64::new(0));
+    let notifier = GovernanceNotifier::new(config.governance.notifications.stream_capacity);
+    let stealth_registry = StealthRegistry::new(&config.stealth_registrations)?;
+    let scan_pool = ScanPool::new(&config.stealth_scan)?;
+    let contracts = ContractRegistry::load(&config.governance, &rpc_client)
+        .await
+        .context("Failed to load governed contract manifests")?;
     let app_state = AppState::new(
         database.clone(),
         Arc::clone(&cache),
         rpc_client.clone(),
         Arc::clone(&last_indexed_block),
+        GovernanceServices {
+            config: config.governance.clone(),
+            notifier: notifier.clone(),
+            contracts,
+        },
+        Avatars::local(&config.avatars),
+        StealthServices {
+            registry: stealth_registry.clone(),
+            scan_pool: scan_pool.clone(),
+        },
     );
 
     let indexer = ChainIndexer::new(
@@ -53,12 +84,50 @@ async fn main() -> Result<()> {
         Arc::clone(&cache),
     );
 
+    let lifecycle = ProposalLifecycle::new(
+        database.clone(),
+        config.governance.clone(),
+        Arc::clone(&cache),
+        notifier.clone(),
+    );
+    let webhooks = WebhookDispatcher::new(
+        database.clone(),
+        &config.governance.notifications,
+        &notifier,
+    )?;
+    let stealth_worker = StealthScanWorker::new(
+        database.clone(),
+        stealth_registry,
+        scan_pool,
+        Arc::clone(&last_indexed_block),
+    );
+
     let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let lifecycle_shutdown = shutdown_rx.clone();
+    let webhook_shutdown = shutdown_rx.clone();
+    let stealth_shutdown = shutdown_rx.clone();
     let indexer_handle = tokio::spawn(async move {
         if let Err(err) = indexer.run(shutdown_rx).await {
             error!("Indexer terminated with error: {err}");
         }
     });
+    let lifecycle_handle = tokio::spawn(async move {
+        if let Err(err) = lifecycle.run(lifecycle_shutdown).await {
+            error!("Proposal lifecycle terminated with error: {err}");
+        }
+    });
+
+    let webhook_handle = tokio::spawn(async move {
+        if let Err(err) = webhooks.run(webhook_shutdown).await {
+            error!("Webhook dispatcher terminated with error: {err}");
+        }
+    });
+
+    let stealth_handle = tokio::spawn(async move {
+        if let Err(err) = stealth_worker.run(stealth_shutdown).await {
+            error!("Stealth scan worker terminated with error: {err}");
+        }
+    });
 
     let listener = TcpListener::bind(config.server.address())
         .await
@@ -79,6 +148,15 @@ async fn main() -> Result<()> {
     if let Err(join_err) = indexer_handle.await {
         error!("Indexer task join error: {join_err}");
     }
+    if let Err(join_err) = lifecycle_handle.await {
+        error!("Proposal lifecycle task join error: {join_err}");
+    }
+    if let Err(join_err) = webhook_handle.await {
+        error!("Webhook dispatcher task join error: {join_err}");
+    }
+    if let Err(join_err) = stealth_handle.await {
+        error!("Stealth scan worker task join error: {join_err}");
+    }
 
     Ok(())
 }
@@ -7,20 +7,56 @@ pub struct ProposalView {
     pub targets: Vec<String>,
     pub values: Vec<String>,
     pub calldatas: Vec<String>,
+    /// Per-action decoding of `calldatas` against each target's manifest.
+    pub actions: Vec<ProposalActionView>,
     pub description: String,
     pub vote_start: i64,
     pub vote_end: i64,
     pub votes_for: i64,
     pub votes_against: i64,
     pub votes_abstain: i64,
-    pub state: String,
+    pub state: ProposalState,
     pub executed_at: Option<i64>,
     pub created_at: i64,
     pub updated_at: i64,
+    pub snapshot_block: Option<i64>,
     pub has_voted: Option<bool>,
     pub user_vote: Option<VoteView>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposalActionView {
+    pub index: u32,
+    pub target: String,
+    pub value: String,
+    pub calldata: String,
+    pub target_status: ActionTargetStatus,
+    pub code_hash: Option<String>,
+    pub method: Option<String>,
+    pub arguments: Vec<DecodedArgumentView>,
+    pub decode_error: Option<String>,
+}
+
+/// How much the API can vouch for an action's target contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionTargetStatus {
+    /// The operator checked the published manifest against the code.
+    Verified,
+    /// A manifest is published but has not been checked against the code.
+    Unverified,
+    /// No manifest is published for the target address.
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodedArgumentView {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub param_type: String,
+    pub value: serde_json::Value,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct VoteView {
     pub proposal_id: i64,
@@ -29,6 +65,9 @@ pub struct VoteView {
     pub weight: i64,
     pub reason: Option<String>,
     pub voted_at: i64,
+    /// Voter's power in the proposal's snapshot, when one has been taken.
+    pub snapshot_power: Option<i64>,
+    pub weight_verified: Option<bool>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -39,12 +78,62 @@ pub struct DelegationView {
     pub delegated_at: i64,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelegationEventView {
+    pub delegator: String,
+    pub delegatee: String,
+    pub previous_delegatee: Option<String>,
+    pub event_type: DelegationEventKind,
+    pub amount: i64,
+    pub block_number: i64,
+    pub tx_id: String,
+    pub event_index: i32,
+    pub occurred_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DelegationEventKind {
+    Delegate,
+    Increase,
+    Undelegate,
+    Redelegate,
+}
+
+impl DelegationEventKind {
+    pub const ALL: [DelegationEventKind; 4] = [
+        DelegationEventKind::Delegate,
+        DelegationEventKind::Increase,
+        DelegationEventKind::Undelegate,
+        DelegationEventKind::Redelegate,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DelegationEventKind::Delegate => "delegate",
+            DelegationEventKind::Increase => "increase",
+            DelegationEventKind::Undelegate => "undelegate",
+            DelegationEventKind::Redelegate => "redelegate",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let normalized = value.trim().to_ascii_lowercase();
+        DelegationEventKind::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == normalized)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct VotingPowerView {
     pub address: String,
     pub voting_power: i64,
+    pub staked_power: i64,
     pub delegated_power: i64,
+    /// Same as `voting_power`; kept for clients reading the older field.
     pub total_power: i64,
+    pub snapshot_block: Option<i64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -57,7 +146,7 @@ pub struct ProposalSummary {
     pub votes_for: i64,
     pub votes_against: i64,
     pub votes_abstain: i64,
-    pub state: String,
+    pub state: ProposalState,
     pub created_at: i64,
 }
 
@@ -78,6 +167,22 @@ pub struct DelegateResponse {
     pub delegation: DelegationView,
 }
 
+/// Entry in the delegate directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DelegateView {
+    pub rank: u32,
+    pub address: String,
+    /// Identity linked to the address through a verified wallet link.
+    pub identity_id: Option<String>,
+    pub display_name: Option<String>,
+    pub received_delegation: i64,
+    pub delegator_count: i64,
+    pub votes_cast: i64,
+    pub participation_rate: f64,
+    pub proposals_authored: i64,
+    pub last_vote_at: Option<i64>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GovernanceStatsView {
     pub address: String,
@@ -131,3 +236,144 @@ pub struct VoteSubmissionResponse {
     pub approve: bool,
     pub finalized: bool,
 }
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProposalAnalyticsView {
+    pub proposal_id: i64,
+    pub state: ProposalState,
+    pub eligible_voting_power: i64,
+    pub participation: i64,
+    pub turnout_pct: f64,
+    pub quorum_required: i64,
+    pub quorum_reached: bool,
+    pub approval_threshold_bps: u32,
+    pub approval_pct: f64,
+    pub approval_reached: bool,
+    pub passing: bool,
+    pub margin_to_flip: i64,
+    pub projected: ProjectedOutcomeView,
+    pub timeline: Vec<VoteTimelineBucket>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectedOutcomeView {
+    pub votes_for: i64,
+    pub votes_against: i64,
+    pub votes_abstain: i64,
+    pub turnout_pct: f64,
+    pub passing: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VoteTimelineBucket {
+    pub hour_start: i64,
+    pub votes_cast: i64,
+    pub weight_for: i64,
+    pub weight_against: i64,
+    pub weight_abstain: i64,
+    pub cumulative_for: i64,
+    pub cumulative_against: i64,
+    pub cumulative_abstain: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalState {
+    Pending,
+    Active,
+    Succeeded,
+    Defeated,
+    Queued,
+    Executed,
+    Expired,
+}
+
+impl ProposalState {
+    pub const ALL: [ProposalState; 7] = [
+        ProposalState::Pending,
+        ProposalState::Active,
+        ProposalState::Succeeded,
+        ProposalState::Defeated,
+        ProposalState::Queued,
+        ProposalState::Executed,
+        ProposalState::Expired,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProposalState::Pending => "pending",
+            ProposalState::Active => "active",
+            ProposalState::Succeeded => "succeeded",
+            ProposalState::Defeated => "defeated",
+            ProposalState::Queued => "queued",
+            ProposalState::Executed => "executed",
+            ProposalState::Expired => "expired",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let normalized = value.trim().to_ascii_lowercase();
+        ProposalState::ALL
+            .into_iter()
+            .find(|state| state.as_str() == normalized)
+    }
+
+    /// Terminal states are never revisited by the lifecycle task.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            ProposalState::Defeated | ProposalState::Executed | ProposalState::Expired
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GovernanceEventKind {
+    ProposalCreated,
+    VotingOpened,
+    VotingClosingSoon,
+    ProposalPassed,
+    ProposalFailed,
+    ProposalExecuted,
+}
+
+impl GovernanceEventKind {
+    pub const ALL: [GovernanceEventKind; 6] = [
+        GovernanceEventKind::ProposalCreated,
+        GovernanceEventKind::VotingOpened,
+        GovernanceEventKind::VotingClosingSoon,
+        GovernanceEventKind::ProposalPassed,
+        GovernanceEventKind::ProposalFailed,
+        GovernanceEventKind::ProposalExecuted,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GovernanceEventKind::ProposalCreated => "proposal_created",
+            GovernanceEventKind::VotingOpened => "voting_opened",
+            GovernanceEventKind::VotingClosingSoon => "voting_closing_soon",
+            GovernanceEventKind::ProposalPassed => "proposal_passed",
+            GovernanceEventKind::ProposalFailed => "proposal_failed",
+            GovernanceEventKind::ProposalExecuted => "proposal_executed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let normalized = value.trim().to_ascii_lowercase();
+        GovernanceEventKind::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == normalized)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GovernanceNotificationView {
+    pub proposal_id: i64,
+    pub kind: GovernanceEventKind,
+    pub proposer: String,
+    pub state: ProposalState,
+    pub vote_end: i64,
+    pub block_number: i64,
+    pub emitted_at: i64,
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::Serialize;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
@@ -12,6 +14,23 @@ pub struct IdentityProfileView {
     pub updated_at: i64,
     pub last_synced_block: i64,
     pub profile_version: i32,
+    /// Another identity's display name is confusable with this one.
+    pub name_collision: bool,
+}
+
+/// Profiles for a batch of identity ids and wallet addresses, in request
+/// order. Wallet addresses map to the identity that owns them publicly.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IdentityBatchView {
+    pub profiles: Vec<IdentityProfileView>,
+    pub wallet_owners: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AvatarUploadView {
+    pub avatar_hash: String,
+    pub content_type: &'static str,
+    pub size_bytes: u64,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
@@ -31,3 +50,131 @@ pub struct IdentitySearchResult {
     pub stats_visibility: String,
     pub updated_at: i64,
 }
+
+/// Identity that owns a wallet through a verified link.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WalletOwnerView {
+    pub wallet_address: String,
+    pub identity_id: String,
+    pub display_name: Option<String>,
+    pub link_type: String,
+    pub verified_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FollowEntryView {
+    pub identity_id: String,
+    pub display_name: Option<String>,
+    pub followed_at: i64,
+}
+
+/// One page of an identity's followers or followees, newest first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FollowListView {
+    pub identity_id: String,
+    pub total: u64,
+    pub limit: u64,
+    pub offset: u64,
+    pub entries: Vec<FollowEntryView>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FollowRelationView {
+    pub identity_id: String,
+    pub other_id: String,
+    pub follows: bool,
+    pub followed_by: bool,
+    pub mutual: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    Transfer,
+    ContractDeployment,
+    /// Any other chain transaction type; `detail` carries the type.
+    Transaction,
+    Vote,
+    Delegation,
+    FaucetDrip,
+    ProfileUpdate,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ActivityFeedItemView {
+    pub kind: ActivityKind,
+    pub identity_id: String,
+    pub wallet_address: Option<String>,
+    pub counterparty: Option<String>,
+    pub amount: Option<i64>,
+    pub proposal_id: Option<i64>,
+    pub detail: Option<String>,
+    pub tx_id: Option<String>,
+    pub block_number: Option<i64>,
+    pub occurred_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ActivityFeedView {
+    pub items: Vec<ActivityFeedItemView>,
+    /// Pass back as `cursor` to fetch the next page; absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
+/// One recorded version of a profile. `changed_fields` is relative to the
+/// previous recorded version; wallet changes are omitted when the caller may
+/// not see the identity's wallets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProfileHistoryEntryView {
+    pub profile_version: i32,
+    pub last_synced_block: i64,
+    pub updated_at: i64,
+    pub display_name: Option<String>,
+    pub avatar_hash: Option<String>,
+    pub bio: Option<String>,
+    pub stats_visibility: String,
+    pub changed_fields: Vec<&'static str>,
+    pub wallets_added: Option<Vec<String>>,
+    pub wallets_removed: Option<Vec<String>>,
+}
+
+/// One page of profile history, newest version first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProfileHistoryView {
+    pub identity_id: String,
+    pub total: u64,
+    pub limit: u64,
+    pub offset: u64,
+    pub entries: Vec<ProfileHistoryEntryView>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WalletStatsView {
+    pub wallet_address: String,
+    /// `None` when the node could not report the balance.
+    pub balance: Option<u64>,
+    pub transactions_sent: u64,
+    pub transactions_received: u64,
+}
+
+/// Governance and chain activity summed over every verified wallet of an
+/// identity.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IdentityStatsView {
+    pub identity_id: String,
+    pub wallet_count: u32,
+    pub proposals_submitted: u64,
+    pub votes_cast: u64,
+    pub proposals_voted: u64,
+    pub participation_rate: f64,
+    pub delegated_in: i64,
+    pub delegated_out: i64,
+    pub net_voting_power: i64,
+    /// `None` when any wallet balance is unavailable.
+    pub total_balance: Option<u64>,
+    pub transactions_sent: u64,
+    pub transactions_received: u64,
+    pub first_activity_at: Option<i64>,
+    pub last_activity_at: Option<i64>,
+    pub wallets: Vec<WalletStatsView>,
+}
@@ -32,9 +32,23 @@ pub struct StealthKeyBundlePayload {
     pub spend_keypair: StealthKeyComponentPayload,
 }
 
+/// Watch-only scan credentials: enough to detect outputs and decrypt memos,
+/// never enough to spend them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StealthViewKeyPayload {
+    pub view_secret: String,
+    pub spend_public_key: String,
+}
+
+/// Exactly one of `view_keys` and `stealth_keys` must be set.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StealthScanRequestPayload {
-    pub stealth_keys: StealthKeyBundlePayload,
+    #[serde(default)]
+    pub view_keys: Option<StealthViewKeyPayload>,
+    /// Deprecated: sends the spend secret, which scanning never needs. Use
+    /// `view_keys` instead.
+    #[serde(default)]
+    pub stealth_keys: Option<StealthKeyBundlePayload>,
     #[serde(default)]
     pub from_block: Option<u64>,
     #[serde(default)]
@@ -115,3 +129,78 @@ impl StealthPrivacyLevel {
         }
     }
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactEncryptedMemoView {
+    pub ciphertext: String,
+    pub nonce: String,
+    pub message_number: u32,
+}
+
+/// One line of the NDJSON stealth output stream. Binary fields are hex.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactStealthOutputView {
+    pub cursor: String,
+    pub block_number: u64,
+    pub tx_id: String,
+    pub output_index: u32,
+    pub stealth_public_key: String,
+    pub tx_public_key: String,
+    pub commitment: String,
+    #[serde(default)]
+    pub amount: Option<u64>,
+    #[serde(default)]
+    pub memo: Option<String>,
+    #[serde(default)]
+    pub encrypted_memo: Option<CompactEncryptedMemoView>,
+}
+
+/// Registers view keys for background scanning. `from_block` may reach
+/// back a bounded number of blocks; by default scanning starts at the next
+/// indexed block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StealthRegistrationRequestPayload {
+    pub view_keys: StealthViewKeyPayload,
+    #[serde(default)]
+    pub from_block: Option<u64>,
+    #[serde(default)]
+    pub ttl_seconds: Option<i64>,
+}
+
+/// `access_token` is returned only here; send it as a bearer token to read
+/// the inbox or revoke the registration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StealthRegistrationView {
+    pub registration_id: String,
+    pub access_token: String,
+    pub start_block: u64,
+    pub expires_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StealthInboxEntryView {
+    pub cursor: String,
+    pub detected_at: i64,
+    pub transaction: OwnedStealthTransactionView,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StealthInboxView {
+    pub registration_id: String,
+    /// Highest block scanned so far, `None` before the first scan.
+    #[serde(default)]
+    pub scanned_through_block: Option<u64>,
+    pub expires_at: i64,
+    pub entries: Vec<StealthInboxEntryView>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+/// Pushed when a background scan adds outputs to an inbox. Carries no
+/// output details; fetch the inbox to read them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StealthInboxNotificationView {
+    pub registration_id: String,
+    pub new_outputs: usize,
+    pub scanned_through_block: u64,
+}
@@ -112,6 +112,106 @@ impl RpcClient {
         Ok(response)
     }
 
+    pub async fn fetch_delegation_events(
+        &self,
+        from_block: u64,
+        limit: u64,
+    ) -> Result<DelegationEventsResponse> {
+        assert!(limit > 0, "Delegation event limit must be positive");
+        assert!(
+            limit <= 1024,
+            "Delegation event limit exceeds defensive bound"
+        );
+        let response: DelegationEventsResponse = self
+            .inner
+            .request(
+                "governance_delegationEvents",
+                rpc_params![from_block, limit],
+            )
+            .await
+            .context("RPC call governance_delegationEvents failed")?;
+        assert!(
+            response.latest_block >= from_block,
+            "Delegation events latest block regressed"
+        );
+        assert!(
+            response.events.len() <= limit as usize,
+            "Delegation events response exceeded requested limit"
+        );
+        Ok(response)
+    }
+
+    pub async fn fetch_governance_events(
+        &self,
+        from_block: u64,
+        limit: u64,
+    ) -> Result<GovernanceEventsResponse> {
+        assert!(limit > 0, "Governance event limit must be positive");
+        assert!(
+            limit <= 1024,
+            "Governance event limit exceeds defensive bound"
+        );
+        let response: GovernanceEventsResponse = self
+            .inner
+            .request("governance_events", rpc_params![from_block, limit])
+            .await
+            .context("RPC call governance_events failed")?;
+        assert!(
+            response.latest_block >= from_block,
+            "Governance events latest block regressed"
+        );
+        assert!(
+            response.proposals.len() + response.votes.len() <= limit as usize,
+            "Governance events response exceeded requested limit"
+        );
+        Ok(response)
+    }
+
+    pub async fn fetch_follow_events(
+        &self,
+        from_block: u64,
+        limit: u64,
+    ) -> Result<FollowEventsResponse> {
+        assert!(limit > 0, "Follow event limit must be positive");
+        assert!(limit <= 1024, "Follow event limit exceeds defensive bound");
+        let response: FollowEventsResponse = self
+            .inner
+            .request("social_followEvents", rpc_params![from_block, limit])
+            .await
+            .context("RPC call social_followEvents failed")?;
+        assert!(
+            response.latest_block >= from_block,
+            "Follow events latest block regressed"
+        );
+        assert!(
+            response.events.len() <= limit as usize,
+            "Follow events response exceeded requested limit"
+        );
+        Ok(response)
+    }
+
+    /// Returns the spendable balance of an account in base units.
+    pub async fn fetch_account_balance(&self, address: &str) -> Result<u64> {
+        assert!(!address.is_empty(), "Account address must be provided");
+        let response: AccountBalanceResponse = self
+            .inner
+            .request("accounts_getBalance", rpc_params![address])
+            .await
+            .context("RPC call accounts_getBalance failed")?;
+        Ok(response.balance)
+    }
+
+    /// Returns the code hash of the contract deployed at `address`.
+    pub async fn fetch_contract_code_hash(&self, address: &str) -> Result<String> {
+        assert!(!address.is_empty(), "Contract address must be provided");
+        let response: ContractInfoResponse = self
+            .inner
+            .request("contracts_getContract", rpc_params![address])
+            .await
+            .context("RPC call contracts_getContract failed")?;
+        Ok(response.code_hash)
+    }
+
     pub async fn generate_stealth_address(
         &self,
         request: &StealthAddressRequestPayload,
@@ -383,6 +483,16 @@ struct BlockNumberResponse {
     pub block_number: u64,
 }
 
+#[derive(Debug, Deserialize)]
+struct AccountBalanceResponse {
+    pub balance: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContractInfoResponse {
+    pub code_hash: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct BlocksResponse {
     pub blocks: Vec<Block>,
@@ -411,6 +521,89 @@ pub struct IdentityRecord {
     pub profile_version: Option<u32>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct DelegationEventsResponse {
+    pub latest_block: u64,
+    #[serde(default)]
+    pub events: Vec<DelegationEventRecord>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DelegationEventRecord {
+    pub event_type: String,
+    pub delegator: String,
+    pub delegatee: String,
+    #[serde(default)]
+    pub previous_delegatee: Option<String>,
+    pub amount: u64,
+    pub block_number: u64,
+    pub tx_id: String,
+    pub event_index: u32,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GovernanceEventsResponse {
+    pub latest_block: u64,
+    #[serde(default)]
+    pub proposals: Vec<ProposalEventRecord>,
+    #[serde(default)]
+    pub votes: Vec<VoteEventRecord>,
+}
+
+/// A proposal as created on chain, repeated when it is executed.
+#[derive(Debug, Deserialize)]
+pub struct ProposalEventRecord {
+    pub proposal_id: u64,
+    pub proposer: String,
+    #[serde(default)]
+    pub targets: Vec<String>,
+    #[serde(default)]
+    pub values: Vec<String>,
+    #[serde(default)]
+    pub calldatas: Vec<String>,
+    pub description: String,
+    pub vote_start: i64,
+    pub vote_end: i64,
+    #[serde(default)]
+    pub executed_at: Option<u64>,
+    pub block_number: u64,
+    pub tx_id: String,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VoteEventRecord {
+    pub proposal_id: u64,
+    pub voter: String,
+    /// 0 = against, 1 = for, 2 = abstain.
+    pub support: u8,
+    pub weight: u64,
+    #[serde(default)]
+    pub reason: Option<String>,
+    pub block_number: u64,
+    pub tx_id: String,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FollowEventsResponse {
+    pub latest_block: u64,
+    #[serde(default)]
+    pub events: Vec<FollowEventRecord>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FollowEventRecord {
+    /// `follow` or `unfollow`.
+    pub event_type: String,
+    pub follower_id: String,
+    pub following_id: String,
+    pub block_number: u64,
+    pub event_index: u32,
+    pub timestamp: u64,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct WalletLinkRecord {
     pub wallet_address: String,
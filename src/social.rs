@@ -0,0 +1,76 @@
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, PaginatorTrait, QueryFilter};
+
+use crate::entities::social_connection;
+
+pub const MAX_FOLLOW_PAGE: u64 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowEventKind {
+    Follow,
+    Unfollow,
+}
+
+impl FollowEventKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "follow" => Some(FollowEventKind::Follow),
+            "unfollow" => Some(FollowEventKind::Unfollow),
+            _ => None,
+        }
+    }
+}
+
+pub async fn follows<C: ConnectionTrait>(
+    db: &C,
+    follower_id: &[u8],
+    following_id: &[u8],
+) -> Result<bool, DbErr> {
+    let edges = social_connection::Entity::find()
+        .filter(social_connection::Column::FollowerId.eq(follower_id.to_vec()))
+        .filter(social_connection::Column::FollowingId.eq(following_id.to_vec()))
+        .count(db)
+        .await?;
+    Ok(edges > 0)
+}
+
+/// Two identities that follow each other count as friends for
+/// `friends_only` visibility.
+pub async fn is_mutual_follow<C: ConnectionTrait>(
+    db: &C,
+    first: &[u8],
+    second: &[u8],
+) -> Result<bool, DbErr> {
+    if first == second {
+        return Ok(false);
+    }
+    let edges = social_connection::Entity::find()
+        .filter(
+            social_connection::Column::FollowerId
+                .eq(first.to_vec())
+                .and(social_connection::Column::FollowingId.eq(second.to_vec()))
+                .or(social_connection::Column::FollowerId
+                    .eq(second.to_vec())
+                    .and(social_connection::Column::FollowingId.eq(first.to_vec()))),
+        )
+        .count(db)
+        .await?;
+    Ok(edges == 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follow_event_kinds_parse() {
+        assert_eq!(
+            FollowEventKind::parse("Follow"),
+            Some(FollowEventKind::Follow)
+        );
+        assert_eq!(
+            FollowEventKind::parse(" unfollow "),
+            Some(FollowEventKind::Unfollow)
+        );
+        assert!(FollowEventKind::parse("block").is_none());
+    }
+}
@@ -1,14 +1,23 @@
 use std::sync::Arc;
-use std::sync::atomic::AtomicU64;
+use std::sync::atomic::{AtomicU32, AtomicU64};
 use std::time::{Duration, Instant};
 
 use moka::future::Cache;
 use sea_orm::DatabaseConnection;
 use serde_json::Value;
 
-use crate::config::CacheConfig;
-use crate::models::identity::{IdentityProfileView, IdentitySearchResult, WalletLinkView};
+use crate::avatar::Avatars;
+use crate::config::{CacheConfig, GovernanceConfig};
+use crate::governance::calldata::ContractRegistry;
+use crate::governance::notifier::GovernanceNotifier;
+use crate::identity::WalletChallenge;
+use crate::models::identity::{
+    IdentityProfileView, IdentitySearchResult, IdentityStatsView, WalletLinkView, WalletOwnerView,
+};
 use crate::rpc::RpcClient;
+use crate::stealth_registry::StealthRegistry;
+use crate::stealth_scanner::ScanPool;
+use crate::stealth_stream::OutputPage;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -17,6 +26,25 @@ pub struct AppState {
     pub rpc: RpcClient,
     pub start_time: Instant,
     pub last_indexed_block: Arc<AtomicU64>,
+    pub governance: GovernanceConfig,
+    pub notifier: GovernanceNotifier,
+    pub contracts: ContractRegistry,
+    pub avatars: Avatars,
+    pub stealth_registry: StealthRegistry,
+    pub scan_pool: ScanPool,
+}
+
+/// Governance handles shared by the HTTP layer.
+pub struct GovernanceServices {
+    pub config: GovernanceConfig,
+    pub notifier: GovernanceNotifier,
+    pub contracts: ContractRegistry,
+}
+
+/// Stealth scanning handles shared by the HTTP layer.
+pub struct StealthServices {
+    pub registry: StealthRegistry,
+    pub scan_pool: ScanPool,
 }
 
 impl AppState {
@@ -25,6 +53,9 @@ impl AppState {
         cache: Arc<ApiCache>,
         rpc: RpcClient,
         last_indexed_block: Arc<AtomicU64>,
+        governance: GovernanceServices,
+        avatars: Avatars,
+        stealth: StealthServices,
     ) -> Self {
         assert!(
             cache.identity_capacity >= 100,
@@ -40,6 +71,12 @@ impl AppState {
             rpc,
             start_time: Instant::now(),
             last_indexed_block,
+            governance: governance.config,
+            notifier: governance.notifier,
+            contracts: governance.contracts,
+            avatars,
+            stealth_registry: stealth.registry,
+            scan_pool: stealth.scan_pool,
         }
     }
 }
@@ -48,8 +85,23 @@ pub struct ApiCache {
     pub identity_profiles: Cache<String, Arc<IdentityProfileView>>,
     pub identity_wallets: Cache<String, Arc<Vec<WalletLinkView>>>,
     pub identity_search: Cache<String, Arc<Vec<IdentitySearchResult>>>,
+    /// Complete identity stats with the indexed block they were computed at.
+    pub identity_stats: Cache<String, Arc<(u64, IdentityStatsView)>>,
+    /// Reverse wallet lookups keyed by address; `None` marks an address with
+    /// no publicly visible owner.
+    pub wallet_owners: Cache<String, Option<Arc<WalletOwnerView>>>,
     pub leaderboards: Cache<String, Value>,
     pub proposals: Cache<String, Value>,
+    /// Outstanding wallet link challenges keyed by hex nonce. Entries are
+    /// removed when redeemed so each nonce is single use.
+    pub wallet_challenges: Cache<String, Arc<WalletChallenge>>,
+    pub wallet_challenge_ttl: Duration,
+    /// Challenges issued per identity within the current challenge TTL,
+    /// keyed by hex identity id.
+    pub wallet_challenge_counts: Cache<String, Arc<AtomicU32>>,
+    pub wallet_challenges_per_identity: u32,
+    /// Compact stealth output pages keyed by range and cursor.
+    pub stealth_output_pages: Cache<String, Arc<OutputPage>>,
     pub identity_capacity: u64,
 }
 
@@ -82,6 +134,16 @@ impl ApiCache {
             .time_to_idle(Duration::from_secs(config.identities_ttl_seconds / 2 + 1))
             .build();
 
+        let identity_stats = Cache::builder()
+            .max_capacity(config.identities_max_capacity)
+            .time_to_live(Duration::from_secs(config.identity_stats_ttl_seconds))
+            .build();
+
+        let wallet_owners = Cache::builder()
+            .max_capacity(config.identities_max_capacity)
+            .time_to_live(Duration::from_secs(config.identities_ttl_seconds))
+            .build();
+
         let leaderboards = Cache::builder()
             .max_capacity(config.leaderboards_max_capacity)
             .time_to_live(Duration::from_secs(config.leaderboards_ttl_seconds))
@@ -92,14 +154,37 @@ impl ApiCache {
             .max_capacity(config.proposals_max_capacity)
             .time_to_live(Duration::from_secs(config.proposals_ttl_seconds))
             .time_to_idle(Duration::from_secs(config.proposals_ttl_seconds / 2 + 1))
+            .support_invalidation_closures()
+            .build();
+
+        let wallet_challenge_ttl = Duration::from_secs(config.wallet_challenge_ttl_seconds);
+        let wallet_challenges = Cache::builder()
+            .max_capacity(config.wallet_challenges_max_capacity)
+            .time_to_live(wallet_challenge_ttl)
+            .build();
+        let wallet_challenge_counts = Cache::builder()
+            .max_capacity(config.wallet_challenges_max_capacity)
+            .time_to_live(wallet_challenge_ttl)
+            .build();
+
+        let stealth_output_pages = Cache::builder()
+            .max_capacity(config.stealth_pages_max_capacity)
+            .time_to_live(Duration::from_secs(config.stealth_pages_ttl_seconds))
             .build();
 
         Self {
             identity_profiles,
             identity_wallets,
             identity_search,
+            identity_stats,
+            wallet_owners,
             leaderboards,
             proposals,
+            wallet_challenges,
+            wallet_challenge_ttl,
+            wallet_challenge_counts,
+            wallet_challenges_per_identity: config.wallet_challenges_per_identity,
+            stealth_output_pages,
             identity_capacity: config.identities_max_capacity,
         }
     }
@@ -0,0 +1,417 @@
+pub mod worker;
+
+use std::sync::Arc;
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use chrono::{DateTime, Utc};
+use sea_orm::ActiveValue::{NotSet, Set};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+use sha2::{Digest, Sha256};
+use silica::privacy::ViewOnlyStealthKeys;
+use tokio::sync::broadcast;
+use tracing::{debug, warn};
+
+use crate::config::StealthRegistrationConfig;
+use crate::entities::{stealth_scan_inbox, stealth_scan_registration};
+use crate::models::privacy::{
+    OwnedStealthTransactionView, StealthInboxNotificationView, StealthViewKeyPayload,
+};
+
+pub const MAX_INBOX_PAGE: u64 = 100;
+
+const REGISTRATION_ID_BYTES: usize = 16;
+const ACCESS_TOKEN_BYTES: usize = 32;
+const NONCE_BYTES: usize = 12;
+const KEY_DOMAIN: &[u8] = b"silica-api/stealth-registration/v1";
+const KEYS_AAD: &[u8] = b"keys:";
+const INBOX_AAD: &[u8] = b"inbox:";
+
+/// Seals registration key material and inbox entries with
+/// ChaCha20-Poly1305. Every registration gets its own key derived from the
+/// server key, and ciphertexts are bound to their registration, so rows
+/// cannot be swapped between inboxes.
+#[derive(Clone)]
+pub struct RegistrySealer {
+    master: [u8; 32],
+}
+
+impl RegistrySealer {
+    pub fn new(master: [u8; 32]) -> Self {
+        Self { master }
+    }
+
+    fn registration_key(&self, registration_id: &str) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(KEY_DOMAIN);
+        hasher.update(self.master);
+        hasher.update(registration_id.as_bytes());
+        hasher.finalize().into()
+    }
+
+    fn seal(&self, registration_id: &str, purpose: &[u8], plaintext: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let key = self.registration_key(registration_id);
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&key));
+        let nonce: [u8; NONCE_BYTES] = rand::random();
+        let aad = [purpose, registration_id.as_bytes()].concat();
+        let ciphertext = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad: &aad,
+                },
+            )
+            .expect("ChaCha20-Poly1305 encryption is infallible for in-memory buffers");
+        (ciphertext, nonce.to_vec())
+    }
+
+    fn open(
+        &self,
+        registration_id: &str,
+        purpose: &[u8],
+        ciphertext: &[u8],
+        nonce: &[u8],
+    ) -> Option<Vec<u8>> {
+        if nonce.len() != NONCE_BYTES {
+            return None;
+        }
+        let key = self.registration_key(registration_id);
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&key));
+        let aad = [purpose, registration_id.as_bytes()].concat();
+        cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: &aad,
+                },
+            )
+            .ok()
+    }
+
+    pub fn seal_keys(
+        &self,
+        registration_id: &str,
+        keys: &StealthViewKeyPayload,
+    ) -> (Vec<u8>, Vec<u8>) {
+        let plaintext = serde_json::to_vec(keys).expect("View key payload serializes");
+        self.seal(registration_id, KEYS_AAD, &plaintext)
+    }
+
+    pub fn open_keys(
+        &self,
+        registration: &stealth_scan_registration::Model,
+    ) -> Option<ViewOnlyStealthKeys> {
+        let plaintext = self.open(
+            &registration.registration_id,
+            KEYS_AAD,
+            &registration.key_ciphertext,
+            &registration.key_nonce,
+        )?;
+        let keys: StealthViewKeyPayload = serde_json::from_slice(&plaintext).ok()?;
+        ViewOnlyStealthKeys::from_hex_components(&keys.view_secret, &keys.spend_public_key).ok()
+    }
+
+    /// Keyed fingerprint of an output, unique per registration without
+    /// revealing which output it is.
+    pub fn output_tag(
+        &self,
+        registration_id: &str,
+        output: &OwnedStealthTransactionView,
+    ) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update(self.registration_key(registration_id));
+        hasher.update(output.transaction_id.as_bytes());
+        hasher.update(b":");
+        hasher.update(output.stealth_address.public_key.as_bytes());
+        hasher.finalize().to_vec()
+    }
+}
+
+/// Fan-out of "new inbox entries" events. Each live stream filters for its
+/// own registration.
+#[derive(Clone)]
+pub struct StealthInboxNotifier {
+    sender: broadcast::Sender<Arc<StealthInboxNotificationView>>,
+}
+
+impl StealthInboxNotifier {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "Notification capacity must be positive");
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<StealthInboxNotificationView>> {
+        self.sender.subscribe()
+    }
+
+    fn publish(&self, notification: StealthInboxNotificationView) {
+        if self.sender.send(Arc::new(notification)).is_err() {
+            debug!("No stealth inbox subscribers connected");
+        }
+    }
+}
+
+/// Shared handle for registration routes and the background scanner.
+/// `sealer` is `None` while no encryption key is configured, which
+/// disables registration.
+#[derive(Clone)]
+pub struct StealthRegistry {
+    pub sealer: Option<RegistrySealer>,
+    pub notifier: StealthInboxNotifier,
+    pub config: StealthRegistrationConfig,
+}
+
+impl StealthRegistry {
+    pub fn new(config: &StealthRegistrationConfig) -> anyhow::Result<Self> {
+        let sealer = config.encryption_key()?.map(RegistrySealer::new);
+        if sealer.is_none() {
+            warn!("No stealth registration key configured; registered scanning disabled");
+        }
+        Ok(Self {
+            sealer,
+            notifier: StealthInboxNotifier::new(config.stream_capacity),
+            config: config.clone(),
+        })
+    }
+}
+
+/// Fresh registration id and access token, both hex. Only the token's hash
+/// is stored.
+pub fn new_credentials() -> (String, String) {
+    let id: [u8; REGISTRATION_ID_BYTES] = rand::random();
+    let token: [u8; ACCESS_TOKEN_BYTES] = rand::random();
+    (hex::encode(id), hex::encode(token))
+}
+
+pub fn token_hash(token: &str) -> Vec<u8> {
+    Sha256::digest(token.trim().as_bytes()).to_vec()
+}
+
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Registrations still live at `now`. Expired rows awaiting cleanup do not
+/// hold capacity.
+pub async fn count_registrations<C: ConnectionTrait>(
+    db: &C,
+    now: DateTime<Utc>,
+) -> Result<u64, DbErr> {
+    stealth_scan_registration::Entity::find()
+        .filter(stealth_scan_registration::Column::ExpiresAt.gt(now))
+        .count(db)
+        .await
+}
+
+/// The live registration `registration_id` if `token` grants access to it.
+pub async fn authorize_registration<C: ConnectionTrait>(
+    db: &C,
+    registration_id: &str,
+    token: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<stealth_scan_registration::Model>, DbErr> {
+    let registration = stealth_scan_registration::Entity::find_by_id(registration_id.to_string())
+        .one(db)
+        .await?;
+    Ok(registration.filter(|registration| {
+        registration.expires_at > now
+            && constant_time_eq(&registration.token_hash, &token_hash(token))
+    }))
+}
+
+/// Deletes registrations past their expiry; their inboxes go with them.
+pub async fn purge_expired<C: ConnectionTrait>(db: &C, now: DateTime<Utc>) -> Result<u64, DbErr> {
+    let result = stealth_scan_registration::Entity::delete_many()
+        .filter(stealth_scan_registration::Column::ExpiresAt.lte(now))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected)
+}
+
+/// Live registrations that have not scanned `latest_block` yet, furthest
+/// behind first.
+pub async fn pending_registrations<C: ConnectionTrait>(
+    db: &C,
+    latest_block: i64,
+    now: DateTime<Utc>,
+    limit: u64,
+) -> Result<Vec<stealth_scan_registration::Model>, DbErr> {
+    stealth_scan_registration::Entity::find()
+        .filter(stealth_scan_registration::Column::NextBlock.lte(latest_block))
+        .filter(stealth_scan_registration::Column::ExpiresAt.gt(now))
+        .order_by_asc(stealth_scan_registration::Column::NextBlock)
+        .order_by_asc(stealth_scan_registration::Column::RegistrationId)
+        .limit(limit)
+        .all(db)
+        .await
+}
+
+/// Seals and stores detected outputs. Outputs already in the inbox are
+/// skipped.
+pub async fn store_detections<C: ConnectionTrait>(
+    db: &C,
+    sealer: &RegistrySealer,
+    registration_id: &str,
+    outputs: &[OwnedStealthTransactionView],
+) -> Result<(), DbErr> {
+    if outputs.is_empty() {
+        return Ok(());
+    }
+    let rows = outputs.iter().map(|output| {
+        let plaintext = serde_json::to_vec(output).expect("Owned output view serializes");
+        let (ciphertext, nonce) = sealer.seal(registration_id, INBOX_AAD, &plaintext);
+        stealth_scan_inbox::ActiveModel {
+            id: NotSet,
+            registration_id: Set(registration_id.to_string()),
+            output_tag: Set(sealer.output_tag(registration_id, output)),
+            ciphertext: Set(ciphertext),
+            nonce: Set(nonce),
+            detected_at: Set(Utc::now().fixed_offset()),
+        }
+    });
+    stealth_scan_inbox::Entity::insert_many(rows)
+        .on_conflict(
+            OnConflict::columns([
+                stealth_scan_inbox::Column::RegistrationId,
+                stealth_scan_inbox::Column::OutputTag,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub struct InboxEntry {
+    pub sequence: i64,
+    pub detected_at: DateTime<Utc>,
+    pub output: OwnedStealthTransactionView,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct InboxPage {
+    pub entries: Vec<InboxEntry>,
+    pub next_cursor: Option<i64>,
+}
+
+/// Inbox entries after `after`, oldest first. Entries that fail to open are
+/// skipped with a warning.
+pub async fn load_inbox<C: ConnectionTrait>(
+    db: &C,
+    sealer: &RegistrySealer,
+    registration_id: &str,
+    after: Option<i64>,
+    limit: u64,
+) -> Result<InboxPage, DbErr> {
+    assert!(
+        (1..=MAX_INBOX_PAGE).contains(&limit),
+        "Inbox page size must be bounded"
+    );
+    let mut query = stealth_scan_inbox::Entity::find()
+        .filter(stealth_scan_inbox::Column::RegistrationId.eq(registration_id));
+    if let Some(after) = after {
+        query = query.filter(stealth_scan_inbox::Column::Id.gt(after));
+    }
+    let mut rows = query
+        .order_by_asc(stealth_scan_inbox::Column::Id)
+        .limit(limit + 1)
+        .all(db)
+        .await?;
+    let has_more = rows.len() as u64 > limit;
+    rows.truncate(limit as usize);
+
+    let next_cursor = has_more.then(|| rows.last().map(|row| row.id)).flatten();
+    let entries = rows
+        .into_iter()
+        .filter_map(|row| {
+            let output = sealer
+                .open(registration_id, INBOX_AAD, &row.ciphertext, &row.nonce)
+                .and_then(|plaintext| serde_json::from_slice(&plaintext).ok());
+            if output.is_none() {
+                warn!(
+                    registration_id,
+                    sequence = row.id,
+                    "Skipping stealth inbox entry that failed to open"
+                );
+            }
+            Some(InboxEntry {
+                sequence: row.id,
+                detected_at: row.detected_at.with_timezone(&Utc),
+                output: output?,
+            })
+        })
+        .collect();
+    Ok(InboxPage {
+        entries,
+        next_cursor,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::privacy::StealthAddressObservation;
+
+    fn output(tx_id: &str) -> OwnedStealthTransactionView {
+        OwnedStealthTransactionView {
+            transaction_id: tx_id.to_string(),
+            sender: "sender".to_string(),
+            fee: 1,
+            amount: 10,
+            timestamp: serde_json::Value::Null,
+            stealth_address: StealthAddressObservation {
+                public_key: "aa".repeat(32),
+                tx_public_key: "bb".repeat(32),
+            },
+            memo: None,
+        }
+    }
+
+    #[test]
+    fn sealed_entries_are_bound_to_their_registration() {
+        let sealer = RegistrySealer::new([7; 32]);
+        let (ciphertext, nonce) = sealer.seal("reg-a", INBOX_AAD, b"payload");
+        assert_eq!(
+            sealer.open("reg-a", INBOX_AAD, &ciphertext, &nonce),
+            Some(b"payload".to_vec())
+        );
+        assert_eq!(sealer.open("reg-b", INBOX_AAD, &ciphertext, &nonce), None);
+        assert_eq!(sealer.open("reg-a", KEYS_AAD, &ciphertext, &nonce), None);
+        assert_eq!(
+            RegistrySealer::new([8; 32]).open("reg-a", INBOX_AAD, &ciphertext, &nonce),
+            None
+        );
+    }
+
+    #[test]
+    fn output_tags_differ_per_registration() {
+        let sealer = RegistrySealer::new([7; 32]);
+        let tag = sealer.output_tag("reg-a", &output("tx1"));
+        assert_eq!(tag, sealer.output_tag("reg-a", &output("tx1")));
+        assert_ne!(tag, sealer.output_tag("reg-b", &output("tx1")));
+        assert_ne!(tag, sealer.output_tag("reg-a", &output("tx2")));
+    }
+
+    #[test]
+    fn credentials_verify_by_hash() {
+        let (id, token) = new_credentials();
+        assert_eq!(id.len(), REGISTRATION_ID_BYTES * 2);
+        assert!(constant_time_eq(&token_hash(&token), &token_hash(&token)));
+        assert!(!constant_time_eq(&token_hash(&token), &token_hash(&id)));
+        assert!(!constant_time_eq(b"ab", b"abc"));
+    }
+}
@@ -0,0 +1,207 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, TransactionTrait,
+};
+use tokio::sync::watch;
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+use crate::entities::{stealth_output, stealth_scan_registration};
+use crate::models::privacy::StealthInboxNotificationView;
+use crate::stealth_scanner::{ScanKeys, ScanPool};
+
+use super::{
+    RegistrySealer, StealthRegistry, pending_registrations, purge_expired, store_detections,
+};
+
+/// Registrations advanced per scan round.
+const REGISTRATIONS_PER_ROUND: u64 = 256;
+/// Scan rounds per tick; each round covers one block window.
+const MAX_ROUNDS_PER_TICK: usize = 16;
+
+/// Background task that scans newly indexed blocks for every registered
+/// view key, so a wallet's outputs are found once instead of on every
+/// request.
+pub struct StealthScanWorker {
+    database: DatabaseConnection,
+    registry: StealthRegistry,
+    pool: ScanPool,
+    last_indexed_block: Arc<AtomicU64>,
+}
+
+impl StealthScanWorker {
+    pub fn new(
+        database: DatabaseConnection,
+        registry: StealthRegistry,
+        pool: ScanPool,
+        last_indexed_block: Arc<AtomicU64>,
+    ) -> Self {
+        Self {
+            database,
+            registry,
+            pool,
+            last_indexed_block,
+        }
+    }
+
+    pub async fn run(self, mut shutdown: watch::Receiver<bool>) -> Result<()> {
+        let Some(sealer) = self.registry.sealer.clone() else {
+            info!("Stealth registrations disabled; scan worker idle");
+            return Ok(());
+        };
+        info!("Starting stealth registration scan worker");
+        loop {
+            tokio::select! {
+                changed = shutdown.changed() => {
+                    match changed {
+                        Ok(_) => {
+                            if *shutdown.borrow() {
+                                info!("Stealth scan worker shutdown signal received");
+                                break;
+                            }
+                        }
+                        Err(_) => {
+                            warn!("Shutdown channel closed unexpectedly. Exiting stealth scan worker");
+                            break;
+                        }
+                    }
+                }
+                _ = sleep(self.registry.config.scan_interval()) => {
+                    if let Err(err) = self.tick(&sealer).await {
+                        warn!("Stealth scan tick failed: {err:#}");
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn tick(&self, sealer: &RegistrySealer) -> Result<()> {
+        let now = Utc::now();
+        let purged = purge_expired(&self.database, now)
+            .await
+            .context("Failed to purge expired stealth registrations")?;
+        if purged > 0 {
+            info!("Purged {purged} expired stealth registrations");
+        }
+
+        let latest = self.last_indexed_block.load(AtomicOrdering::SeqCst);
+        let latest = i64::try_from(latest).context("Indexed block exceeds storage bounds")?;
+        let batch = i64::try_from(self.registry.config.scan_batch_blocks)
+            .context("Scan batch exceeds storage bounds")?;
+
+        for _ in 0..MAX_ROUNDS_PER_TICK {
+            let pending =
+                pending_registrations(&self.database, latest, now, REGISTRATIONS_PER_ROUND)
+                    .await
+                    .context("Failed to load pending stealth registrations")?;
+            let Some(from_block) = pending.first().map(|registration| registration.next_block)
+            else {
+                break;
+            };
+            let to_block = latest.min(from_block.saturating_add(batch - 1));
+
+            let outputs = stealth_output::Entity::find()
+                .filter(stealth_output::Column::BlockNumber.gte(from_block))
+                .filter(stealth_output::Column::BlockNumber.lte(to_block))
+                .order_by_asc(stealth_output::Column::BlockNumber)
+                .order_by_asc(stealth_output::Column::TxId)
+                .order_by_asc(stealth_output::Column::OutputIndex)
+                .all(&self.database)
+                .await
+                .context("Failed to load stealth outputs for registered scan")?;
+            debug!(
+                from_block,
+                to_block,
+                outputs = outputs.len(),
+                registrations = pending.len(),
+                "Scanning stealth outputs for registrations"
+            );
+
+            // Registered scanning shares the scan slots with requests, but
+            // waits for one instead of failing.
+            let _permit = self.pool.acquire().await;
+            for registration in pending
+                .iter()
+                .filter(|registration| registration.next_block <= to_block)
+            {
+                if let Err(err) = self
+                    .scan_registration(sealer, registration, &outputs, to_block)
+                    .await
+                {
+                    warn!(
+                        registration_id = %registration.registration_id,
+                        "Registered stealth scan failed: {err:#}"
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Scans `outputs` (sorted by block, ending at `to_block`) from the
+    /// registration's next block and advances it past `to_block`.
+    async fn scan_registration(
+        &self,
+        sealer: &RegistrySealer,
+        registration: &stealth_scan_registration::Model,
+        outputs: &[stealth_output::Model],
+        to_block: i64,
+    ) -> Result<()> {
+        let start = outputs.partition_point(|output| output.block_number < registration.next_block);
+        let detected = match sealer.open_keys(registration) {
+            Some(keys) => {
+                self.pool
+                    .owned_outputs_in(&outputs[start..], ScanKeys::ViewOnly(keys))
+                    .await
+            }
+            None => {
+                // Skip ahead rather than retrying undecryptable keys forever.
+                warn!(
+                    registration_id = %registration.registration_id,
+                    "Registered view keys failed to open; skipping blocks"
+                );
+                Vec::new()
+            }
+        };
+
+        let txn = self.database.begin().await?;
+        let advanced = stealth_scan_registration::Entity::update_many()
+            .col_expr(
+                stealth_scan_registration::Column::NextBlock,
+                Expr::value(to_block + 1),
+            )
+            .filter(
+                stealth_scan_registration::Column::RegistrationId
+                    .eq(registration.registration_id.as_str()),
+            )
+            .filter(stealth_scan_registration::Column::NextBlock.eq(registration.next_block))
+            .exec(&txn)
+            .await?;
+        if advanced.rows_affected == 0 {
+            // Revoked, or advanced by another worker, since it was loaded.
+            txn.rollback().await?;
+            return Ok(());
+        }
+        store_detections(&txn, sealer, &registration.registration_id, &detected).await?;
+        txn.commit().await?;
+
+        if !detected.is_empty() {
+            self.registry
+                .notifier
+                .publish(StealthInboxNotificationView {
+                    registration_id: registration.registration_id.clone(),
+                    new_outputs: detected.len(),
+                    scanned_through_block: u64::try_from(to_block).unwrap_or(0),
+                });
+        }
+        Ok(())
+    }
+}
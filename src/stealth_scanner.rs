@@ -1,20 +1,29 @@
 use std::convert::TryFrom;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
 
-use anyhow::anyhow;
+use anyhow::{Context, anyhow};
 use chrono::{DateTime, Utc};
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
 use sea_orm::{
     ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter,
-    QueryOrder,
+    QueryOrder, QuerySelect,
 };
 use serde_json::Value;
 use silica::privacy::stealth::OwnedTransaction;
 use silica::privacy::transactions::StealthTransaction;
-use silica::privacy::{DoubleRatchetState, EncryptedPayload, StealthAddress, StealthKeyPair};
+use silica::privacy::{
+    DoubleRatchetState, EncryptedPayload, StealthAddress, StealthKeyPair, ViewOnlyStealthKeys,
+};
 use silica_models::stealth::StealthAddressView;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, oneshot};
 use tracing::warn;
 
+use crate::config::StealthScanConfig;
 use crate::entities::stealth_output;
 use crate::models::privacy::{OwnedStealthTransactionView, StealthAddressObservation};
+use crate::stealth_stream::{OutputCursor, after_cursor};
 
 const MAX_OUTPUTS_PER_REQUEST: u64 = 200_000;
 
@@ -28,6 +37,36 @@ pub enum ScanError {
         "requested range returned {observed} stealth outputs which exceeds the defensive bound of {limit}"
     )]
     OutputOverflow { observed: u64, limit: u64 },
+    #[error("too many stealth scans in progress")]
+    Busy,
+}
+
+/// Keys a scan detects ownership with. View-only keys (view secret plus
+/// spend public key) find owned outputs and decrypt memos but can never
+/// spend them, so the server does not need to hold a spend secret.
+pub enum ScanKeys {
+    /// Deprecated: carries the spend secret, which scanning does not use.
+    Full(StealthKeyPair),
+    ViewOnly(ViewOnlyStealthKeys),
+}
+
+impl ScanKeys {
+    fn owns_stealth_address(&self, address: &StealthAddress) -> bool {
+        match self {
+            ScanKeys::Full(keys) => keys.owns_stealth_address(address).is_some(),
+            ScanKeys::ViewOnly(keys) => keys.owns_stealth_address(address).is_some(),
+        }
+    }
+
+    fn scan_for_transaction(&self, transaction: &StealthTransaction) -> Option<OwnedTransaction> {
+        let transactions = std::slice::from_ref(transaction);
+        match self {
+            ScanKeys::Full(keys) => keys.scan_for_transactions(transactions),
+            ScanKeys::ViewOnly(keys) => keys.scan_for_transactions(transactions),
+        }
+        .into_iter()
+        .next()
+    }
 }
 
 #[derive(Debug, Default)]
@@ -43,16 +82,127 @@ impl ScanOutcome {
     pub fn empty() -> Self {
         Self::default()
     }
+
+    fn absorb(&mut self, owned: Vec<OwnedStealthTransactionView>, limit: usize) {
+        for view in owned {
+            self.total_balance = self.total_balance.saturating_add(view.amount);
+            self.owned_total += 1;
+            if self.transactions.len() < limit {
+                self.transactions.push(view);
+            }
+        }
+        self.has_more = self.owned_total > self.transactions.len();
+    }
+}
+
+/// Dedicated threads for the elliptic-curve checks and memo decryption of
+/// stealth scans, keeping them off the async runtime. A semaphore caps how
+/// many scans run at once across requests and registered scanning.
+#[derive(Clone)]
+pub struct ScanPool {
+    threads: Arc<ThreadPool>,
+    permits: Arc<Semaphore>,
+    chunk_rows: u64,
+}
+
+impl ScanPool {
+    pub fn new(config: &StealthScanConfig) -> anyhow::Result<Self> {
+        let threads = ThreadPoolBuilder::new()
+            .num_threads(config.worker_threads())
+            .thread_name(|index| format!("stealth-scan-{index}"))
+            .build()
+            .context("Failed to build stealth scan thread pool")?;
+        Ok(Self {
+            threads: Arc::new(threads),
+            permits: Arc::new(Semaphore::new(config.max_concurrent_scans)),
+            chunk_rows: config.chunk_rows,
+        })
+    }
+
+    /// A scan slot, or `None` if all are taken.
+    pub fn try_acquire(&self) -> Option<OwnedSemaphorePermit> {
+        Arc::clone(&self.permits).try_acquire_owned().ok()
+    }
+
+    /// Waits for a scan slot; for background work that can queue.
+    pub async fn acquire(&self) -> OwnedSemaphorePermit {
+        Arc::clone(&self.permits)
+            .acquire_owned()
+            .await
+            .expect("Scan semaphore is never closed")
+    }
+
+    /// Evaluates `records` in parallel on the pool, preserving their order.
+    /// Records still queued when `cancel` is set are skipped.
+    async fn detect(
+        &self,
+        records: Vec<StealthOutputRecord>,
+        keys: Arc<ScanKeys>,
+        cancel: Arc<AtomicBool>,
+    ) -> Vec<OwnedStealthTransactionView> {
+        let (sender, receiver) = oneshot::channel();
+        self.threads.spawn(move || {
+            let owned = records
+                .par_iter()
+                .filter_map(|record| {
+                    if cancel.load(AtomicOrdering::Relaxed) {
+                        return None;
+                    }
+                    evaluate_record(record, &keys)
+                })
+                .collect::<Vec<_>>();
+            // The receiver is gone if the scan was cancelled.
+            let _ = sender.send(owned);
+        });
+        receiver.await.unwrap_or_default()
+    }
+
+    /// Every output among `models` that `keys` own, in input order. The
+    /// caller holds a scan slot.
+    pub async fn owned_outputs_in(
+        &self,
+        models: &[stealth_output::Model],
+        keys: ScanKeys,
+    ) -> Vec<OwnedStealthTransactionView> {
+        let cancel = CancelOnDrop::new();
+        self.detect(convert_models(models), Arc::new(keys), cancel.flag())
+            .await
+    }
+}
+
+/// Flags a scan as cancelled when dropped. Axum drops a handler's future
+/// when its client disconnects, so pending detection work stops too.
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl CancelOnDrop {
+    fn new() -> Self {
+        Self(Arc::new(AtomicBool::new(false)))
+    }
+
+    fn flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.0)
+    }
 }
 
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, AtomicOrdering::Relaxed);
+    }
+}
+
+/// Scans `from_block..=to_block` chunk by chunk: while the pool checks one
+/// chunk the next is fetched, so neither the rows nor the detection of the
+/// whole range block a runtime worker.
 pub async fn scan_owned_outputs(
     database: &DatabaseConnection,
-    keys: &StealthKeyPair,
+    pool: &ScanPool,
+    keys: ScanKeys,
     from_block: u64,
     to_block: u64,
     limit: usize,
 ) -> Result<ScanOutcome, ScanError> {
     assert!(from_block <= to_block, "scan range must be ordered");
+    let _permit = pool.try_acquire().ok_or(ScanError::Busy)?;
     let from_i64 = i64::try_from(from_block)
         .map_err(|_| ScanError::BlockBoundExceeded { block: from_block })?;
     let to_i64 =
@@ -78,15 +228,57 @@ pub async fn scan_owned_outputs(
         });
     }
 
-    let models = stealth_output::Entity::find()
-        .filter(range_condition)
+    let keys = Arc::new(keys);
+    let cancel = CancelOnDrop::new();
+    let mut outcome = ScanOutcome::empty();
+    let mut chunk = fetch_chunk(database, &range_condition, None, pool.chunk_rows).await?;
+    while !chunk.is_empty() {
+        let next_cursor = (chunk.len() as u64 == pool.chunk_rows)
+            .then(|| chunk.last().map(chunk_cursor))
+            .flatten();
+        let records = convert_models(&chunk);
+        outcome.total_scanned += records.len();
+
+        let detection = pool.detect(records, Arc::clone(&keys), cancel.flag());
+        let (owned, next_chunk) = match next_cursor {
+            Some(cursor) => {
+                let fetch = fetch_chunk(database, &range_condition, Some(&cursor), pool.chunk_rows);
+                tokio::join!(detection, fetch)
+            }
+            None => (detection.await, Ok(Vec::new())),
+        };
+        outcome.absorb(owned, limit);
+        chunk = next_chunk?;
+    }
+
+    Ok(outcome)
+}
+
+async fn fetch_chunk(
+    database: &DatabaseConnection,
+    range_condition: &Condition,
+    after: Option<&OutputCursor>,
+    chunk_rows: u64,
+) -> Result<Vec<stealth_output::Model>, DbErr> {
+    let mut query = stealth_output::Entity::find().filter(range_condition.clone());
+    if let Some(after) = after {
+        query = query.filter(after_cursor(after));
+    }
+    query
         .order_by_asc(stealth_output::Column::BlockNumber)
+        .order_by_asc(stealth_output::Column::TxId)
         .order_by_asc(stealth_output::Column::OutputIndex)
+        .limit(chunk_rows)
         .all(database)
-        .await?;
+        .await
+}
 
-    let records = convert_models(&models);
-    Ok(detect_owned_outputs(&records, keys, limit))
+fn chunk_cursor(model: &stealth_output::Model) -> OutputCursor {
+    OutputCursor {
+        block_number: u64::try_from(model.block_number).unwrap_or(0),
+        tx_id: model.tx_id.clone(),
+        output_index: u32::try_from(model.output_index).unwrap_or(0),
+    }
 }
 
 fn convert_models(models: &[stealth_output::Model]) -> Vec<StealthOutputRecord> {
@@ -104,49 +296,23 @@ fn convert_models(models: &[stealth_output::Model]) -> Vec<StealthOutputRecord>
     records
 }
 
-fn detect_owned_outputs(
-    records: &[StealthOutputRecord],
-    keys: &StealthKeyPair,
-    limit: usize,
-) -> ScanOutcome {
-    if records.is_empty() {
-        return ScanOutcome::empty();
-    }
-
-    let mut outcome = ScanOutcome {
-        transactions: Vec::with_capacity(limit.min(records.len())),
-        owned_total: 0,
-        total_balance: 0,
-        total_scanned: records.len(),
-        has_more: false,
-    };
-
-    for record in records {
-        let maybe_view = match &record.kind {
-            StoredOutputKind::Plaintext { amount, memo } => {
-                evaluate_plaintext(record, *amount, memo, keys)
-            }
-            StoredOutputKind::Encrypted { memo } => evaluate_encrypted(record, memo, keys),
-        };
-
-        if let Some(view) = maybe_view {
-            outcome.total_balance = outcome.total_balance.saturating_add(view.amount);
-            outcome.owned_total += 1;
-            if outcome.transactions.len() < limit {
-                outcome.transactions.push(view);
-            }
+fn evaluate_record(
+    record: &StealthOutputRecord,
+    keys: &ScanKeys,
+) -> Option<OwnedStealthTransactionView> {
+    match &record.kind {
+        StoredOutputKind::Plaintext { amount, memo } => {
+            evaluate_plaintext(record, *amount, memo, keys)
         }
+        StoredOutputKind::Encrypted { memo } => evaluate_encrypted(record, memo, keys),
     }
-
-    outcome.has_more = outcome.owned_total > outcome.transactions.len();
-    outcome
 }
 
 fn evaluate_plaintext(
     record: &StealthOutputRecord,
     amount: u64,
     memo: &Option<String>,
-    keys: &StealthKeyPair,
+    keys: &ScanKeys,
 ) -> Option<OwnedStealthTransactionView> {
     let address = record.address.to_stealth_address().ok()?;
     let stealth_tx = StealthTransaction {
@@ -161,12 +327,7 @@ fn evaluate_plaintext(
         memo: memo.clone(),
     };
 
-    let owned: Option<OwnedTransaction> = keys
-        .scan_for_transactions(std::slice::from_ref(&stealth_tx))
-        .into_iter()
-        .next();
-
-    let owned = owned?;
+    let owned = keys.scan_for_transaction(&stealth_tx)?;
     let decrypted_amount = owned.decrypted_amount.unwrap_or(amount);
     let memo_value = owned
         .decrypted_memo
@@ -180,10 +341,12 @@ fn evaluate_plaintext(
 fn evaluate_encrypted(
     record: &StealthOutputRecord,
     memo: &StoredEncryptedMemo,
-    keys: &StealthKeyPair,
+    keys: &ScanKeys,
 ) -> Option<OwnedStealthTransactionView> {
     let address = record.address.to_stealth_address().ok()?;
-    keys.owns_stealth_address(&address)?;
+    if !keys.owns_stealth_address(&address) {
+        return None;
+    }
 
     let compressed = address.public_key.compress();
     let shared_secret = compressed.as_bytes();
@@ -223,6 +386,7 @@ fn memo_to_value(memo: &str) -> Value {
     serde_json::from_str(memo).unwrap_or_else(|_| Value::String(memo.to_string()))
 }
 
+#[derive(Clone)]
 struct StealthOutputRecord {
     tx_id: String,
     sender: String,
@@ -245,6 +409,7 @@ impl AddressRecord {
     }
 }
 
+#[derive(Clone)]
 enum StoredOutputKind {
     Plaintext { amount: u64, memo: Option<String> },
     Encrypted { memo: StoredEncryptedMemo },
@@ -346,50 +511,66 @@ mod tests {
     use chrono::Utc;
     use silica::privacy::PrivateTransactionPayload;
 
-    fn address_record(address: &StealthAddress) -> AddressRecord {
+    fn output_model(tx_id: &str, address: &StealthAddress) -> stealth_output::Model {
         let view = address.to_view();
-        let observation = StealthAddressObservation {
-            public_key: hex::encode(view.public_key),
-            tx_public_key: hex::encode(view.tx_public_key),
-        };
-        AddressRecord { view, observation }
+        let now = Utc::now().fixed_offset();
+        stealth_output::Model {
+            tx_id: tx_id.to_string(),
+            output_index: 0,
+            block_number: 1,
+            sender: "sender".to_string(),
+            fee: 1,
+            timestamp: now,
+            commitment: Vec::new(),
+            stealth_public_key: view.public_key.to_vec(),
+            tx_public_key: view.tx_public_key.to_vec(),
+            amount: None,
+            memo_plaintext: None,
+            encrypted_memo_ciphertext: None,
+            encrypted_memo_nonce: None,
+            encrypted_memo_message_number: None,
+            output_created_at: now,
+            inserted_at: now,
+        }
     }
 
-    #[test]
-    fn detect_owned_plaintext_outputs_returns_view() {
+    fn scan_pool() -> ScanPool {
+        ScanPool::new(&StealthScanConfig {
+            worker_threads: Some(2),
+            ..StealthScanConfig::default()
+        })
+        .expect("pool")
+    }
+
+    #[tokio::test]
+    async fn detect_owned_plaintext_outputs_returns_view() {
         let recipient = StealthKeyPair::generate();
         let (address, _) = StealthKeyPair::generate_stealth_address(
             &recipient.view_keypair.public,
             &recipient.spend_keypair.public,
         );
 
-        let record = StealthOutputRecord {
-            tx_id: "tx_plain".to_string(),
+        let model = stealth_output::Model {
             sender: "sender_alpha".to_string(),
             fee: 10,
-            timestamp: Utc::now(),
-            address: address_record(&address),
-            kind: StoredOutputKind::Plaintext {
-                amount: 42,
-                memo: Some("{\"note\":\"hello\"}".to_string()),
-            },
+            amount: Some(42),
+            memo_plaintext: Some("{\"note\":\"hello\"}".to_string()),
+            ..output_model("tx_plain", &address)
         };
 
-        let records = vec![record];
-        let outcome = detect_owned_outputs(&records, &recipient, 4);
+        let owned = scan_pool()
+            .owned_outputs_in(&[model], ScanKeys::Full(recipient))
+            .await;
 
-        assert_eq!(outcome.owned_total, 1, "exactly one owned output detected");
-        assert_eq!(outcome.total_balance, 42);
-        assert!(!outcome.has_more);
-
-        let view = outcome.transactions.first().expect("transaction returned");
+        assert_eq!(owned.len(), 1, "exactly one owned output detected");
+        let view = owned.first().expect("transaction returned");
         assert_eq!(view.amount, 42);
         assert_eq!(view.sender, "sender_alpha");
         assert_eq!(view.memo.as_ref().unwrap()["note"], "hello");
     }
 
-    #[test]
-    fn detect_owned_encrypted_outputs_decrypts_payload() {
+    #[tokio::test]
+    async fn detect_owned_encrypted_outputs_decrypts_payload() {
         let recipient = StealthKeyPair::generate();
         let (address, _) = StealthKeyPair::generate_stealth_address(
             &recipient.view_keypair.public,
@@ -410,29 +591,118 @@ mod tests {
             .encrypt_payload(&payload)
             .expect("encryption succeeds");
 
-        let record = StealthOutputRecord {
-            tx_id: "tx_encrypted".to_string(),
+        let model = stealth_output::Model {
             sender: "sender_beta".to_string(),
-            fee: payload.fee,
-            timestamp: Utc::now(),
-            address: address_record(&address),
-            kind: StoredOutputKind::Encrypted {
-                memo: StoredEncryptedMemo {
-                    ciphertext: encrypted.ciphertext.clone(),
-                    nonce: encrypted.nonce,
-                    message_number: encrypted.message_number,
-                },
-            },
+            fee: payload.fee as i64,
+            encrypted_memo_ciphertext: Some(encrypted.ciphertext.clone()),
+            encrypted_memo_nonce: Some(encrypted.nonce.to_vec()),
+            encrypted_memo_message_number: Some(encrypted.message_number as i32),
+            ..output_model("tx_encrypted", &address)
         };
 
-        let records = vec![record];
-        let outcome = detect_owned_outputs(&records, &recipient, 4);
+        let owned = scan_pool()
+            .owned_outputs_in(&[model], ScanKeys::Full(recipient))
+            .await;
 
-        assert_eq!(outcome.owned_total, 1);
-        assert_eq!(outcome.total_balance, payload.amount);
-
-        let view = outcome.transactions.first().expect("transaction returned");
+        assert_eq!(owned.len(), 1);
+        let view = owned.first().expect("transaction returned");
         assert_eq!(view.amount, payload.amount);
         assert_eq!(view.memo.as_ref().unwrap()["note"], "secret");
     }
+
+    #[tokio::test]
+    async fn view_only_keys_detect_without_spend_secret() {
+        let recipient = StealthKeyPair::generate();
+        let stranger = StealthKeyPair::generate();
+        let (address, _) = StealthKeyPair::generate_stealth_address(
+            &recipient.view_keypair.public,
+            &recipient.spend_keypair.public,
+        );
+        let model = stealth_output::Model {
+            sender: "sender_gamma".to_string(),
+            amount: Some(9),
+            memo_plaintext: Some("{\"note\":\"watch\"}".to_string()),
+            ..output_model("tx_view_only", &address)
+        };
+        let models = vec![model];
+        let pool = scan_pool();
+
+        let owned = pool
+            .owned_outputs_in(&models, ScanKeys::ViewOnly(recipient.view_only()))
+            .await;
+        assert_eq!(owned.len(), 1);
+        let view = owned.first().expect("transaction returned");
+        assert_eq!(view.amount, 9);
+        assert_eq!(view.memo.as_ref().unwrap()["note"], "watch");
+
+        let owned = pool
+            .owned_outputs_in(&models, ScanKeys::ViewOnly(stranger.view_only()))
+            .await;
+        assert!(owned.is_empty());
+    }
+
+    #[tokio::test]
+    async fn pool_detection_preserves_order_and_honours_cancel() {
+        let recipient = StealthKeyPair::generate();
+        let models = (0..32)
+            .map(|index| {
+                let (address, _) = StealthKeyPair::generate_stealth_address(
+                    &recipient.view_keypair.public,
+                    &recipient.spend_keypair.public,
+                );
+                stealth_output::Model {
+                    amount: Some(index),
+                    ..output_model(&format!("tx_{index:02}"), &address)
+                }
+            })
+            .collect::<Vec<_>>();
+        let pool = ScanPool::new(&StealthScanConfig {
+            worker_threads: Some(4),
+            ..StealthScanConfig::default()
+        })
+        .expect("pool");
+
+        let owned = pool
+            .owned_outputs_in(&models, ScanKeys::ViewOnly(recipient.view_only()))
+            .await;
+        let ids = owned
+            .iter()
+            .map(|view| view.transaction_id.clone())
+            .collect::<Vec<_>>();
+        let expected = (0..32)
+            .map(|index| format!("tx_{index:02}"))
+            .collect::<Vec<_>>();
+        assert_eq!(ids, expected);
+
+        let mut outcome = ScanOutcome::empty();
+        outcome.absorb(owned, 8);
+        assert_eq!(outcome.owned_total, 32);
+        assert_eq!(outcome.total_balance, (0..32).sum::<u64>());
+        assert!(outcome.has_more);
+
+        let cancel = CancelOnDrop::new();
+        let flag = cancel.flag();
+        drop(cancel);
+        assert!(flag.load(AtomicOrdering::Relaxed));
+        let keys = Arc::new(ScanKeys::ViewOnly(recipient.view_only()));
+        assert!(
+            pool.detect(convert_models(&models), keys, flag)
+                .await
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn scan_slots_are_bounded() {
+        let pool = ScanPool::new(&StealthScanConfig {
+            worker_threads: Some(1),
+            max_concurrent_scans: 1,
+            ..StealthScanConfig::default()
+        })
+        .expect("pool");
+        let permit = pool.try_acquire().expect("first slot");
+        assert!(pool.try_acquire().is_none());
+        drop(permit);
+        assert!(pool.try_acquire().is_some());
+    }
 }
@@ -0,0 +1,369 @@
+use std::convert::TryFrom;
+
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+
+use crate::entities::stealth_output;
+use crate::models::privacy::{CompactEncryptedMemoView, CompactStealthOutputView};
+
+/// Outputs fetched per database round trip while streaming.
+pub const STEALTH_STREAM_PAGE_SIZE: u64 = 1_000;
+/// Widest block range a single stream may cover; clients resume with a
+/// cursor or a later `from_block` for more.
+pub const MAX_STEALTH_STREAM_BLOCK_RANGE: u64 = 100_000;
+
+/// Leading bytes of a binary stream, versioning the frame layout below.
+pub const BINARY_STREAM_MAGIC: &[u8; 4] = b"CSO\x01";
+
+const FLAG_AMOUNT: u8 = 0b001;
+const FLAG_PLAINTEXT_MEMO: u8 = 0b010;
+const FLAG_ENCRYPTED_MEMO: u8 = 0b100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamEncoding {
+    Ndjson,
+    Binary,
+}
+
+impl StreamEncoding {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "ndjson" | "jsonl" => Some(StreamEncoding::Ndjson),
+            "binary" | "bin" => Some(StreamEncoding::Binary),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            StreamEncoding::Ndjson => "application/x-ndjson",
+            StreamEncoding::Binary => "application/octet-stream",
+        }
+    }
+}
+
+/// Position of an output in the stream, which is ordered by block, then
+/// transaction id, then output index. Encoded as `block:index:tx_id` so the
+/// free-form transaction id comes last.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OutputCursor {
+    pub block_number: u64,
+    pub tx_id: String,
+    pub output_index: u32,
+}
+
+impl OutputCursor {
+    pub fn encode(&self) -> String {
+        format!("{}:{}:{}", self.block_number, self.output_index, self.tx_id)
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.splitn(3, ':');
+        let block_number = parts.next()?.parse::<u64>().ok()?;
+        let output_index = parts.next()?.parse::<u32>().ok()?;
+        let tx_id = parts.next()?;
+        if tx_id.is_empty() {
+            return None;
+        }
+        Some(Self {
+            block_number,
+            tx_id: tx_id.to_string(),
+            output_index,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactEncryptedMemo {
+    pub ciphertext: Vec<u8>,
+    pub nonce: Vec<u8>,
+    pub message_number: u32,
+}
+
+/// The public fields of a stealth output a wallet needs to run
+/// `scan_for_transactions` locally. Plaintext outputs carry their amount
+/// and memo, encrypted ones their memo envelope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactStealthOutput {
+    pub cursor: OutputCursor,
+    pub stealth_public_key: Vec<u8>,
+    pub tx_public_key: Vec<u8>,
+    pub commitment: Vec<u8>,
+    pub amount: Option<u64>,
+    pub memo: Option<String>,
+    pub encrypted_memo: Option<CompactEncryptedMemo>,
+}
+
+impl CompactStealthOutput {
+    fn from_model(model: stealth_output::Model) -> Option<Self> {
+        let cursor = OutputCursor {
+            block_number: u64::try_from(model.block_number).ok()?,
+            tx_id: model.tx_id,
+            output_index: u32::try_from(model.output_index).ok()?,
+        };
+        let encrypted_memo = match (
+            model.encrypted_memo_ciphertext,
+            model.encrypted_memo_nonce,
+            model.encrypted_memo_message_number,
+        ) {
+            (Some(ciphertext), Some(nonce), Some(message_number)) => Some(CompactEncryptedMemo {
+                ciphertext,
+                nonce,
+                message_number: u32::try_from(message_number).ok()?,
+            }),
+            _ => None,
+        };
+        Some(Self {
+            cursor,
+            stealth_public_key: model.stealth_public_key,
+            tx_public_key: model.tx_public_key,
+            commitment: model.commitment,
+            amount: model.amount.and_then(|amount| u64::try_from(amount).ok()),
+            memo: model.memo_plaintext,
+            encrypted_memo,
+        })
+    }
+
+    pub fn view(&self) -> CompactStealthOutputView {
+        CompactStealthOutputView {
+            cursor: self.cursor.encode(),
+            block_number: self.cursor.block_number,
+            tx_id: self.cursor.tx_id.clone(),
+            output_index: self.cursor.output_index,
+            stealth_public_key: hex::encode(&self.stealth_public_key),
+            tx_public_key: hex::encode(&self.tx_public_key),
+            commitment: hex::encode(&self.commitment),
+            amount: self.amount,
+            memo: self.memo.clone(),
+            encrypted_memo: self
+                .encrypted_memo
+                .as_ref()
+                .map(|memo| CompactEncryptedMemoView {
+                    ciphertext: hex::encode(&memo.ciphertext),
+                    nonce: hex::encode(&memo.nonce),
+                    message_number: memo.message_number,
+                }),
+        }
+    }
+
+    /// Appends one binary frame: a big-endian `u32` body length, then
+    ///
+    /// ```text
+    /// u64 block_number | u32 output_index | u16 len, tx_id (UTF-8)
+    /// u16 len, stealth_public_key | u16 len, tx_public_key | u16 len, commitment
+    /// u8 flags
+    /// [flags & 1] u64 amount
+    /// [flags & 2] u32 len, memo (UTF-8)
+    /// [flags & 4] u32 len, ciphertext | u16 len, nonce | u32 message_number
+    /// ```
+    ///
+    /// Returns `None` if a field overflows its length prefix.
+    pub fn encode_frame(&self, out: &mut Vec<u8>) -> Option<()> {
+        let mut body = Vec::with_capacity(128);
+        body.extend_from_slice(&self.cursor.block_number.to_be_bytes());
+        body.extend_from_slice(&self.cursor.output_index.to_be_bytes());
+        put_u16_prefixed(&mut body, self.cursor.tx_id.as_bytes())?;
+        put_u16_prefixed(&mut body, &self.stealth_public_key)?;
+        put_u16_prefixed(&mut body, &self.tx_public_key)?;
+        put_u16_prefixed(&mut body, &self.commitment)?;
+
+        let mut flags = 0u8;
+        if self.amount.is_some() {
+            flags |= FLAG_AMOUNT;
+        }
+        if self.memo.is_some() {
+            flags |= FLAG_PLAINTEXT_MEMO;
+        }
+        if self.encrypted_memo.is_some() {
+            flags |= FLAG_ENCRYPTED_MEMO;
+        }
+        body.push(flags);
+        if let Some(amount) = self.amount {
+            body.extend_from_slice(&amount.to_be_bytes());
+        }
+        if let Some(memo) = &self.memo {
+            put_u32_prefixed(&mut body, memo.as_bytes())?;
+        }
+        if let Some(memo) = &self.encrypted_memo {
+            put_u32_prefixed(&mut body, &memo.ciphertext)?;
+            put_u16_prefixed(&mut body, &memo.nonce)?;
+            body.extend_from_slice(&memo.message_number.to_be_bytes());
+        }
+
+        out.extend_from_slice(&u32::try_from(body.len()).ok()?.to_be_bytes());
+        out.extend_from_slice(&body);
+        Some(())
+    }
+}
+
+fn put_u16_prefixed(out: &mut Vec<u8>, bytes: &[u8]) -> Option<()> {
+    out.extend_from_slice(&u16::try_from(bytes.len()).ok()?.to_be_bytes());
+    out.extend_from_slice(bytes);
+    Some(())
+}
+
+fn put_u32_prefixed(out: &mut Vec<u8>, bytes: &[u8]) -> Option<()> {
+    out.extend_from_slice(&u32::try_from(bytes.len()).ok()?.to_be_bytes());
+    out.extend_from_slice(bytes);
+    Some(())
+}
+
+/// Closing bytes of a complete stream: a zero-length frame, or a final
+/// NDJSON line. A body that ends without it was cut short and should be
+/// resumed from the last cursor received.
+pub fn stream_trailer(encoding: StreamEncoding, to_block: u64) -> Vec<u8> {
+    match encoding {
+        StreamEncoding::Binary => 0u32.to_be_bytes().to_vec(),
+        StreamEncoding::Ndjson => format!(
+            "{}\n",
+            serde_json::json!({ "complete": true, "to_block": to_block })
+        )
+        .into_bytes(),
+    }
+}
+
+/// One page of the stream. `next_cursor` is set while the range may hold
+/// more outputs; it follows the last row read, even if that row was skipped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutputPage {
+    pub outputs: Vec<CompactStealthOutput>,
+    pub next_cursor: Option<OutputCursor>,
+}
+
+/// Outputs of `from_block..=to_block` strictly after `cursor`, at most
+/// `STEALTH_STREAM_PAGE_SIZE` of them. Rows whose numbers do not fit the
+/// stream layout are skipped.
+pub async fn load_output_page<C: ConnectionTrait>(
+    db: &C,
+    from_block: u64,
+    to_block: u64,
+    cursor: Option<&OutputCursor>,
+) -> Result<OutputPage, DbErr> {
+    assert!(from_block <= to_block, "stream range must be ordered");
+    let from = i64::try_from(from_block).unwrap_or(i64::MAX);
+    let to = i64::try_from(to_block).unwrap_or(i64::MAX);
+
+    let mut query = stealth_output::Entity::find()
+        .filter(stealth_output::Column::BlockNumber.gte(from))
+        .filter(stealth_output::Column::BlockNumber.lte(to));
+    if let Some(cursor) = cursor {
+        query = query.filter(after_cursor(cursor));
+    }
+    let models = query
+        .order_by_asc(stealth_output::Column::BlockNumber)
+        .order_by_asc(stealth_output::Column::TxId)
+        .order_by_asc(stealth_output::Column::OutputIndex)
+        .limit(STEALTH_STREAM_PAGE_SIZE)
+        .all(db)
+        .await?;
+
+    let next_cursor = match models.last() {
+        Some(last) if models.len() as u64 == STEALTH_STREAM_PAGE_SIZE => Some(OutputCursor {
+            block_number: u64::try_from(last.block_number).unwrap_or(0),
+            tx_id: last.tx_id.clone(),
+            output_index: u32::try_from(last.output_index).unwrap_or(0),
+        }),
+        _ => None,
+    };
+    Ok(OutputPage {
+        outputs: models
+            .into_iter()
+            .filter_map(CompactStealthOutput::from_model)
+            .collect(),
+        next_cursor,
+    })
+}
+
+pub fn after_cursor(cursor: &OutputCursor) -> Condition {
+    let block = i64::try_from(cursor.block_number).unwrap_or(i64::MAX);
+    let index = i32::try_from(cursor.output_index).unwrap_or(i32::MAX);
+    Condition::any()
+        .add(stealth_output::Column::BlockNumber.gt(block))
+        .add(
+            Condition::all()
+                .add(stealth_output::Column::BlockNumber.eq(block))
+                .add(stealth_output::Column::TxId.gt(cursor.tx_id.as_str())),
+        )
+        .add(
+            Condition::all()
+                .add(stealth_output::Column::BlockNumber.eq(block))
+                .add(stealth_output::Column::TxId.eq(cursor.tx_id.as_str()))
+                .add(stealth_output::Column::OutputIndex.gt(index)),
+        )
+}
+
+/// Cache key of one page. Pages only ever cover indexed blocks, whose
+/// outputs no longer change, so the key needs no block height.
+pub fn page_cache_key(from_block: u64, to_block: u64, cursor: Option<&OutputCursor>) -> String {
+    let cursor = cursor.map(OutputCursor::encode).unwrap_or_default();
+    format!("{from_block}:{to_block}:{cursor}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(encrypted: bool) -> CompactStealthOutput {
+        CompactStealthOutput {
+            cursor: OutputCursor {
+                block_number: 42,
+                tx_id: "tx:with:colons".to_string(),
+                output_index: 3,
+            },
+            stealth_public_key: vec![0xaa; 32],
+            tx_public_key: vec![0xbb; 32],
+            commitment: vec![0xcc; 32],
+            amount: (!encrypted).then_some(7),
+            memo: None,
+            encrypted_memo: encrypted.then(|| CompactEncryptedMemo {
+                ciphertext: vec![1, 2, 3],
+                nonce: vec![9; 12],
+                message_number: 5,
+            }),
+        }
+    }
+
+    #[test]
+    fn cursor_round_trips_with_colons_in_tx_id() {
+        let cursor = output(false).cursor;
+        assert_eq!(cursor.encode(), "42:3:tx:with:colons");
+        assert_eq!(OutputCursor::parse(&cursor.encode()), Some(cursor));
+        assert_eq!(OutputCursor::parse("42:3:"), None);
+        assert_eq!(OutputCursor::parse("x:3:tx"), None);
+    }
+
+    #[test]
+    fn binary_frame_is_length_prefixed() {
+        let mut out = Vec::new();
+        output(true).encode_frame(&mut out).expect("encode");
+        let length = u32::from_be_bytes(out[..4].try_into().unwrap()) as usize;
+        assert_eq!(out.len(), 4 + length);
+        assert_eq!(&out[4..12], &42u64.to_be_bytes());
+        assert_eq!(&out[12..16], &3u32.to_be_bytes());
+        assert_eq!(&out[out.len() - 4..], &5u32.to_be_bytes());
+    }
+
+    #[test]
+    fn binary_flags_mark_optional_fields() {
+        let mut plaintext = Vec::new();
+        output(false).encode_frame(&mut plaintext).expect("encode");
+        let mut encrypted = Vec::new();
+        output(true).encode_frame(&mut encrypted).expect("encode");
+        // Shared prefix: length, block, index, tx id, three 32 byte keys.
+        let flags_at = 4 + 8 + 4 + 2 + 14 + 3 * (2 + 32);
+        assert_eq!(plaintext[flags_at], FLAG_AMOUNT);
+        assert_eq!(encrypted[flags_at], FLAG_ENCRYPTED_MEMO);
+    }
+
+    #[test]
+    fn ndjson_trailer_marks_completion() {
+        let trailer = stream_trailer(StreamEncoding::Ndjson, 99);
+        let value: serde_json::Value =
+            serde_json::from_slice(trailer.trim_ascii_end()).expect("json");
+        assert_eq!(value["complete"], true);
+        assert_eq!(value["to_block"], 99);
+        assert_eq!(stream_trailer(StreamEncoding::Binary, 99), vec![0; 4]);
+    }
+}
@@ -1,5 +1,14 @@
 import type { Address, UnixSeconds } from './common.js';
 
+export type ProposalState =
+  | 'pending'
+  | 'active'
+  | 'succeeded'
+  | 'defeated'
+  | 'queued'
+  | 'executed'
+  | 'expired';
+
 export interface ProposalSummary {
   readonly proposal_id: number;
   readonly proposer: Address;
@@ -9,7 +18,7 @@ export interface ProposalSummary {
   readonly votes_for: number;
   readonly votes_against: number;
   readonly votes_abstain: number;
-  readonly state: string;
+  readonly state: ProposalState;
   readonly created_at: UnixSeconds;
 }
 
@@ -21,6 +30,30 @@ export interface VoteView {
   readonly weight: number;
   readonly reason: string | null;
   readonly voted_at: UnixSeconds;
+  /** Voter's power in the proposal's snapshot, when one has been taken. */
+  readonly snapshot_power: number | null;
+  readonly weight_verified: boolean | null;
+}
+
+/** `verified` when the deployed code hash matched the manifest's at startup. */
+export type ActionTargetStatus = "verified" | "unverified" | "unknown";
+
+export interface DecodedArgumentView {
+  readonly name: string;
+  readonly type: string;
+  readonly value: unknown;
+}
+
+export interface ProposalActionView {
+  readonly index: number;
+  readonly target: Address;
+  readonly value: string;
+  readonly calldata: string;
+  readonly target_status: ActionTargetStatus;
+  readonly code_hash: string | null;
+  readonly method: string | null;
+  readonly arguments: readonly DecodedArgumentView[];
+  readonly decode_error: string | null;
 }
 
 export interface ProposalView {
@@ -29,20 +62,59 @@ export interface ProposalView {
   readonly targets: readonly string[];
   readonly values: readonly string[];
   readonly calldatas: readonly string[];
+  readonly actions: readonly ProposalActionView[];
   readonly description: string;
   readonly vote_start: UnixSeconds;
   readonly vote_end: UnixSeconds;
   readonly votes_for: number;
   readonly votes_against: number;
   readonly votes_abstain: number;
-  readonly state: string;
+  readonly state: ProposalState;
   readonly executed_at: UnixSeconds | null;
   readonly created_at: UnixSeconds;
   readonly updated_at: UnixSeconds;
+  readonly snapshot_block: number | null;
   readonly has_voted: boolean | null;
   readonly user_vote: VoteView | null;
 }
 
+export interface ProjectedOutcomeView {
+  readonly votes_for: number;
+  readonly votes_against: number;
+  readonly votes_abstain: number;
+  readonly turnout_pct: number;
+  readonly passing: boolean;
+}
+
+export interface VoteTimelineBucket {
+  readonly hour_start: UnixSeconds;
+  readonly votes_cast: number;
+  readonly weight_for: number;
+  readonly weight_against: number;
+  readonly weight_abstain: number;
+  readonly cumulative_for: number;
+  readonly cumulative_against: number;
+  readonly cumulative_abstain: number;
+}
+
+export interface ProposalAnalyticsView {
+  readonly proposal_id: number;
+  readonly state: ProposalState;
+  readonly eligible_voting_power: number;
+  readonly participation: number;
+  readonly turnout_pct: number;
+  readonly quorum_required: number;
+  readonly quorum_reached: boolean;
+  readonly approval_threshold_bps: number;
+  readonly approval_pct: number;
+  readonly approval_reached: boolean;
+  readonly passing: boolean;
+  /** Additional weight needed to flip the current outcome. */
+  readonly margin_to_flip: number;
+  readonly projected: ProjectedOutcomeView;
+  readonly timeline: readonly VoteTimelineBucket[];
+}
+
 export interface VoteHistoryEntry {
   readonly proposal_id: number;
   readonly support: number;
@@ -58,11 +130,43 @@ export interface DelegationView {
   readonly delegated_at: UnixSeconds;
 }
 
+export type DelegationEventKind = "delegate" | "increase" | "undelegate" | "redelegate";
+
+export interface DelegationEventView {
+  readonly delegator: Address;
+  readonly delegatee: Address;
+  readonly previous_delegatee: Address | null;
+  readonly event_type: DelegationEventKind;
+  readonly amount: number;
+  readonly block_number: number;
+  readonly tx_id: string;
+  readonly event_index: number;
+  readonly occurred_at: UnixSeconds;
+}
+
 export interface VotingPowerView {
   readonly address: Address;
   readonly voting_power: number;
+  readonly staked_power: number;
   readonly delegated_power: number;
+  /** Same as `voting_power`; kept for clients reading the older field. */
   readonly total_power: number;
+  readonly snapshot_block: number | null;
+}
+
+export type DelegateRanking = "delegation" | "participation" | "proposals";
+
+export interface DelegateView {
+  readonly rank: number;
+  readonly address: Address;
+  readonly identity_id: string | null;
+  readonly display_name: string | null;
+  readonly received_delegation: number;
+  readonly delegator_count: number;
+  readonly votes_cast: number;
+  readonly participation_rate: number;
+  readonly proposals_authored: number;
+  readonly last_vote_at: UnixSeconds | null;
 }
 
 export interface GovernanceStatsView {
@@ -121,3 +225,21 @@ export interface DelegateResponse {
   readonly amount: number;
   readonly delegation: DelegationView;
 }
+
+export type GovernanceEventKind =
+  | "proposal_created"
+  | "voting_opened"
+  | "voting_closing_soon"
+  | "proposal_passed"
+  | "proposal_failed"
+  | "proposal_executed";
+
+export interface GovernanceNotificationView {
+  readonly proposal_id: number;
+  readonly kind: GovernanceEventKind;
+  readonly proposer: Address;
+  readonly state: ProposalState;
+  readonly vote_end: UnixSeconds;
+  readonly block_number: number;
+  readonly emitted_at: UnixSeconds;
+}
@@ -11,6 +11,28 @@ export interface IdentityProfileView {
   readonly updated_at: UnixSeconds;
   readonly last_synced_block: number;
   readonly profile_version: number;
+  /** Another identity's display name is confusable with this one. */
+  readonly name_collision: boolean;
+}
+
+export interface IdentityBatchRequest {
+  /** At most 100 ids and addresses combined. */
+  readonly identity_ids?: readonly string[];
+  readonly wallet_addresses?: readonly string[];
+}
+
+/** Profiles in request order; unknown ids and unowned addresses are omitted. */
+export interface IdentityBatchView {
+  readonly profiles: readonly IdentityProfileView[];
+  /** Wallet address to the identity that publicly owns it. */
+  readonly wallet_owners: { readonly [address: string]: string };
+}
+
+/** Response to `PUT /identity/avatars/{hash}` with the raw image as body. */
+export interface AvatarUploadView {
+  readonly avatar_hash: string;
+  readonly content_type: 'image/png' | 'image/jpeg' | 'image/gif' | 'image/webp';
+  readonly size_bytes: number;
 }
 
 export interface WalletLinkView {
@@ -22,6 +44,19 @@ export interface WalletLinkView {
   readonly last_synced_block: number;
 }
 
+/** Identity that owns a wallet through a verified link. */
+export interface WalletOwnerView {
+  readonly wallet_address: string;
+  readonly identity_id: string;
+  readonly display_name: string | null;
+  readonly link_type: string;
+  readonly verified_at: UnixSeconds;
+}
+
+export interface WalletOwnerBatchRequest {
+  readonly addresses: readonly string[];
+}
+
 export interface IdentitySearchResult {
   readonly identity_id: string;
   readonly display_name: string | null;
@@ -35,10 +70,22 @@ export interface IdentitySearchResponse {
   readonly results: readonly IdentitySearchResult[];
 }
 
+export interface ConfusableNameResponse {
+  readonly name: string;
+  /** UTS #39 skeleton shared by every match. */
+  readonly skeleton: string;
+  /** Oldest identity first. */
+  readonly matches: readonly IdentitySearchResult[];
+}
+
 export interface WalletVerificationRequest {
   readonly wallet_address: string;
   /** Optional proof signature (hex). */
   readonly signature: string | null;
+  /** Wallet public key (hex); it must derive `wallet_address`. */
+  readonly public_key: string;
+  /** Inferred from the key length when omitted. */
+  readonly signature_scheme?: string | null;
 }
 
 export interface WalletVerificationResponse {
@@ -46,8 +93,163 @@ export interface WalletVerificationResponse {
   readonly wallet_address: string;
   readonly linked: boolean;
   readonly verified: boolean;
+  /** Scheme the proof was checked with, e.g. `ed25519` or `dilithium3`. */
+  readonly signature_scheme: string | null;
   readonly proof_signature: string | null;
   readonly verified_at: UnixSeconds | null;
   readonly last_synced_block: number | null;
+  /** Why verification failed; null when verified. */
   readonly reason: string | null;
 }
+
+export interface WalletChallengeRequest {
+  readonly wallet_address: string;
+  /** Defaults to `main`. */
+  readonly link_type?: string | null;
+}
+
+export interface WalletChallengeResponse {
+  readonly identity_id: string;
+  readonly wallet_address: string;
+  readonly link_type: string;
+  readonly nonce: string;
+  /** Message the wallet signs to redeem the challenge. */
+  readonly challenge: string;
+  /** Message the wallet signs as its registry link proof. */
+  readonly link_message: string;
+  readonly expires_at: UnixSeconds;
+}
+
+export interface WalletLinkSubmission {
+  readonly nonce: string;
+  /** Signature over `challenge` (hex or base64). */
+  readonly challenge_signature: string;
+  /** Signature over `link_message` (hex or base64). */
+  readonly proof_signature: string;
+  /** Wallet public key (hex); it must derive the challenged address. */
+  readonly public_key: string;
+  /** Inferred from the key length when omitted. */
+  readonly signature_scheme?: string | null;
+}
+
+export interface WalletLinkProofResponse {
+  readonly identity_id: string;
+  readonly wallet_address: string;
+  readonly link_type: string;
+  readonly proof_signature: string;
+  readonly signature_scheme: string;
+  readonly link_message: string;
+}
+
+export interface FollowEntryView {
+  readonly identity_id: string;
+  readonly display_name: string | null;
+  readonly followed_at: UnixSeconds;
+}
+
+/** One page of followers or followees, newest first. */
+export interface FollowListView {
+  readonly identity_id: string;
+  /** Every edge, including entries hidden from the caller. */
+  readonly total: number;
+  readonly limit: number;
+  readonly offset: number;
+  /** Identities hidden from the caller are left out. */
+  readonly entries: readonly FollowEntryView[];
+}
+
+/** Requires both identities to be visible to the caller. */
+export interface FollowRelationView {
+  readonly identity_id: string;
+  readonly other_id: string;
+  /** `identity_id` follows `other_id`. */
+  readonly follows: boolean;
+  /** `other_id` follows `identity_id`. */
+  readonly followed_by: boolean;
+  readonly mutual: boolean;
+}
+
+export type ActivityKind =
+  | 'transfer'
+  | 'contract_deployment'
+  | 'transaction'
+  | 'vote'
+  | 'delegation'
+  | 'faucet_drip'
+  | 'profile_update';
+
+export interface ActivityFeedItemView {
+  readonly kind: ActivityKind;
+  readonly identity_id: string;
+  readonly wallet_address: string | null;
+  readonly counterparty: string | null;
+  readonly amount: number | null;
+  readonly proposal_id: number | null;
+  /** Vote support, delegation event type, transaction type or profile version. */
+  readonly detail: string | null;
+  readonly tx_id: string | null;
+  readonly block_number: number | null;
+  readonly occurred_at: UnixSeconds;
+}
+
+export interface ActivityFeedView {
+  readonly items: readonly ActivityFeedItemView[];
+  /** Pass back as `cursor` for the next page; null on the last page. */
+  readonly next_cursor: string | null;
+}
+
+export type ProfileHistoryField = 'display_name' | 'avatar_hash' | 'bio' | 'stats_visibility';
+
+export interface ProfileHistoryEntryView {
+  readonly profile_version: number;
+  readonly last_synced_block: number;
+  readonly updated_at: UnixSeconds;
+  readonly display_name: string | null;
+  readonly avatar_hash: string | null;
+  readonly bio: string | null;
+  readonly stats_visibility: string;
+  /** Fields that differ from the previous recorded version. */
+  readonly changed_fields: readonly ProfileHistoryField[];
+  /** Null when the caller may not see this identity's wallets. */
+  readonly wallets_added: readonly string[] | null;
+  readonly wallets_removed: readonly string[] | null;
+}
+
+/** One page of profile history, newest version first. */
+export interface ProfileHistoryView {
+  readonly identity_id: string;
+  readonly total: number;
+  readonly limit: number;
+  readonly offset: number;
+  readonly entries: readonly ProfileHistoryEntryView[];
+}
+
+export interface WalletStatsView {
+  readonly wallet_address: string;
+  /** Null when the node could not report the balance. */
+  readonly balance: number | null;
+  readonly transactions_sent: number;
+  readonly transactions_received: number;
+}
+
+/** Activity summed over every verified wallet of an identity. */
+export interface IdentityStatsView {
+  readonly identity_id: string;
+  readonly wallet_count: number;
+  readonly proposals_submitted: number;
+  readonly votes_cast: number;
+  /** Distinct proposals any wallet voted on. */
+  readonly proposals_voted: number;
+  readonly participation_rate: number;
+  /** Delegations between the identity's own wallets are excluded. */
+  readonly delegated_in: number;
+  readonly delegated_out: number;
+  readonly net_voting_power: number;
+  /** Null when any wallet balance is unavailable. */
+  readonly total_balance: number | null;
+  readonly transactions_sent: number;
+  readonly transactions_received: number;
+  readonly first_activity_at: UnixSeconds | null;
+  readonly last_activity_at: UnixSeconds | null;
+  readonly wallets: readonly WalletStatsView[];
+}
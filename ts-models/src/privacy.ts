@@ -23,8 +23,16 @@ export interface StealthKeyBundlePayload {
   readonly spend_keypair: StealthKeyComponentPayload;
 }
 
+export interface StealthViewKeyPayload {
+  readonly view_secret: string;
+  readonly spend_public_key: string;
+}
+
+/** Exactly one of `view_keys` and `stealth_keys` must be set. */
 export interface StealthScanRequestPayload {
-  readonly stealth_keys: StealthKeyBundlePayload;
+  readonly view_keys: StealthViewKeyPayload | null;
+  /** @deprecated Sends the spend secret; use `view_keys`. */
+  readonly stealth_keys: StealthKeyBundlePayload | null;
   readonly from_block: number | null;
   readonly to_block: number | null;
   readonly limit: number | null;
@@ -79,3 +87,64 @@ export interface StealthTransferResponsePayload {
   readonly tx_hash: string;
   readonly status: string;
 }
+
+export interface CompactEncryptedMemoView {
+  readonly ciphertext: HexString;
+  readonly nonce: HexString;
+  readonly message_number: number;
+}
+
+/** One line of the NDJSON stealth output stream. */
+export interface CompactStealthOutputView {
+  /** Pass as `cursor` to resume the stream after this output. */
+  readonly cursor: string;
+  readonly block_number: number;
+  readonly tx_id: string;
+  readonly output_index: number;
+  readonly stealth_public_key: HexString;
+  readonly tx_public_key: HexString;
+  readonly commitment: HexString;
+  readonly amount: number | null;
+  readonly memo: string | null;
+  readonly encrypted_memo: CompactEncryptedMemoView | null;
+}
+
+/** Final NDJSON line of a stream that reached `to_block`. */
+export interface CompactStealthStreamTrailer {
+  readonly complete: true;
+  readonly to_block: number;
+}
+
+export interface StealthRegistrationRequestPayload {
+  readonly view_keys: StealthViewKeyPayload;
+  readonly from_block: number | null;
+  readonly ttl_seconds: number | null;
+}
+
+/** `access_token` is only returned at registration. */
+export interface StealthRegistrationView {
+  readonly registration_id: string;
+  readonly access_token: string;
+  readonly start_block: number;
+  readonly expires_at: number;
+}
+
+export interface StealthInboxEntryView {
+  readonly cursor: string;
+  readonly detected_at: number;
+  readonly transaction: OwnedStealthTransactionView;
+}
+
+export interface StealthInboxView {
+  readonly registration_id: string;
+  readonly scanned_through_block: number | null;
+  readonly expires_at: number;
+  readonly entries: readonly StealthInboxEntryView[];
+  readonly next_cursor: string | null;
+}
+
+export interface StealthInboxNotificationView {
+  readonly registration_id: string;
+  readonly new_outputs: number;
+  readonly scanned_through_block: number;
+}